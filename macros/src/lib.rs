@@ -1,35 +1,243 @@
 use proc_macro::TokenStream as InterfaceTokenStream;
 use proc_macro2::{Span, TokenStream};
 use quote::{ToTokens, quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::visit_mut::VisitMut;
 use syn::{DeriveInput, parse_quote};
 
-#[proc_macro_derive(RustState, attributes(state_root))]
+/// Names of the generic parameters that are introduced by the generated code.
+/// Using any of them as the name of the type or one of its generic parameters
+/// would silently clash with the generated code.
+const RESERVED_NAMES: &[&str] = &["S", "P", "SAFE", "StateTwo", "ImplFor"];
+
+/// Errors collected while expanding the derive.
+///
+/// Errors are accumulated instead of returned early, so that a single misuse
+/// reports every problem at once and as much code as possible is still
+/// generated. This avoids a flood of confusing follow-up errors at the use
+/// sites of the missing paths.
+#[derive(Default)]
+struct Errors(Option<syn::Error>);
+
+impl Errors {
+    fn push(&mut self, error: syn::Error) {
+        match &mut self.0 {
+            Some(errors) => errors.combine(error),
+            None => self.0 = Some(error),
+        }
+    }
+}
+
+impl ToTokens for Errors {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if let Some(errors) = &self.0 {
+            tokens.extend(errors.to_compile_error());
+        }
+    }
+}
+
+/// Derive paths for every field of a struct.
+///
+/// Attributes:
+/// - `#[state_root]`: Mark the type as the root of the state.
+/// - `#[state(no_selector)]`: On the type or a field. Don't generate `Selector`
+///   implementations and use generic paths provided by `rust_state` instead.
+///   This avoids coherence conflicts with blanket implementations in the same
+///   crate.
+/// - `#[state(reflect)]`: On the type. Implement `Reflect`, so fields can be
+///   looked up by name at runtime. Every field type has to implement `Reflect`
+///   as well.
+#[proc_macro_derive(RustState, attributes(state_root, state))]
 pub fn derive_rust_state(token_stream: InterfaceTokenStream) -> InterfaceTokenStream {
     let DeriveInput {
         ident,
+        vis,
         attrs,
         data,
         generics,
         ..
-    } = syn::parse(token_stream).expect("failed to parse token stream");
+    } = syn::parse_macro_input!(token_stream as DeriveInput);
+
+    let mut errors = Errors::default();
 
-    let is_root = attrs
-        .iter()
-        .filter_map(|attribute| syn::parse::<syn::Ident>(attribute.meta.to_token_stream().into()).ok())
-        .any(|ident| ident.to_string().as_str() == "state_root");
+    let is_root = parse_state_root(&attrs, &mut errors);
+    let options = parse_state_options(&attrs, &mut errors);
+
+    // Lifetime parameters and reserved names make the generated code invalid, so
+    // only report the errors in those cases.
+    if !check_generics(&ident, &generics, &mut errors) {
+        return errors.into_token_stream().into();
+    }
 
-    let root_impl = is_root.then(|| impl_for_root(ident.clone(), generics.clone()));
-    let inner_impl = impl_for_inner(ident, data, generics);
+    // `Self` in field types would refer to the generated path types, so replace
+    // it with the type itself.
+    let mut data = data;
+    ReplaceSelf::new(&ident, &generics).visit_data_mut(&mut data);
+
+    let root_impl = is_root.then(|| impl_for_root(&vis, ident.clone(), generics.clone(), options));
+    let reflect_impl = options.reflect.then(|| impl_reflect(&ident, &data, &generics, &mut errors));
+    let inner_impl = impl_for_inner(&vis, ident, data, generics, options, &mut errors);
 
     quote! {
         #root_impl
 
         #inner_impl
+
+        #reflect_impl
+
+        #errors
     }
     .into()
 }
 
-fn impl_for_root(ident: syn::Ident, generics: syn::Generics) -> TokenStream {
+/// Check if the type is marked with `#[state_root]`, reporting malformed
+/// attributes.
+///
+/// A malformed attribute still marks the type as the root, so the generated
+/// code is usable and only the attribute itself is reported.
+fn parse_state_root(attrs: &[syn::Attribute], errors: &mut Errors) -> bool {
+    let mut is_root = false;
+
+    for attribute in attrs.iter().filter(|attribute| attribute.path().is_ident("state_root")) {
+        if !matches!(attribute.meta, syn::Meta::Path(_)) {
+            errors.push(syn::Error::new_spanned(
+                &attribute.meta,
+                "`state_root` does not take any arguments, use `#[state_root]`",
+            ));
+        }
+
+        if is_root {
+            errors.push(syn::Error::new_spanned(attribute, "duplicate `state_root` attribute"));
+        }
+
+        is_root = true;
+    }
+
+    is_root
+}
+
+/// Options set through `#[state(...)]` attributes on the type or on a field.
+#[derive(Default, Clone, Copy)]
+struct StateOptions {
+    /// Don't generate `Selector` implementations and use the generic paths
+    /// provided by `rust_state` instead.
+    no_selector: bool,
+    /// Implement `Reflect` for the type. Only valid on the type itself.
+    reflect: bool,
+}
+
+fn parse_state_options(attrs: &[syn::Attribute], errors: &mut Errors) -> StateOptions {
+    let mut options = StateOptions::default();
+
+    for attribute in attrs.iter().filter(|attribute| attribute.path().is_ident("state")) {
+        let result = attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("no_selector") {
+                options.no_selector = true;
+                Ok(())
+            } else if meta.path.is_ident("reflect") {
+                options.reflect = true;
+                Ok(())
+            } else {
+                Err(meta.error("unknown `state` option, expected `no_selector` or `reflect`"))
+            }
+        });
+
+        if let Err(error) = result {
+            errors.push(error);
+        }
+    }
+
+    options
+}
+
+/// Check that code can be generated for the type and its generics. Returns
+/// `false` if that is not the case.
+fn check_generics(ident: &syn::Ident, generics: &syn::Generics, errors: &mut Errors) -> bool {
+    let mut is_valid = true;
+
+    // Paths need to be `'static`, so there is no way to generate working code for
+    // types with lifetime parameters.
+    for lifetime in generics.lifetimes() {
+        errors.push(syn::Error::new_spanned(
+            lifetime,
+            "`RustState` cannot be derived for types with lifetime parameters, since paths need to be `'static`",
+        ));
+        is_valid = false;
+    }
+
+    let type_params = generics.type_params().map(|type_param| &type_param.ident);
+    let const_params = generics.const_params().map(|const_param| &const_param.ident);
+
+    for ident in std::iter::once(ident).chain(type_params).chain(const_params) {
+        if RESERVED_NAMES.contains(&ident.to_string().as_str()) {
+            errors.push(syn::Error::new_spanned(
+                ident,
+                format!("`{ident}` is used internally by `RustState` and cannot be used as a name here, please rename it"),
+            ));
+            is_valid = false;
+        }
+    }
+
+    is_valid
+}
+
+/// Replaces `Self` in types and paths with the type the derive is applied to.
+struct ReplaceSelf {
+    self_type: syn::Type,
+}
+
+impl ReplaceSelf {
+    fn new(ident: &syn::Ident, generics: &syn::Generics) -> Self {
+        let (_, type_generics, _) = generics.split_for_impl();
+
+        Self {
+            self_type: parse_quote!(#ident #type_generics),
+        }
+    }
+
+    /// Turn `Self::Item` into `<Type>::Item`.
+    fn replace_prefix(&self, qself: &mut Option<syn::QSelf>, path: &mut syn::Path) {
+        if qself.is_some() || path.segments.len() < 2 || path.segments[0].ident != "Self" {
+            return;
+        }
+
+        let span = path.segments[0].ident.span();
+        path.segments = path.segments.iter().skip(1).cloned().collect();
+        path.leading_colon = Some(syn::Token![::](span));
+        *qself = Some(syn::QSelf {
+            lt_token: syn::Token![<](span),
+            ty: Box::new(self.self_type.clone()),
+            position: 0,
+            as_token: None,
+            gt_token: syn::Token![>](span),
+        });
+    }
+}
+
+impl VisitMut for ReplaceSelf {
+    fn visit_type_mut(&mut self, ty: &mut syn::Type) {
+        if let syn::Type::Path(type_path) = ty {
+            if type_path.qself.is_none() && type_path.path.is_ident("Self") {
+                *ty = self.self_type.clone();
+                return;
+            }
+        }
+
+        syn::visit_mut::visit_type_mut(self, ty);
+    }
+
+    fn visit_type_path_mut(&mut self, type_path: &mut syn::TypePath) {
+        self.replace_prefix(&mut type_path.qself, &mut type_path.path);
+        syn::visit_mut::visit_type_path_mut(self, type_path);
+    }
+
+    fn visit_expr_path_mut(&mut self, expr_path: &mut syn::ExprPath) {
+        self.replace_prefix(&mut expr_path.qself, &mut expr_path.path);
+        syn::visit_mut::visit_expr_path_mut(self, expr_path);
+    }
+}
+
+fn impl_for_root(vis: &syn::Visibility, ident: syn::Ident, generics: syn::Generics, options: StateOptions) -> TokenStream {
     let lifetimes = generics.lifetimes().map(|lifetime| quote!(&#lifetime ())).collect::<Vec<_>>();
     let type_params = generics.type_params().map(|type_param| quote!(#type_param)).collect::<Vec<_>>();
 
@@ -37,11 +245,25 @@ fn impl_for_root(ident: syn::Ident, generics: syn::Generics) -> TokenStream {
 
     let extension_trait_name = syn::Ident::new(&format!("{ident}RootExt"), ident.span());
 
+    if options.no_selector {
+        return quote_spanned! { Span::mixed_site() =>
+            impl #impl_generics rust_state::StateMarker for #ident #type_generics #where_clause {}
+
+            #vis trait #extension_trait_name {
+                fn path() -> impl rust_state::Path<#ident, #ident> + Copy {
+                    rust_state::RootPath::new()
+                }
+            }
+
+            impl #impl_generics #extension_trait_name for #ident #type_generics #where_clause {}
+        };
+    }
+
     quote_spanned! { Span::mixed_site() =>
         impl #impl_generics rust_state::StateMarker for #ident #type_generics #where_clause {}
 
-        pub trait #extension_trait_name {
-            fn path() -> impl rust_state::Path<#ident, #ident> {
+        #vis trait #extension_trait_name {
+            fn path() -> impl rust_state::Path<#ident, #ident> + Copy {
                 struct AnonymousPath #type_generics #where_clause {
                     _marker: std::marker::PhantomData<(#(#lifetimes,)* #(#type_params,)*)>,
                 }
@@ -54,11 +276,17 @@ fn impl_for_root(ident: syn::Ident, generics: syn::Generics) -> TokenStream {
 
                 impl #impl_generics Copy for AnonymousPath #type_generics #where_clause {}
 
-                impl #impl_generics rust_state::Path<#ident, #ident> for AnonymousPath #type_generics #where_clause {
+                impl #impl_generics rust_state::PathRead<#ident, #ident> for AnonymousPath #type_generics #where_clause {
                     fn follow<'a>(&self, state: &'a #ident) -> Option<&'a #ident> {
                         Some(state)
                     }
 
+                    fn key(&self) -> Option<rust_state::PathKey> {
+                        Some(rust_state::PathKey::new::<Self>())
+                    }
+                }
+
+                impl #impl_generics rust_state::PathWrite<#ident, #ident> for AnonymousPath #type_generics #where_clause {
                     fn follow_mut<'a>(&self, state: &'a mut #ident) -> Option<&'a mut #ident> {
                         Some(state)
                     }
@@ -78,7 +306,14 @@ fn impl_for_root(ident: syn::Ident, generics: syn::Generics) -> TokenStream {
     }
 }
 
-fn impl_for_inner(ident: syn::Ident, data: syn::Data, generics: syn::Generics) -> TokenStream {
+fn impl_for_inner(
+    vis: &syn::Visibility,
+    ident: syn::Ident,
+    data: syn::Data,
+    generics: syn::Generics,
+    options: StateOptions,
+    errors: &mut Errors,
+) -> TokenStream {
     let (_impl_generics, type_generics, _where_clause) = generics.split_for_impl();
 
     let lifetimes = generics.lifetimes().map(|lifetime| quote!(&#lifetime ())).collect::<Vec<_>>();
@@ -100,7 +335,11 @@ fn impl_for_inner(ident: syn::Ident, data: syn::Data, generics: syn::Generics) -
 
     let mut clone_generics = struct_generics.clone();
     let clone_where_clause = clone_generics.make_where_clause();
-    clone_where_clause.predicates.push(parse_quote!(P: Copy));
+    clone_where_clause.predicates.push(parse_quote!(P: Clone));
+
+    let mut copy_generics = struct_generics.clone();
+    let copy_where_clause = copy_generics.make_where_clause();
+    copy_where_clause.predicates.push(parse_quote!(P: Copy));
 
     let mut path_generics = generics.clone();
     path_generics.params.push(parse_quote!(S: 'static));
@@ -127,10 +366,16 @@ fn impl_for_inner(ident: syn::Ident, data: syn::Data, generics: syn::Generics) -
             fields @ syn::Fields::Named(_) => {
                 for field in fields.into_iter() {
                     let field_name = field.ident.as_ref().unwrap();
+                    let field_options = parse_state_options(&field.attrs, errors);
                     let field_type = field.ty;
 
+                    if options.no_selector || field_options.no_selector {
+                        extension_trait_methods.push(field_path_method(field_name, &field_type, field_name, &ident_with_generics));
+                        continue;
+                    }
+
                     extension_trait_methods.push(quote_spanned! { Span::mixed_site() =>
-                        fn #field_name(self) -> impl rust_state::Path<StateTwo, #field_type, SAFE> {
+                        fn #field_name(self) -> impl rust_state::Path<StateTwo, #field_type, SAFE> + Copy {
                             pub struct AnonymousPath #struct_creation_generics #struct_where_clause {
                                 path: P,
                                 _marker: std::marker::PhantomData<(S, #(#lifetimes,)* #(#type_params,)*)>,
@@ -139,21 +384,27 @@ fn impl_for_inner(ident: syn::Ident, data: syn::Data, generics: syn::Generics) -
                             impl #struct_impl_generics Clone for AnonymousPath #struct_type_generics #clone_where_clause {
                                 fn clone(&self) -> Self {
                                     Self {
-                                        path: self.path,
+                                        path: self.path.clone(),
                                         _marker: std::marker::PhantomData,
                                     }
                                 }
                             }
 
-                            impl #struct_impl_generics Copy for AnonymousPath #struct_type_generics #clone_where_clause {}
+                            impl #struct_impl_generics Copy for AnonymousPath #struct_type_generics #copy_where_clause {}
 
                             impl #struct_creation_generics !rust_state::AutoImplSelector for AnonymousPath #struct_type_generics #struct_where_clause {}
 
-                            impl #path_impl_generics rust_state::Path<S, #field_type, SAFE> for AnonymousPath #struct_type_generics #path_where_clause {
+                            impl #path_impl_generics rust_state::PathRead<S, #field_type, SAFE> for AnonymousPath #struct_type_generics #path_where_clause {
                                 fn follow<'a>(&self, state: &'a S) -> Option<&'a #field_type> {
                                     Some(&self.path.follow(state)?.#field_name)
                                 }
 
+                                fn key(&self) -> Option<rust_state::PathKey> {
+                                    Some(rust_state::PathKey::new::<Self>().with(self.path.key()?))
+                                }
+                            }
+
+                            impl #path_impl_generics rust_state::PathWrite<S, #field_type, SAFE> for AnonymousPath #struct_type_generics #path_where_clause {
                                 fn follow_mut<'a>(&self, state: &'a mut S) -> Option<&'a mut #field_type> {
                                     Some(&mut self.path.follow_mut(state)?.#field_name)
                                 }
@@ -161,7 +412,7 @@ fn impl_for_inner(ident: syn::Ident, data: syn::Data, generics: syn::Generics) -
 
                             impl #selector_impl_generics rust_state::Selector<S, #field_type, SAFE> for AnonymousPath #struct_type_generics #selector_where_clause {
                                 fn select<'a>(&'a self, state: &'a S) -> Option<&'a #field_type> {
-                                    <Self as rust_state::Path<S, #field_type, SAFE>>::follow(self, state)
+                                    <Self as rust_state::PathRead<S, #field_type, SAFE>>::follow(self, state)
                                 }
                             }
 
@@ -176,9 +427,15 @@ fn impl_for_inner(ident: syn::Ident, data: syn::Data, generics: syn::Generics) -
                     let field_type = field.ty;
 
                     let field_index = syn::LitInt::new(&index.to_string(), Span::call_site());
+                    let field_options = parse_state_options(&field.attrs, errors);
+
+                    if options.no_selector || field_options.no_selector {
+                        extension_trait_methods.push(field_path_method(&field_name, &field_type, &field_index, &ident_with_generics));
+                        continue;
+                    }
 
                     extension_trait_methods.push(quote_spanned! { Span::mixed_site() =>
-                        fn #field_name(self) -> impl rust_state::Path<StateTwo, #field_type, SAFE> {
+                        fn #field_name(self) -> impl rust_state::Path<StateTwo, #field_type, SAFE> + Copy {
                             pub struct AnonymousPath #struct_creation_generics #struct_where_clause {
                                 path: P,
                                 _marker: std::marker::PhantomData<(S, #(#lifetimes,)* #(#type_params,)*)>,
@@ -187,21 +444,27 @@ fn impl_for_inner(ident: syn::Ident, data: syn::Data, generics: syn::Generics) -
                             impl #struct_impl_generics Clone for AnonymousPath #struct_type_generics #clone_where_clause {
                                 fn clone(&self) -> Self {
                                     Self {
-                                        path: self.path,
+                                        path: self.path.clone(),
                                         _marker: std::marker::PhantomData,
                                     }
                                 }
                             }
 
-                            impl #struct_impl_generics Copy for AnonymousPath #struct_type_generics #clone_where_clause {}
+                            impl #struct_impl_generics Copy for AnonymousPath #struct_type_generics #copy_where_clause {}
 
                             impl #struct_creation_generics !rust_state::AutoImplSelector for AnonymousPath #struct_type_generics #struct_where_clause {}
 
-                            impl #path_impl_generics rust_state::Path<S, #field_type, SAFE> for AnonymousPath #struct_type_generics #path_where_clause {
+                            impl #path_impl_generics rust_state::PathRead<S, #field_type, SAFE> for AnonymousPath #struct_type_generics #path_where_clause {
                                 fn follow<'a>(&self, state: &'a S) -> Option<&'a #field_type> {
                                     Some(&self.path.follow(state)?.#field_index)
                                 }
 
+                                fn key(&self) -> Option<rust_state::PathKey> {
+                                    Some(rust_state::PathKey::new::<Self>().with(self.path.key()?))
+                                }
+                            }
+
+                            impl #path_impl_generics rust_state::PathWrite<S, #field_type, SAFE> for AnonymousPath #struct_type_generics #path_where_clause {
                                 fn follow_mut<'a>(&self, state: &'a mut S) -> Option<&'a mut #field_type> {
                                     Some(&mut self.path.follow_mut(state)?.#field_index)
                                 }
@@ -209,7 +472,7 @@ fn impl_for_inner(ident: syn::Ident, data: syn::Data, generics: syn::Generics) -
 
                             impl #selector_impl_generics rust_state::Selector<S, #field_type, SAFE> for AnonymousPath #struct_type_generics #selector_where_clause {
                                 fn select<'a>(&'a self, state: &'a S) -> Option<&'a #field_type> {
-                                    <Self as rust_state::Path<S, #field_type, SAFE>>::follow(self, state)
+                                    <Self as rust_state::PathRead<S, #field_type, SAFE>>::follow(self, state)
                                 }
                             }
 
@@ -221,15 +484,22 @@ fn impl_for_inner(ident: syn::Ident, data: syn::Data, generics: syn::Generics) -
 
             syn::Fields::Unit => {}
         },
-        syn::Data::Enum(_) => {
-            // extension_trait_methods.push(quote_spanned! { Span::mixed_site()
-            // => });
+        // Still generate the (empty) extension trait for enums and unions, so the
+        // only error is the one below.
+        syn::Data::Enum(data_enum) => {
+            errors.push(syn::Error::new(
+                data_enum.enum_token.span(),
+                "`RustState` cannot be derived for enums, use `VariantExt::try_as` on a path to the enum instead",
+            ));
+        }
+        syn::Data::Union(data_union) => {
+            errors.push(syn::Error::new(
+                data_union.union_token.span(),
+                "`RustState` cannot be derived for unions, since reading a field of a union is unsafe",
+            ));
         }
-        syn::Data::Union(_) => todo!(),
     }
 
-    // let turboed_generics = add_turbo_fish(type_generics.to_token_stream());
-
     let mut extension_trait_generics = generics.clone();
     extension_trait_generics.params.push(parse_quote!(StateTwo: 'static));
     extension_trait_generics.params.push(parse_quote!(const SAFE: bool));
@@ -239,14 +509,14 @@ fn impl_for_inner(ident: syn::Ident, data: syn::Data, generics: syn::Generics) -
     let mut extension_trait_implement_generics = generics.clone();
     extension_trait_implement_generics
         .params
-        .push(parse_quote!(ImplFor: rust_state::Path<StateTwo, #ident_with_generics, SAFE>));
+        .push(parse_quote!(ImplFor: rust_state::Path<StateTwo, #ident_with_generics, SAFE> + Copy));
     extension_trait_implement_generics.params.push(parse_quote!(StateTwo: 'static));
     extension_trait_implement_generics.params.push(parse_quote!(const SAFE: bool));
     let (extension_trait_implement_impl_generics, _extension_trait_implement_type_generics, extension_trait_implement_where_clause) =
         extension_trait_implement_generics.split_for_impl();
 
     quote_spanned! { Span::mixed_site() =>
-        pub trait #extension_trait_name #extension_trait_impl_generics: rust_state::Path<StateTwo, #ident_with_generics, SAFE> #extension_trait_where_clause {
+        #vis trait #extension_trait_name #extension_trait_impl_generics: rust_state::Path<StateTwo, #ident_with_generics, SAFE> + Copy #extension_trait_where_clause {
             #(#extension_trait_methods)*
         }
 
@@ -254,3 +524,87 @@ fn impl_for_inner(ident: syn::Ident, data: syn::Data, generics: syn::Generics) -
             {}
     }
 }
+
+/// Generate the `Reflect` implementation for a type marked with
+/// `#[state(reflect)]`.
+///
+/// Fields are looked up by name, or by index for tuple structs. Fields marked
+/// with `#[state(reflect)]` are reported, since the option only applies to
+/// the type.
+fn impl_reflect(ident: &syn::Ident, data: &syn::Data, generics: &syn::Generics, errors: &mut Errors) -> TokenStream {
+    let syn::Data::Struct(data_struct) = data else {
+        errors.push(syn::Error::new_spanned(
+            ident,
+            "`#[state(reflect)]` is only supported for structs",
+        ));
+        return TokenStream::new();
+    };
+
+    let mut generics = generics.clone();
+    let where_clause = generics.make_where_clause();
+    where_clause.predicates.push(parse_quote!(Self: 'static));
+
+    let mut names = Vec::new();
+    let mut members = Vec::new();
+
+    for (index, field) in data_struct.fields.iter().enumerate() {
+        if parse_state_options(&field.attrs, errors).reflect {
+            errors.push(syn::Error::new_spanned(field, "`reflect` can only be used on the type"));
+        }
+
+        let field_type = &field.ty;
+        where_clause.predicates.push(parse_quote!(#field_type: rust_state::Reflect));
+
+        match &field.ident {
+            Some(field_name) => {
+                names.push(field_name.to_string());
+                members.push(quote!(#field_name));
+            }
+            None => {
+                let field_index = syn::Index::from(index);
+                names.push(index.to_string());
+                members.push(quote!(#field_index));
+            }
+        }
+    }
+
+    let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+    quote_spanned! { Span::mixed_site() =>
+        impl #impl_generics rust_state::Reflect for #ident #type_generics #where_clause {
+            fn field(&self, name: &str) -> Option<&dyn rust_state::Reflect> {
+                match name {
+                    #(#names => Some(&self.#members),)*
+                    _ => None,
+                }
+            }
+
+            fn field_mut(&mut self, name: &str) -> Option<&mut dyn rust_state::Reflect> {
+                match name {
+                    #(#names => Some(&mut self.#members),)*
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
+/// Generate a path method for a field marked with `#[state(no_selector)]`,
+/// using the generic `FieldPath` instead of generating a new path type.
+fn field_path_method(
+    method_name: &syn::Ident,
+    field_type: &syn::Type,
+    member: &dyn ToTokens,
+    ident_with_generics: &TokenStream,
+) -> TokenStream {
+    quote_spanned! { Span::mixed_site() =>
+        fn #method_name(self) -> impl rust_state::Path<StateTwo, #field_type, SAFE> + Copy {
+            rust_state::FieldPath::new(
+                self,
+                stringify!(#member),
+                |state: &#ident_with_generics| &state.#member,
+                |state: &mut #ident_with_generics| &mut state.#member,
+            )
+        }
+    }
+}
@@ -18,11 +18,15 @@
 //! let item_path = State::path().items().array_index(1);
 //!
 //! assert_eq!(context.try_get(&item_path), Some(&8));
+//!
+//! let const_item_path = State::path().items().array_index_const::<2>();
+//!
+//! assert_eq!(context.get(&const_item_path), &9);
 //! ```
 
 use std::marker::PhantomData;
 
-use crate::{Path, Selector};
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
 
 /// A path for doing a dynamic lookup into an array.
 ///
@@ -40,12 +44,16 @@ where
     ArrayPath: Path<State, [Item; N], SAFE>,
 {
     fn clone(&self) -> Self {
-        *self
+        Self {
+            array_path: self.array_path.clone(),
+            index: self.index,
+            _marker: PhantomData,
+        }
     }
 }
 
 impl<State, ArrayPath, Item, const N: usize, const SAFE: bool> Copy for ArrayLookup<State, ArrayPath, Item, N, SAFE> where
-    ArrayPath: Path<State, [Item; N], SAFE>
+    ArrayPath: Path<State, [Item; N], SAFE> + Copy
 {
 }
 
@@ -60,7 +68,7 @@ where
     }
 }
 
-impl<State, ArrayPath, Item, const N: usize, const SAFE: bool> Path<State, Item, false> for ArrayLookup<State, ArrayPath, Item, N, SAFE>
+impl<State, ArrayPath, Item, const N: usize, const SAFE: bool> PathRead<State, Item, false> for ArrayLookup<State, ArrayPath, Item, N, SAFE>
 where
     State: 'static,
     ArrayPath: Path<State, [Item; N], SAFE>,
@@ -70,30 +78,134 @@ where
         self.array_path.follow(state)?.get(self.index)
     }
 
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.array_path.key()?).with(self.index))
+    }
+}
+
+impl<State, ArrayPath, Item, const N: usize, const SAFE: bool> PathWrite<State, Item, false>
+    for ArrayLookup<State, ArrayPath, Item, N, SAFE>
+where
+    State: 'static,
+    ArrayPath: Path<State, [Item; N], SAFE>,
+    Item: 'static,
+{
     fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Item> {
         self.array_path.follow_mut(state)?.get_mut(self.index)
     }
 }
 
+/// A path to an index into an array that is checked at compile time.
+///
+/// This type is not accessible outside this module, instead
+/// [`ArrayLookupExt`] can be used to construct it and receive a `impl
+/// Path<State, Item>`.
+struct ArrayConstLookup<State, ArrayPath, Item, const N: usize, const I: usize, const SAFE: bool> {
+    array_path: ArrayPath,
+    _marker: PhantomData<(State, Item)>,
+}
+
+impl<State, ArrayPath, Item, const N: usize, const I: usize, const SAFE: bool> Clone
+    for ArrayConstLookup<State, ArrayPath, Item, N, I, SAFE>
+where
+    ArrayPath: Path<State, [Item; N], SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            array_path: self.array_path.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, ArrayPath, Item, const N: usize, const I: usize, const SAFE: bool> Copy for ArrayConstLookup<State, ArrayPath, Item, N, I, SAFE> where
+    ArrayPath: Path<State, [Item; N], SAFE> + Copy
+{
+}
+
+impl<State, ArrayPath, Item, const N: usize, const I: usize, const SAFE: bool> Selector<State, Item, SAFE>
+    for ArrayConstLookup<State, ArrayPath, Item, N, I, SAFE>
+where
+    State: 'static,
+    ArrayPath: Path<State, [Item; N], SAFE>,
+    Item: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Item> {
+        self.follow(state)
+    }
+}
+
+impl<State, ArrayPath, Item, const N: usize, const I: usize, const SAFE: bool> PathRead<State, Item, SAFE>
+    for ArrayConstLookup<State, ArrayPath, Item, N, I, SAFE>
+where
+    State: 'static,
+    ArrayPath: Path<State, [Item; N], SAFE>,
+    Item: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Item> {
+        self.array_path.follow(state).map(|array| &array[I])
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.array_path.key()?))
+    }
+}
+
+impl<State, ArrayPath, Item, const N: usize, const I: usize, const SAFE: bool> PathWrite<State, Item, SAFE>
+    for ArrayConstLookup<State, ArrayPath, Item, N, I, SAFE>
+where
+    State: 'static,
+    ArrayPath: Path<State, [Item; N], SAFE>,
+    Item: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Item> {
+        self.array_path.follow_mut(state).map(|array| &mut array[I])
+    }
+}
+
 pub trait ArrayLookupExt<State, T, Item, const N: usize, const SAFE: bool>
 where
     State: 'static,
-    Self: Path<State, [Item; N], SAFE>,
+    Self: Path<State, [Item; N], SAFE> + Copy,
     Item: 'static,
 {
-    fn array_index(self, index: usize) -> impl Path<State, Item, false> {
+    fn array_index(self, index: usize) -> impl Path<State, Item, false> + Copy {
         ArrayLookup {
             array_path: self,
             index,
             _marker: PhantomData,
         }
     }
+
+    /// Index into the array with an index that is checked against the length
+    /// of the array at compile time. Since the index is always in bounds, the
+    /// path is safe if the array path is safe.
+    ///
+    /// ```compile_fail
+    /// use rust_state::{ArrayLookupExt, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct State {
+    ///     items: [u32; 3],
+    /// }
+    ///
+    /// let item_path = State::path().items().array_index_const::<3>();
+    /// ```
+    fn array_index_const<const I: usize>(self) -> impl Path<State, Item, SAFE> + Copy {
+        const { assert!(I < N, "array index out of bounds") };
+
+        ArrayConstLookup::<State, Self, Item, N, I, SAFE> {
+            array_path: self,
+            _marker: PhantomData,
+        }
+    }
 }
 
 impl<State, T, Item, const N: usize, const SAFE: bool> ArrayLookupExt<State, T, Item, N, SAFE> for T
 where
     State: 'static,
-    T: Path<State, [Item; N], SAFE>,
+    T: Path<State, [Item; N], SAFE> + Copy,
     Item: 'static,
 {
 }
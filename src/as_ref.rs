@@ -25,34 +25,38 @@
 //!
 //! let path = State::path().inner().path_as_ref();
 //!
-//! assert_eq!(context.get(&path), TestItem { value : 42 });
+//! assert_eq!(context.get(&path), &TestItem { value: 42 });
 //! ```
 
 use std::marker::PhantomData;
 
-use crate::{Path, Selector};
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
 
 /// A path that dereferences a type implementing [`AsRef<T>`] in the state into
 /// a path of `T`.
 ///
 /// This type is not accessible outside this module. Instead,
-/// [`AsRefExt`] is used to construct it and receive an `impl Path<State, T>`.
-struct AsRefPath<State, RefPath, Inner, Target, const SAFE: bool> {
+/// [`AsRefExt`] is used to construct it and receive an `impl PathRead<State,
+/// T>` or `impl Path<State, T>`.
+struct AsRefPath<State, RefPath, Inner, Target: ?Sized, const SAFE: bool> {
     ref_path: RefPath,
     _marker: PhantomData<(State, Inner, Target)>,
 }
 
-impl<State, RefPath, Inner, Target, const SAFE: bool> Clone for AsRefPath<State, RefPath, Inner, Target, SAFE>
+impl<State, RefPath, Inner, Target: ?Sized, const SAFE: bool> Clone for AsRefPath<State, RefPath, Inner, Target, SAFE>
 where
     RefPath: Path<State, Inner, SAFE>,
 {
     fn clone(&self) -> Self {
-        *self
+        Self {
+            ref_path: self.ref_path.clone(),
+            _marker: PhantomData,
+        }
     }
 }
 
-impl<State, RefPath, Inner, Target, const SAFE: bool> Copy for AsRefPath<State, RefPath, Inner, Target, SAFE> where
-    RefPath: Path<State, Inner, SAFE>
+impl<State, RefPath, Inner, Target: ?Sized, const SAFE: bool> Copy for AsRefPath<State, RefPath, Inner, Target, SAFE> where
+    RefPath: Path<State, Inner, SAFE> + Copy
 {
 }
 
@@ -61,24 +65,36 @@ where
     State: 'static,
     RefPath: Path<State, Inner, SAFE>,
     Inner: AsRef<Target> + 'static,
-    Target: 'static,
+    Target: ?Sized + 'static,
 {
     fn select<'a>(&'a self, state: &'a State) -> Option<&'a Target> {
         self.ref_path.follow(state).map(|inner| inner.as_ref())
     }
 }
 
-impl<State, RefPath, Inner, Target, const SAFE: bool> Path<State, Target, SAFE> for AsRefPath<State, RefPath, Inner, Target, SAFE>
+impl<State, RefPath, Inner, Target, const SAFE: bool> PathRead<State, Target, SAFE> for AsRefPath<State, RefPath, Inner, Target, SAFE>
 where
     State: 'static,
     RefPath: Path<State, Inner, SAFE>,
-    Inner: AsRef<Target> + AsMut<Target> + 'static,
-    Target: 'static,
+    Inner: AsRef<Target> + 'static,
+    Target: ?Sized + 'static,
 {
     fn follow<'a>(&self, state: &'a State) -> Option<&'a Target> {
         self.ref_path.follow(state).map(|inner| inner.as_ref())
     }
 
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.ref_path.key()?))
+    }
+}
+
+impl<State, RefPath, Inner, Target, const SAFE: bool> PathWrite<State, Target, SAFE> for AsRefPath<State, RefPath, Inner, Target, SAFE>
+where
+    State: 'static,
+    RefPath: Path<State, Inner, SAFE>,
+    Inner: AsRef<Target> + AsMut<Target> + 'static,
+    Target: ?Sized + 'static,
+{
     fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Target> {
         self.ref_path.follow_mut(state).map(|inner| inner.as_mut())
     }
@@ -87,11 +103,42 @@ where
 pub trait AsRefExt<State, Inner, Target, const SAFE: bool>
 where
     State: 'static,
-    Self: Path<State, Inner, SAFE>,
-    Inner: AsRef<Target> + AsMut<Target> + 'static,
-    Target: 'static,
+    Self: Path<State, Inner, SAFE> + Copy,
+    Inner: AsRef<Target> + 'static,
+    Target: ?Sized + 'static,
 {
-    fn path_as_ref(self) -> impl Path<State, Target, SAFE> {
+    /// Read-only path to the [`AsRef`] target of this path.
+    ///
+    /// Example:
+    /// ```
+    /// use std::rc::Rc;
+    /// use rust_state::{AsRefExt, Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct State {
+    ///     name: Rc<str>,
+    /// }
+    ///
+    /// let context = Context::new(State { name: "Shared".into() });
+    ///
+    /// let name_path = State::path().name().read_as_ref();
+    ///
+    /// assert_eq!(context.get(&name_path), "Shared");
+    /// ```
+    fn read_as_ref(self) -> impl PathRead<State, Target, SAFE> + Copy {
+        AsRefPath {
+            ref_path: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Path to the [`AsRef`] target of this path. Writing requires [`AsMut`]
+    /// as well.
+    fn path_as_ref(self) -> impl Path<State, Target, SAFE> + Copy
+    where
+        Inner: AsMut<Target>,
+    {
         AsRefPath {
             ref_path: self,
             _marker: PhantomData,
@@ -102,8 +149,8 @@ where
 impl<State, T, Inner, Target, const SAFE: bool> AsRefExt<State, Inner, Target, SAFE> for T
 where
     State: 'static,
-    T: Path<State, Inner, SAFE>,
-    Inner: AsRef<Target> + AsMut<Target> + 'static,
-    Target: 'static,
+    T: Path<State, Inner, SAFE> + Copy,
+    Inner: AsRef<Target> + 'static,
+    Target: ?Sized + 'static,
 {
 }
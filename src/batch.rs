@@ -0,0 +1,1162 @@
+//! Module providing the [`Batch`] builder and the [`ChangeBatch`] to group
+//! multiple changes into a single change.
+//!
+//! All changes of a batch are queued as one unit once the batch is committed,
+//! so they are applied together and in order. Dropping a batch without
+//! committing it discards all changes.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, RustState, VecItem};
+//!
+//! struct TestItem {
+//!     id: u32,
+//! }
+//!
+//! impl VecItem for TestItem {
+//!     type Id = u32;
+//!
+//!     fn get_id(&self) -> Self::Id {
+//!         self.id
+//!     }
+//! }
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     items: Vec<TestItem>,
+//!     selected: Option<u32>,
+//! }
+//!
+//! let mut context = Context::new(State {
+//!     items: Vec::new(),
+//!     selected: None,
+//! });
+//!
+//! let items_path = State::path().items();
+//! let selected_path = State::path().selected();
+//!
+//! context.set_record_changes(true);
+//!
+//! context
+//!     .batch()
+//!     .vec_push(items_path, TestItem { id: 10 })
+//!     .update_value(selected_path, Some(10))
+//!     .commit();
+//!
+//! let report = context.apply();
+//!
+//! assert_eq!(context.get(&items_path).len(), 1);
+//! assert_eq!(context.get(&selected_path), &Some(10));
+//!
+//! // Every change of the batch is recorded.
+//! assert!(report.change_set().contains_path(items_path));
+//! assert!(report.change_set().contains_path(selected_path));
+//! ```
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::hash::Hash;
+use std::ops::Range;
+
+#[cfg(feature = "slotmap")]
+use slotmap::{Key, SlotMap};
+
+use crate::change::{self, Callbacks, ChangeResult, PathChange, RecordedChange};
+#[cfg(feature = "sync")]
+use crate::lock::Lock;
+use crate::{Cached, Context, Extensions, Flags, MapItem, VecItem, Versioned};
+
+/// A builder for a group of changes that are queued as a single change.
+///
+/// Created using [`Context::batch`]. The methods mirror the ones of the same
+/// name on the [`Context`].
+#[must_use = "changes of a batch are only queued once it is committed"]
+pub struct Batch<'a, State> {
+    context: &'a Context<State>,
+    changes: Vec<RecordedChange<State>>,
+}
+
+impl<'a, State: 'static> Batch<'a, State> {
+    pub(crate) fn new(context: &'a Context<State>) -> Self {
+        Self {
+            context,
+            changes: Vec::new(),
+        }
+    }
+
+    fn push<F>(mut self, change: PathChange<F>) -> Self
+    where
+        F: FnOnce(&mut State, &mut Callbacks) -> ChangeResult + 'static,
+    {
+        self.changes.push(change.boxed());
+        self
+    }
+
+    /// See [`Context::update_value`].
+    pub fn update_value<Path, Value, const SAFE: bool>(self, path: Path, value: Value) -> Self
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: 'static,
+    {
+        self.push(change::update_value(path, value))
+    }
+
+    /// See [`Context::update_value_with`].
+    pub fn update_value_with<Path, Value, F, const SAFE: bool>(self, path: Path, closure: F) -> Self
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: 'static,
+        F: Fn(&mut Value) + 'static,
+    {
+        self.push(change::update_value_with(path, closure))
+    }
+
+    /// See [`Context::update_value_try`].
+    pub fn update_value_try<Path, Value, F, E, const SAFE: bool>(self, path: Path, closure: F) -> Self
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: 'static,
+        F: FnOnce(&mut Value) -> Result<(), E> + 'static,
+        E: Error + 'static,
+    {
+        self.push(change::update_value_try(path, closure))
+    }
+
+    /// See [`Context::update_root`].
+    pub fn update_root<F>(self, closure: F) -> Self
+    where
+        F: FnOnce(&mut State) + 'static,
+    {
+        self.push(change::update_root(closure))
+    }
+
+    /// See [`Context::update_cell`].
+    pub fn update_cell<Path, Value, F, const SAFE: bool>(self, path: Path, closure: F) -> Self
+    where
+        Path: crate::Path<State, RefCell<Value>, SAFE>,
+        Value: 'static,
+        F: FnOnce(&mut Value) + 'static,
+    {
+        self.push(change::update_cell(path, closure))
+    }
+
+    /// See [`Context::update_locked`].
+    #[cfg(feature = "sync")]
+    pub fn update_locked<Path, Value, F, const SAFE: bool>(self, path: Path, closure: F) -> Self
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: Lock + 'static,
+        F: FnOnce(&mut Value::Target) + 'static,
+    {
+        self.push(change::update_locked(path, closure))
+    }
+
+    /// See [`Context::update_each`].
+    pub fn update_each<Traversal, Value, F>(self, traversal: Traversal, closure: F) -> Self
+    where
+        Traversal: crate::Traversal<State, Value>,
+        Value: ?Sized + 'static,
+        F: FnMut(&mut Value) + 'static,
+    {
+        self.push(change::update_each(traversal, closure))
+    }
+
+    /// See [`Context::update_each_indexed`].
+    pub fn update_each_indexed<Traversal, Key, Value, F>(self, traversal: Traversal, closure: F) -> Self
+    where
+        Traversal: crate::IndexedTraversal<State, Key, Value>,
+        Key: 'static,
+        Value: ?Sized + 'static,
+        F: FnMut(&Key, &mut Value) + 'static,
+    {
+        self.push(change::update_each_indexed(traversal, closure))
+    }
+
+    /// See [`Context::take`].
+    pub fn take<Path, Value, F, const SAFE: bool>(self, path: Path, callback: F) -> Self
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: Default + 'static,
+        F: FnOnce(Value) + 'static,
+    {
+        self.push(change::take(path, callback))
+    }
+
+    /// See [`Context::replace`].
+    pub fn replace<Path, Value, F, const SAFE: bool>(self, path: Path, value: Value, callback: F) -> Self
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: 'static,
+        F: FnOnce(Value) + 'static,
+    {
+        self.push(change::replace(path, value, callback))
+    }
+
+    /// See [`Context::refresh_cache`].
+    pub fn refresh_cache<CachePath, SourcePath, Value, Source, F, const CACHE_SAFE: bool, const SOURCE_SAFE: bool>(
+        self,
+        cache_path: CachePath,
+        source_path: SourcePath,
+        compute: F,
+    ) -> Self
+    where
+        CachePath: crate::Path<State, Cached<Value>, CACHE_SAFE>,
+        SourcePath: crate::Path<State, Versioned<Source>, SOURCE_SAFE>,
+        Value: 'static,
+        Source: 'static,
+        F: FnOnce(&Source) -> Value + 'static,
+    {
+        self.push(change::refresh_cache(cache_path, source_path, compute))
+    }
+
+    /// See [`Context::vec_push`].
+    pub fn vec_push<Path, Value, const SAFE: bool>(self, path: Path, value: Value) -> Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.push(change::vec_push(path, value))
+    }
+
+    /// See [`Context::vec_remove`].
+    pub fn vec_remove<Path, Value, const SAFE: bool>(self, path: Path, id: Value::Id) -> Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.push(change::vec_remove(path, id))
+    }
+
+    /// See [`Context::vec_remove_with`].
+    pub fn vec_remove_with<Path, Value, F, const SAFE: bool>(self, path: Path, id: Value::Id, callback: F) -> Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+        F: FnOnce(Vec<Value>) + 'static,
+    {
+        self.push(change::vec_remove_with(path, id, callback))
+    }
+
+    /// See [`Context::vec_clear`].
+    pub fn vec_clear<Path, Value, const SAFE: bool>(self, path: Path) -> Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.push(change::vec_clear(path))
+    }
+
+    /// See [`Context::vec_sort_by`].
+    pub fn vec_sort_by<Path, Value, F, const SAFE: bool>(self, path: Path, comparator: F) -> Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+        F: FnMut(&Value, &Value) -> Ordering + 'static,
+    {
+        self.push(change::vec_sort_by(path, comparator))
+    }
+
+    /// See [`Context::vec_extend`].
+    pub fn vec_extend<Path, Value, Items, const SAFE: bool>(self, path: Path, items: Items) -> Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+        Items: IntoIterator<Item = Value> + 'static,
+    {
+        self.push(change::vec_extend(path, items))
+    }
+
+    /// See [`Context::vec_retain`].
+    pub fn vec_retain<Path, Value, F, const SAFE: bool>(self, path: Path, predicate: F) -> Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+        F: FnMut(&Value) -> bool + 'static,
+    {
+        self.push(change::vec_retain(path, predicate))
+    }
+
+    /// See [`Context::vec_swap_remove`].
+    pub fn vec_swap_remove<Path, Value, const SAFE: bool>(self, path: Path, id: Value::Id) -> Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.push(change::vec_swap_remove(path, id))
+    }
+
+    /// See [`Context::vec_move`].
+    pub fn vec_move<Path, Value, const SAFE: bool>(self, path: Path, id: Value::Id, new_index: usize) -> Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.push(change::vec_move(path, id, new_index))
+    }
+
+    /// See [`Context::vec_dedup`].
+    pub fn vec_dedup<Path, Value, const SAFE: bool>(self, path: Path) -> Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.push(change::vec_dedup(path))
+    }
+
+    /// See [`Context::vec_truncate`].
+    pub fn vec_truncate<Path, Value, const SAFE: bool>(self, path: Path, len: usize) -> Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.push(change::vec_truncate(path, len))
+    }
+
+    /// See [`Context::vec_pop`].
+    pub fn vec_pop<Path, Value, F, const SAFE: bool>(self, path: Path, callback: F) -> Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+        F: FnOnce(Option<Value>) + 'static,
+    {
+        self.push(change::vec_pop(path, callback))
+    }
+
+    /// See [`Context::vec_push_unique`].
+    pub fn vec_push_unique<Path, Value, const SAFE: bool>(self, path: Path, value: Value) -> Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.push(change::vec_push_unique(path, value))
+    }
+
+    /// See [`Context::string_push_str`].
+    pub fn string_push_str<Path, const SAFE: bool>(self, path: Path, string: impl Into<String>) -> Self
+    where
+        Path: crate::Path<State, String, SAFE>,
+    {
+        self.push(change::string_push_str(path, string.into()))
+    }
+
+    /// See [`Context::string_clear`].
+    pub fn string_clear<Path, const SAFE: bool>(self, path: Path) -> Self
+    where
+        Path: crate::Path<State, String, SAFE>,
+    {
+        self.push(change::string_clear(path))
+    }
+
+    /// See [`Context::string_replace_range`].
+    pub fn string_replace_range<Path, const SAFE: bool>(self, path: Path, range: Range<usize>, replacement: impl Into<String>) -> Self
+    where
+        Path: crate::Path<State, String, SAFE>,
+    {
+        self.push(change::string_replace_range(path, range, replacement.into()))
+    }
+
+    /// See [`Context::flag_set`].
+    pub fn flag_set<Path, Value, const SAFE: bool>(self, path: Path, flag: Value, value: bool) -> Self
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: Flags,
+    {
+        self.push(change::flag_set(path, flag, value))
+    }
+
+    /// See [`Context::flag_toggle`].
+    pub fn flag_toggle<Path, Value, const SAFE: bool>(self, path: Path, flag: Value) -> Self
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: Flags,
+    {
+        self.push(change::flag_toggle(path, flag))
+    }
+
+    /// See [`Context::map_insert`].
+    pub fn map_insert<Path, Value, const SAFE: bool>(self, path: Path, id: Value::Id, value: Value) -> Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+    {
+        self.push(change::map_insert(path, id, value))
+    }
+
+    /// See [`Context::map_insert_default`].
+    pub fn map_insert_default<Path, Value, const SAFE: bool>(self, path: Path, id: Value::Id) -> Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + Default + 'static,
+    {
+        self.push(change::map_insert_default(path, id))
+    }
+
+    /// See [`Context::map_replace`].
+    pub fn map_replace<Path, Value, F, const SAFE: bool>(self, path: Path, id: Value::Id, value: Value, callback: F) -> Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnOnce(Option<Value>) + 'static,
+    {
+        self.push(change::map_replace(path, id, value, callback))
+    }
+
+    /// See [`Context::map_remove`].
+    pub fn map_remove<Path, Value, const SAFE: bool>(self, path: Path, id: Value::Id) -> Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+    {
+        self.push(change::map_remove(path, id))
+    }
+
+    /// See [`Context::map_clear`].
+    pub fn map_clear<Path, Value, const SAFE: bool>(self, path: Path) -> Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+    {
+        self.push(change::map_clear(path))
+    }
+
+    /// See [`Context::map_retain`].
+    pub fn map_retain<Path, Value, F, const SAFE: bool>(self, path: Path, predicate: F) -> Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnMut(&Value::Id, &mut Value) -> bool + 'static,
+    {
+        self.push(change::map_retain(path, predicate))
+    }
+
+    /// See [`Context::map_get_or_insert_with`].
+    pub fn map_get_or_insert_with<Path, Value, F, const SAFE: bool>(self, path: Path, id: Value::Id, create: F) -> Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnOnce() -> Value + 'static,
+    {
+        self.push(change::map_get_or_insert_with(path, id, create))
+    }
+
+    /// See [`Context::map_update`].
+    pub fn map_update<Path, Value, F, const SAFE: bool>(self, path: Path, id: Value::Id, closure: F) -> Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnOnce(&mut Value) + 'static,
+    {
+        self.push(change::map_update(path, id, closure))
+    }
+
+    /// See [`Context::btree_insert`].
+    pub fn btree_insert<Path, Key, Value, const SAFE: bool>(self, path: Path, key: Key, value: Value) -> Self
+    where
+        Path: crate::Path<State, BTreeMap<Key, Value>, SAFE>,
+        Key: Ord + 'static,
+        Value: 'static,
+    {
+        self.push(change::btree_insert(path, key, value))
+    }
+
+    /// See [`Context::btree_remove`].
+    pub fn btree_remove<Path, Key, Value, const SAFE: bool>(self, path: Path, key: Key) -> Self
+    where
+        Path: crate::Path<State, BTreeMap<Key, Value>, SAFE>,
+        Key: Ord + 'static,
+        Value: 'static,
+    {
+        self.push(change::btree_remove(path, key))
+    }
+
+    /// See [`Context::set_insert`].
+    pub fn set_insert<Path, Value, const SAFE: bool>(self, path: Path, value: Value) -> Self
+    where
+        Path: crate::Path<State, HashSet<Value>, SAFE>,
+        Value: Eq + Hash + 'static,
+    {
+        self.push(change::set_insert(path, value))
+    }
+
+    /// See [`Context::set_remove`].
+    pub fn set_remove<Path, Value, const SAFE: bool>(self, path: Path, value: Value) -> Self
+    where
+        Path: crate::Path<State, HashSet<Value>, SAFE>,
+        Value: Eq + Hash + 'static,
+    {
+        self.push(change::set_remove(path, value))
+    }
+
+    /// See [`Context::set_clear`].
+    pub fn set_clear<Path, Value, const SAFE: bool>(self, path: Path) -> Self
+    where
+        Path: crate::Path<State, HashSet<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.push(change::set_clear(path))
+    }
+
+    /// See [`Context::slotmap_insert`].
+    #[cfg(feature = "slotmap")]
+    pub fn slotmap_insert<Path, K, Value, F, const SAFE: bool>(self, path: Path, value: Value, callback: F) -> Self
+    where
+        Path: crate::Path<State, SlotMap<K, Value>, SAFE>,
+        K: Key + 'static,
+        Value: 'static,
+        F: FnOnce(K) + 'static,
+    {
+        self.push(change::slotmap_insert(path, value, callback))
+    }
+
+    /// See [`Context::slotmap_remove`].
+    #[cfg(feature = "slotmap")]
+    pub fn slotmap_remove<Path, K, Value, const SAFE: bool>(self, path: Path, key: K) -> Self
+    where
+        Path: crate::Path<State, SlotMap<K, Value>, SAFE>,
+        K: Key + 'static,
+        Value: 'static,
+    {
+        self.push(change::slotmap_remove(path, key))
+    }
+
+    /// See [`Context::map_vec_push`].
+    pub fn map_vec_push<Path, Key, Value, const SAFE: bool>(self, path: Path, key: Key, value: Value) -> Self
+    where
+        Path: crate::Path<State, HashMap<Key, Vec<Value>>, SAFE>,
+        Key: Eq + Hash + 'static,
+        Value: 'static,
+    {
+        self.push(change::map_vec_push(path, key, value))
+    }
+
+    /// See [`Context::btree_set_insert`].
+    pub fn btree_set_insert<Path, Value, const SAFE: bool>(self, path: Path, value: Value) -> Self
+    where
+        Path: crate::Path<State, BTreeSet<Value>, SAFE>,
+        Value: Ord + 'static,
+    {
+        self.push(change::btree_set_insert(path, value))
+    }
+
+    /// See [`Context::btree_set_remove`].
+    pub fn btree_set_remove<Path, Value, const SAFE: bool>(self, path: Path, value: Value) -> Self
+    where
+        Path: crate::Path<State, BTreeSet<Value>, SAFE>,
+        Value: Ord + 'static,
+    {
+        self.push(change::btree_set_remove(path, value))
+    }
+
+    /// See [`Context::insert_extension_at`].
+    pub fn insert_extension_at<Path, Value, const SAFE: bool>(self, path: Path, value: Value) -> Self
+    where
+        Path: crate::Path<State, Extensions, SAFE>,
+        Value: 'static,
+    {
+        self.push(change::insert_extension(path, value))
+    }
+
+    /// See [`Context::remove_extension_at`].
+    pub fn remove_extension_at<Value, Path, const SAFE: bool>(self, path: Path) -> Self
+    where
+        Path: crate::Path<State, Extensions, SAFE>,
+        Value: 'static,
+    {
+        self.push(change::remove_extension::<State, Value, Path, SAFE>(path))
+    }
+
+    /// Queue all changes of this batch as a single change on the [`Context`].
+    pub fn commit(self) {
+        if !self.changes.is_empty() {
+            self.context.queue_batch(self.changes);
+        }
+    }
+}
+
+/// A change that can be sent to another thread.
+type SendStateChange<State> = Box<dyn FnOnce(&mut State, &mut Callbacks) -> ChangeResult + Send>;
+
+/// A group of changes that can be built without access to a [`Context`].
+///
+/// Unlike [`Batch`], a `ChangeBatch` can be constructed anywhere, stored and
+/// sent to other threads. It is queued on a [`Context`] using
+/// [`Context::enqueue_batch`], where all of its changes are applied as a
+/// single change.
+///
+/// The methods mirror the ones of the same name on the [`Context`].
+///
+/// Example:
+/// ```
+/// use std::thread;
+///
+/// use rust_state::{ChangeBatch, Context, RustState};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct MyState {
+///     lines: Vec<String>,
+/// }
+///
+/// let mut context = Context::new(MyState { lines: Vec::new() });
+/// let lines_path = MyState::path().lines();
+///
+/// let batch = thread::spawn(move || {
+///     let mut batch = ChangeBatch::new();
+///
+///     for index in 0..3 {
+///         batch.vec_push(lines_path, format!("Line {index}"));
+///     }
+///
+///     batch
+/// })
+/// .join()
+/// .unwrap();
+///
+/// context.enqueue_batch(batch);
+/// context.apply();
+///
+/// assert_eq!(context.get(&lines_path).len(), 3);
+/// ```
+pub struct ChangeBatch<State> {
+    changes: Vec<PathChange<SendStateChange<State>>>,
+}
+
+impl<State> Default for ChangeBatch<State> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<State> ChangeBatch<State> {
+    /// Create a new, empty batch.
+    pub fn new() -> Self {
+        Self { changes: Vec::new() }
+    }
+
+    /// Get the number of changes in this batch.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Check if this batch contains no changes.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl<State: 'static> ChangeBatch<State> {
+    fn push<F>(&mut self, change: PathChange<F>) -> &mut Self
+    where
+        F: FnOnce(&mut State, &mut Callbacks) -> ChangeResult + Send + 'static,
+    {
+        self.changes.push(PathChange {
+            record: change.record,
+            apply: Box::new(change.apply),
+            fallible: change.fallible,
+        });
+        self
+    }
+
+    /// See [`Context::update_value`].
+    pub fn update_value<Path, Value, const SAFE: bool>(&mut self, path: Path, value: Value) -> &mut Self
+    where
+        Path: crate::Path<State, Value, SAFE> + Send,
+        Value: Send + 'static,
+    {
+        self.push(change::update_value(path, value))
+    }
+
+    /// See [`Context::update_value_with`].
+    pub fn update_value_with<Path, Value, F, const SAFE: bool>(&mut self, path: Path, closure: F) -> &mut Self
+    where
+        Path: crate::Path<State, Value, SAFE> + Send,
+        Value: 'static,
+        F: Fn(&mut Value) + Send + 'static,
+    {
+        self.push(change::update_value_with(path, closure))
+    }
+
+    /// See [`Context::update_value_try`].
+    pub fn update_value_try<Path, Value, F, E, const SAFE: bool>(&mut self, path: Path, closure: F) -> &mut Self
+    where
+        Path: crate::Path<State, Value, SAFE> + Send,
+        Value: 'static,
+        F: FnOnce(&mut Value) -> Result<(), E> + Send + 'static,
+        E: Error + 'static,
+    {
+        self.push(change::update_value_try(path, closure))
+    }
+
+    /// See [`Context::update_root`].
+    pub fn update_root<F>(&mut self, closure: F) -> &mut Self
+    where
+        F: FnOnce(&mut State) + Send + 'static,
+    {
+        self.push(change::update_root(closure))
+    }
+
+    /// See [`Context::update_cell`].
+    pub fn update_cell<Path, Value, F, const SAFE: bool>(&mut self, path: Path, closure: F) -> &mut Self
+    where
+        Path: crate::Path<State, RefCell<Value>, SAFE> + Send,
+        Value: 'static,
+        F: FnOnce(&mut Value) + Send + 'static,
+    {
+        self.push(change::update_cell(path, closure))
+    }
+
+    /// See [`Context::update_locked`].
+    #[cfg(feature = "sync")]
+    pub fn update_locked<Path, Value, F, const SAFE: bool>(&mut self, path: Path, closure: F) -> &mut Self
+    where
+        Path: crate::Path<State, Value, SAFE> + Send,
+        Value: Lock + 'static,
+        F: FnOnce(&mut Value::Target) + Send + 'static,
+    {
+        self.push(change::update_locked(path, closure))
+    }
+
+    /// See [`Context::update_each`].
+    pub fn update_each<Traversal, Value, F>(&mut self, traversal: Traversal, closure: F) -> &mut Self
+    where
+        Traversal: crate::Traversal<State, Value> + Send,
+        Value: ?Sized + 'static,
+        F: FnMut(&mut Value) + Send + 'static,
+    {
+        self.push(change::update_each(traversal, closure))
+    }
+
+    /// See [`Context::update_each_indexed`].
+    pub fn update_each_indexed<Traversal, Key, Value, F>(&mut self, traversal: Traversal, closure: F) -> &mut Self
+    where
+        Traversal: crate::IndexedTraversal<State, Key, Value> + Send,
+        Key: 'static,
+        Value: ?Sized + 'static,
+        F: FnMut(&Key, &mut Value) + Send + 'static,
+    {
+        self.push(change::update_each_indexed(traversal, closure))
+    }
+
+    /// See [`Context::take`].
+    pub fn take<Path, Value, F, const SAFE: bool>(&mut self, path: Path, callback: F) -> &mut Self
+    where
+        Path: crate::Path<State, Value, SAFE> + Send,
+        Value: Default + 'static,
+        F: FnOnce(Value) + Send + 'static,
+    {
+        self.push(change::take(path, callback))
+    }
+
+    /// See [`Context::replace`].
+    pub fn replace<Path, Value, F, const SAFE: bool>(&mut self, path: Path, value: Value, callback: F) -> &mut Self
+    where
+        Path: crate::Path<State, Value, SAFE> + Send,
+        Value: Send + 'static,
+        F: FnOnce(Value) + Send + 'static,
+    {
+        self.push(change::replace(path, value, callback))
+    }
+
+    /// See [`Context::refresh_cache`].
+    pub fn refresh_cache<CachePath, SourcePath, Value, Source, F, const CACHE_SAFE: bool, const SOURCE_SAFE: bool>(
+        &mut self,
+        cache_path: CachePath,
+        source_path: SourcePath,
+        compute: F,
+    ) -> &mut Self
+    where
+        CachePath: crate::Path<State, Cached<Value>, CACHE_SAFE> + Send,
+        SourcePath: crate::Path<State, Versioned<Source>, SOURCE_SAFE> + Send,
+        Value: 'static,
+        Source: 'static,
+        F: FnOnce(&Source) -> Value + Send + 'static,
+    {
+        self.push(change::refresh_cache(cache_path, source_path, compute))
+    }
+
+    /// See [`Context::vec_push`].
+    pub fn vec_push<Path, Value, const SAFE: bool>(&mut self, path: Path, value: Value) -> &mut Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE> + Send,
+        Value: Send + 'static,
+    {
+        self.push(change::vec_push(path, value))
+    }
+
+    /// See [`Context::vec_remove`].
+    pub fn vec_remove<Path, Value, const SAFE: bool>(&mut self, path: Path, id: Value::Id) -> &mut Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE> + Send,
+        Value: VecItem + 'static,
+        Value::Id: Send,
+    {
+        self.push(change::vec_remove(path, id))
+    }
+
+    /// See [`Context::vec_remove_with`].
+    pub fn vec_remove_with<Path, Value, F, const SAFE: bool>(&mut self, path: Path, id: Value::Id, callback: F) -> &mut Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE> + Send,
+        Value: VecItem + 'static,
+        F: FnOnce(Vec<Value>) + Send + 'static,
+        Value::Id: Send,
+    {
+        self.push(change::vec_remove_with(path, id, callback))
+    }
+
+    /// See [`Context::vec_clear`].
+    pub fn vec_clear<Path, Value, const SAFE: bool>(&mut self, path: Path) -> &mut Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE> + Send,
+        Value: 'static,
+    {
+        self.push(change::vec_clear(path))
+    }
+
+    /// See [`Context::vec_sort_by`].
+    pub fn vec_sort_by<Path, Value, F, const SAFE: bool>(&mut self, path: Path, comparator: F) -> &mut Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE> + Send,
+        Value: 'static,
+        F: FnMut(&Value, &Value) -> Ordering + Send + 'static,
+    {
+        self.push(change::vec_sort_by(path, comparator))
+    }
+
+    /// See [`Context::vec_extend`].
+    pub fn vec_extend<Path, Value, Items, const SAFE: bool>(&mut self, path: Path, items: Items) -> &mut Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE> + Send,
+        Value: 'static,
+        Items: IntoIterator<Item = Value> + Send + 'static,
+    {
+        self.push(change::vec_extend(path, items))
+    }
+
+    /// See [`Context::vec_retain`].
+    pub fn vec_retain<Path, Value, F, const SAFE: bool>(&mut self, path: Path, predicate: F) -> &mut Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE> + Send,
+        Value: 'static,
+        F: FnMut(&Value) -> bool + Send + 'static,
+    {
+        self.push(change::vec_retain(path, predicate))
+    }
+
+    /// See [`Context::vec_swap_remove`].
+    pub fn vec_swap_remove<Path, Value, const SAFE: bool>(&mut self, path: Path, id: Value::Id) -> &mut Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE> + Send,
+        Value: VecItem + 'static,
+        Value::Id: Send,
+    {
+        self.push(change::vec_swap_remove(path, id))
+    }
+
+    /// See [`Context::vec_move`].
+    pub fn vec_move<Path, Value, const SAFE: bool>(&mut self, path: Path, id: Value::Id, new_index: usize) -> &mut Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE> + Send,
+        Value: VecItem + 'static,
+        Value::Id: Send,
+    {
+        self.push(change::vec_move(path, id, new_index))
+    }
+
+    /// See [`Context::vec_dedup`].
+    pub fn vec_dedup<Path, Value, const SAFE: bool>(&mut self, path: Path) -> &mut Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE> + Send,
+        Value: VecItem + 'static,
+    {
+        self.push(change::vec_dedup(path))
+    }
+
+    /// See [`Context::vec_truncate`].
+    pub fn vec_truncate<Path, Value, const SAFE: bool>(&mut self, path: Path, len: usize) -> &mut Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE> + Send,
+        Value: 'static,
+    {
+        self.push(change::vec_truncate(path, len))
+    }
+
+    /// See [`Context::vec_pop`].
+    pub fn vec_pop<Path, Value, F, const SAFE: bool>(&mut self, path: Path, callback: F) -> &mut Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE> + Send,
+        Value: 'static,
+        F: FnOnce(Option<Value>) + Send + 'static,
+    {
+        self.push(change::vec_pop(path, callback))
+    }
+
+    /// See [`Context::vec_push_unique`].
+    pub fn vec_push_unique<Path, Value, const SAFE: bool>(&mut self, path: Path, value: Value) -> &mut Self
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE> + Send,
+        Value: VecItem + Send + 'static,
+    {
+        self.push(change::vec_push_unique(path, value))
+    }
+
+    /// See [`Context::string_push_str`].
+    pub fn string_push_str<Path, const SAFE: bool>(&mut self, path: Path, string: impl Into<String>) -> &mut Self
+    where
+        Path: crate::Path<State, String, SAFE> + Send,
+    {
+        self.push(change::string_push_str(path, string.into()))
+    }
+
+    /// See [`Context::string_clear`].
+    pub fn string_clear<Path, const SAFE: bool>(&mut self, path: Path) -> &mut Self
+    where
+        Path: crate::Path<State, String, SAFE> + Send,
+    {
+        self.push(change::string_clear(path))
+    }
+
+    /// See [`Context::string_replace_range`].
+    pub fn string_replace_range<Path, const SAFE: bool>(
+        &mut self,
+        path: Path,
+        range: Range<usize>,
+        replacement: impl Into<String>,
+    ) -> &mut Self
+    where
+        Path: crate::Path<State, String, SAFE> + Send,
+    {
+        self.push(change::string_replace_range(path, range, replacement.into()))
+    }
+
+    /// See [`Context::flag_set`].
+    pub fn flag_set<Path, Value, const SAFE: bool>(&mut self, path: Path, flag: Value, value: bool) -> &mut Self
+    where
+        Path: crate::Path<State, Value, SAFE> + Send,
+        Value: Flags + Send,
+    {
+        self.push(change::flag_set(path, flag, value))
+    }
+
+    /// See [`Context::flag_toggle`].
+    pub fn flag_toggle<Path, Value, const SAFE: bool>(&mut self, path: Path, flag: Value) -> &mut Self
+    where
+        Path: crate::Path<State, Value, SAFE> + Send,
+        Value: Flags + Send,
+    {
+        self.push(change::flag_toggle(path, flag))
+    }
+
+    /// See [`Context::map_insert`].
+    pub fn map_insert<Path, Value, const SAFE: bool>(&mut self, path: Path, id: Value::Id, value: Value) -> &mut Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE> + Send,
+        Value: MapItem + Send + 'static,
+        Value::Id: Send,
+    {
+        self.push(change::map_insert(path, id, value))
+    }
+
+    /// See [`Context::map_insert_default`].
+    pub fn map_insert_default<Path, Value, const SAFE: bool>(&mut self, path: Path, id: Value::Id) -> &mut Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE> + Send,
+        Value: MapItem + Default + 'static,
+        Value::Id: Send,
+    {
+        self.push(change::map_insert_default(path, id))
+    }
+
+    /// See [`Context::map_replace`].
+    pub fn map_replace<Path, Value, F, const SAFE: bool>(&mut self, path: Path, id: Value::Id, value: Value, callback: F) -> &mut Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE> + Send,
+        Value: MapItem + Send + 'static,
+        F: FnOnce(Option<Value>) + Send + 'static,
+        Value::Id: Send,
+    {
+        self.push(change::map_replace(path, id, value, callback))
+    }
+
+    /// See [`Context::map_remove`].
+    pub fn map_remove<Path, Value, const SAFE: bool>(&mut self, path: Path, id: Value::Id) -> &mut Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE> + Send,
+        Value: MapItem + 'static,
+        Value::Id: Send,
+    {
+        self.push(change::map_remove(path, id))
+    }
+
+    /// See [`Context::map_clear`].
+    pub fn map_clear<Path, Value, const SAFE: bool>(&mut self, path: Path) -> &mut Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE> + Send,
+        Value: MapItem + 'static,
+    {
+        self.push(change::map_clear(path))
+    }
+
+    /// See [`Context::map_retain`].
+    pub fn map_retain<Path, Value, F, const SAFE: bool>(&mut self, path: Path, predicate: F) -> &mut Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE> + Send,
+        Value: MapItem + 'static,
+        F: FnMut(&Value::Id, &mut Value) -> bool + Send + 'static,
+    {
+        self.push(change::map_retain(path, predicate))
+    }
+
+    /// See [`Context::map_get_or_insert_with`].
+    pub fn map_get_or_insert_with<Path, Value, F, const SAFE: bool>(&mut self, path: Path, id: Value::Id, create: F) -> &mut Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE> + Send,
+        Value: MapItem + 'static,
+        F: FnOnce() -> Value + Send + 'static,
+        Value::Id: Send,
+    {
+        self.push(change::map_get_or_insert_with(path, id, create))
+    }
+
+    /// See [`Context::map_update`].
+    pub fn map_update<Path, Value, F, const SAFE: bool>(&mut self, path: Path, id: Value::Id, closure: F) -> &mut Self
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE> + Send,
+        Value: MapItem + 'static,
+        F: FnOnce(&mut Value) + Send + 'static,
+        Value::Id: Send,
+    {
+        self.push(change::map_update(path, id, closure))
+    }
+
+    /// See [`Context::btree_insert`].
+    pub fn btree_insert<Path, Key, Value, const SAFE: bool>(&mut self, path: Path, key: Key, value: Value) -> &mut Self
+    where
+        Path: crate::Path<State, BTreeMap<Key, Value>, SAFE> + Send,
+        Key: Ord + Send + 'static,
+        Value: Send + 'static,
+    {
+        self.push(change::btree_insert(path, key, value))
+    }
+
+    /// See [`Context::btree_remove`].
+    pub fn btree_remove<Path, Key, Value, const SAFE: bool>(&mut self, path: Path, key: Key) -> &mut Self
+    where
+        Path: crate::Path<State, BTreeMap<Key, Value>, SAFE> + Send,
+        Key: Ord + Send + 'static,
+        Value: 'static,
+    {
+        self.push(change::btree_remove(path, key))
+    }
+
+    /// See [`Context::set_insert`].
+    pub fn set_insert<Path, Value, const SAFE: bool>(&mut self, path: Path, value: Value) -> &mut Self
+    where
+        Path: crate::Path<State, HashSet<Value>, SAFE> + Send,
+        Value: Eq + Hash + Send + 'static,
+    {
+        self.push(change::set_insert(path, value))
+    }
+
+    /// See [`Context::set_remove`].
+    pub fn set_remove<Path, Value, const SAFE: bool>(&mut self, path: Path, value: Value) -> &mut Self
+    where
+        Path: crate::Path<State, HashSet<Value>, SAFE> + Send,
+        Value: Eq + Hash + Send + 'static,
+    {
+        self.push(change::set_remove(path, value))
+    }
+
+    /// See [`Context::set_clear`].
+    pub fn set_clear<Path, Value, const SAFE: bool>(&mut self, path: Path) -> &mut Self
+    where
+        Path: crate::Path<State, HashSet<Value>, SAFE> + Send,
+        Value: 'static,
+    {
+        self.push(change::set_clear(path))
+    }
+
+    /// See [`Context::slotmap_insert`].
+    #[cfg(feature = "slotmap")]
+    pub fn slotmap_insert<Path, K, Value, F, const SAFE: bool>(&mut self, path: Path, value: Value, callback: F) -> &mut Self
+    where
+        Path: crate::Path<State, SlotMap<K, Value>, SAFE> + Send,
+        K: Key + 'static,
+        Value: Send + 'static,
+        F: FnOnce(K) + Send + 'static,
+    {
+        self.push(change::slotmap_insert(path, value, callback))
+    }
+
+    /// See [`Context::slotmap_remove`].
+    #[cfg(feature = "slotmap")]
+    pub fn slotmap_remove<Path, K, Value, const SAFE: bool>(&mut self, path: Path, key: K) -> &mut Self
+    where
+        Path: crate::Path<State, SlotMap<K, Value>, SAFE> + Send,
+        K: Key + Send + 'static,
+        Value: 'static,
+    {
+        self.push(change::slotmap_remove(path, key))
+    }
+
+    /// See [`Context::map_vec_push`].
+    pub fn map_vec_push<Path, Key, Value, const SAFE: bool>(&mut self, path: Path, key: Key, value: Value) -> &mut Self
+    where
+        Path: crate::Path<State, HashMap<Key, Vec<Value>>, SAFE> + Send,
+        Key: Eq + Hash + Send + 'static,
+        Value: Send + 'static,
+    {
+        self.push(change::map_vec_push(path, key, value))
+    }
+
+    /// See [`Context::btree_set_insert`].
+    pub fn btree_set_insert<Path, Value, const SAFE: bool>(&mut self, path: Path, value: Value) -> &mut Self
+    where
+        Path: crate::Path<State, BTreeSet<Value>, SAFE> + Send,
+        Value: Ord + Send + 'static,
+    {
+        self.push(change::btree_set_insert(path, value))
+    }
+
+    /// See [`Context::btree_set_remove`].
+    pub fn btree_set_remove<Path, Value, const SAFE: bool>(&mut self, path: Path, value: Value) -> &mut Self
+    where
+        Path: crate::Path<State, BTreeSet<Value>, SAFE> + Send,
+        Value: Ord + Send + 'static,
+    {
+        self.push(change::btree_set_remove(path, value))
+    }
+
+    /// See [`Context::insert_extension_at`].
+    pub fn insert_extension_at<Path, Value, const SAFE: bool>(&mut self, path: Path, value: Value) -> &mut Self
+    where
+        Path: crate::Path<State, Extensions, SAFE> + Send,
+        Value: Send + 'static,
+    {
+        self.push(change::insert_extension(path, value))
+    }
+
+    /// See [`Context::remove_extension_at`].
+    pub fn remove_extension_at<Value, Path, const SAFE: bool>(&mut self, path: Path) -> &mut Self
+    where
+        Path: crate::Path<State, Extensions, SAFE> + Send,
+        Value: 'static,
+    {
+        self.push(change::remove_extension::<State, Value, Path, SAFE>(path))
+    }
+
+    /// Get the changes of this batch, so they can be queued.
+    pub(crate) fn into_changes(self) -> Vec<RecordedChange<State>> {
+        self.changes
+            .into_iter()
+            .map(|change| RecordedChange {
+                record: change.record,
+                apply: change.apply,
+                fallible: change.fallible,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,165 @@
+//! Module providing [`BoxedPath`], a type-erased [`Path`].
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// Object safe version of [`Path`] that [`BoxedPath`] stores.
+trait ErasedPath<State, To: ?Sized> {
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a To>;
+
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut To>;
+
+    fn write(&self, state: &mut State, value: To) -> bool
+    where
+        To: Sized;
+
+    fn key(&self) -> Option<PathKey>;
+}
+
+/// Wrapper tying a path to its `SAFE` parameter.
+struct Erased<T, const SAFE: bool>(T);
+
+impl<State, To, T, const SAFE: bool> ErasedPath<State, To> for Erased<T, SAFE>
+where
+    To: ?Sized,
+    T: Path<State, To, SAFE>,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a To> {
+        self.0.follow(state)
+    }
+
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut To> {
+        self.0.follow_mut(state)
+    }
+
+    fn write(&self, state: &mut State, value: To) -> bool
+    where
+        To: Sized,
+    {
+        self.0.write(state, value)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        self.0.key()
+    }
+}
+
+/// A type-erased path, so paths of different types to the same target can be
+/// stored together.
+///
+/// The erased path is shared through an [`Arc`], so boxed paths are cheap to
+/// [`Clone`], but unlike most paths they are not [`Copy`]. Extension methods
+/// such as field paths require a [`Copy`] path, so build the full path before
+/// boxing it.
+///
+/// Boxed paths to the same target share one type, so
+/// [`ChangeSet::contains_path`](crate::ChangeSet::contains_path) can't tell
+/// them apart.
+///
+/// Example:
+/// ```
+/// use rust_state::{BoxedPath, Context, RustState};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct MyState {
+///     width: u32,
+///     height: u32,
+/// }
+///
+/// let mut context = Context::new(MyState { width: 10, height: 20 });
+///
+/// let paths: Vec<BoxedPath<MyState, u32>> = vec![
+///     BoxedPath::new(MyState::path().width()),
+///     BoxedPath::new(MyState::path().height()),
+/// ];
+///
+/// for path in &paths {
+///     context.update_value(path.clone(), 0);
+/// }
+///
+/// context.apply();
+///
+/// assert_eq!(context.get(&paths[0]), &0);
+/// assert_eq!(context.get(&paths[1]), &0);
+/// ```
+pub struct BoxedPath<State: 'static, To: ?Sized + 'static, const SAFE: bool = true> {
+    path: Arc<dyn ErasedPath<State, To>>,
+}
+
+impl<State, To, const SAFE: bool> BoxedPath<State, To, SAFE>
+where
+    State: 'static,
+    To: ?Sized + 'static,
+{
+    /// Erase the type of a path.
+    pub fn new(path: impl Path<State, To, SAFE>) -> Self {
+        Self {
+            path: Arc::new(Erased::<_, SAFE>(path)),
+        }
+    }
+}
+
+impl<State, To, const SAFE: bool> Clone for BoxedPath<State, To, SAFE>
+where
+    State: 'static,
+    To: ?Sized + 'static,
+{
+    fn clone(&self) -> Self {
+        Self { path: self.path.clone() }
+    }
+}
+
+impl<State, To, const SAFE: bool> fmt::Debug for BoxedPath<State, To, SAFE>
+where
+    State: 'static,
+    To: ?Sized + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxedPath").field("to", &std::any::type_name::<To>()).finish()
+    }
+}
+
+impl<State, To, const SAFE: bool> Selector<State, To, SAFE> for BoxedPath<State, To, SAFE>
+where
+    State: 'static,
+    To: ?Sized + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a To> {
+        self.path.follow(state)
+    }
+}
+
+impl<State, To, const SAFE: bool> PathRead<State, To, SAFE> for BoxedPath<State, To, SAFE>
+where
+    State: 'static,
+    To: ?Sized + 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a To> {
+        self.path.follow(state)
+    }
+
+    /// Boxed paths have the same key as the path they were created from.
+    fn key(&self) -> Option<PathKey> {
+        self.path.key()
+    }
+}
+
+impl<State, To, const SAFE: bool> PathWrite<State, To, SAFE> for BoxedPath<State, To, SAFE>
+where
+    State: 'static,
+    To: ?Sized + 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut To> {
+        self.path.follow_mut(state)
+    }
+
+    fn write(&self, state: &mut State, value: To) -> bool
+    where
+        To: Sized,
+    {
+        self.path.write(state, value)
+    }
+}
@@ -0,0 +1,171 @@
+//! Module providing an extension trait to index a [`BTreeMap`] in the state.
+//!
+//! Other than [`HashMap`](std::collections::HashMap), a [`BTreeMap`] is
+//! ordered, so in addition to looking up a key there are paths to the first
+//! and last entry of the map.
+//!
+//! Example:
+//! ```
+//! use std::collections::BTreeMap;
+//! use rust_state::{BTreeMapLookupExt, Context, ManuallyAssertExt, RustState};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     items: BTreeMap<u32, &'static str>,
+//! }
+//!
+//! let context = Context::new(State {
+//!     items: BTreeMap::from([(20, "second"), (10, "first")]),
+//! });
+//!
+//! let item_path = State::path().items().lookup(20);
+//! let first_path = State::path().items().first();
+//! let last_path = State::path().items().last();
+//!
+//! assert_eq!(context.try_get(&item_path), Some(&"second"));
+//! assert_eq!(context.try_get(&first_path), Some(&"first"));
+//! assert_eq!(context.try_get(&last_path), Some(&"second"));
+//! ```
+
+use std::collections::BTreeMap;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// The entry of a [`BTreeMap`] a [`BTreeMapLookup`] resolves to.
+#[derive(Clone, Copy, Hash)]
+enum Entry<Key> {
+    Key(Key),
+    First,
+    Last,
+}
+
+/// A path for doing a lookup into a [`BTreeMap`].
+///
+/// This type is not accessible outside this module, instead
+/// [`BTreeMapLookupExt`] can be used to construct it and receive a `impl
+/// Path<State, Value>`.
+struct BTreeMapLookup<State, MapPath, Key, Value, const SAFE: bool> {
+    map_path: MapPath,
+    entry: Entry<Key>,
+    _marker: PhantomData<(State, Value)>,
+}
+
+impl<State, MapPath, Key, Value, const SAFE: bool> Clone for BTreeMapLookup<State, MapPath, Key, Value, SAFE>
+where
+    MapPath: Path<State, BTreeMap<Key, Value>, SAFE>,
+    Key: Copy,
+{
+    fn clone(&self) -> Self {
+        Self {
+            map_path: self.map_path.clone(),
+            entry: self.entry,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, MapPath, Key, Value, const SAFE: bool> Copy for BTreeMapLookup<State, MapPath, Key, Value, SAFE>
+where
+    MapPath: Path<State, BTreeMap<Key, Value>, SAFE> + Copy,
+    Key: Copy,
+{
+}
+
+impl<State, MapPath, Key, Value, const SAFE: bool> Selector<State, Value, false> for BTreeMapLookup<State, MapPath, Key, Value, SAFE>
+where
+    State: 'static,
+    MapPath: Path<State, BTreeMap<Key, Value>, SAFE>,
+    Key: Ord + Hash + Copy + 'static,
+    Value: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Value> {
+        self.follow(state)
+    }
+}
+
+impl<State, MapPath, Key, Value, const SAFE: bool> PathRead<State, Value, false> for BTreeMapLookup<State, MapPath, Key, Value, SAFE>
+where
+    State: 'static,
+    MapPath: Path<State, BTreeMap<Key, Value>, SAFE>,
+    Key: Ord + Hash + Copy + 'static,
+    Value: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Value> {
+        let map = self.map_path.follow(state)?;
+
+        match &self.entry {
+            Entry::Key(key) => map.get(key),
+            Entry::First => map.values().next(),
+            Entry::Last => map.values().next_back(),
+        }
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.map_path.key()?).with(self.entry))
+    }
+}
+
+impl<State, MapPath, Key, Value, const SAFE: bool> PathWrite<State, Value, false> for BTreeMapLookup<State, MapPath, Key, Value, SAFE>
+where
+    State: 'static,
+    MapPath: Path<State, BTreeMap<Key, Value>, SAFE>,
+    Key: Ord + Hash + Copy + 'static,
+    Value: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Value> {
+        let map = self.map_path.follow_mut(state)?;
+
+        match &self.entry {
+            Entry::Key(key) => map.get_mut(key),
+            Entry::First => map.values_mut().next(),
+            Entry::Last => map.values_mut().next_back(),
+        }
+    }
+}
+
+pub trait BTreeMapLookupExt<State, T, Key, Value, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, BTreeMap<Key, Value>, SAFE> + Copy,
+    Key: Ord + Hash + Copy + 'static,
+    Value: 'static,
+{
+    /// Path to the value with the given key.
+    fn lookup(self, key: Key) -> impl Path<State, Value, false> + Copy {
+        BTreeMapLookup {
+            map_path: self,
+            entry: Entry::Key(key),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Path to the value with the smallest key.
+    fn first(self) -> impl Path<State, Value, false> + Copy {
+        BTreeMapLookup {
+            map_path: self,
+            entry: Entry::First,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Path to the value with the largest key.
+    fn last(self) -> impl Path<State, Value, false> + Copy {
+        BTreeMapLookup {
+            map_path: self,
+            entry: Entry::Last,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Key, Value, const SAFE: bool> BTreeMapLookupExt<State, T, Key, Value, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, BTreeMap<Key, Value>, SAFE> + Copy,
+    Key: Ord + Hash + Copy + 'static,
+    Value: 'static,
+{
+}
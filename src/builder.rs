@@ -0,0 +1,285 @@
+//! Module providing the [`ContextBuilder`], which creates a
+//! [`Context`] with configuration options.
+
+use std::error::Error;
+
+use crate::{ApplyOrder, ApplyReport, Context, StateMarker};
+
+/// Builder for a [`Context`].
+///
+/// Created using [`Context::builder`]. Every option corresponds to a setter
+/// on the [`Context`].
+///
+/// Example:
+/// ```
+/// use rust_state::{ApplyOrder, Context, RustState};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct MyState {
+///     value: u32,
+/// }
+///
+/// let mut context = Context::builder(MyState { value: 5 })
+///     .queue_capacity(128)
+///     .apply_order(ApplyOrder::GroupedBySource)
+///     .error_handler(|error| eprintln!("Failed to apply change: {error}"))
+///     .strict(true)
+///     .build();
+///
+/// context.update_value(MyState::path().value(), 10);
+/// context.apply();
+///
+/// assert_eq!(context.get(&MyState::path().value()), &10);
+/// ```
+#[must_use]
+pub struct ContextBuilder<State> {
+    context: Context<State>,
+}
+
+impl<State: StateMarker> ContextBuilder<State> {
+    pub(crate) fn new(state: State) -> Self {
+        Self {
+            context: Context::new(state),
+        }
+    }
+}
+
+impl<State: 'static> ContextBuilder<State> {
+    /// Reserve space for the given number of pending changes, so queuing
+    /// changes doesn't have to reallocate until that many are pending.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let context = Context::builder(MyState { value: 0 }).queue_capacity(1024).build();
+    ///
+    /// assert_eq!(context.pending_changes(), 0);
+    /// ```
+    pub fn queue_capacity(mut self, capacity: usize) -> Self {
+        self.context.reserve_changes(capacity);
+        self
+    }
+
+    /// Set the order in which [`Context::apply`] applies pending changes.
+    /// Defaults to [`ApplyOrder::Insertion`]. See
+    /// [`Context::set_apply_order`].
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{ApplyOrder, Context, RustState, SourceKey};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     log: Vec<u32>,
+    /// }
+    ///
+    /// let mut context = Context::builder(MyState { log: Vec::new() })
+    ///     .apply_order(ApplyOrder::GroupedBySource)
+    ///     .build();
+    ///
+    /// let log_path = MyState::path().log();
+    ///
+    /// context.with_source(SourceKey(1), |context| context.vec_push(log_path, 1));
+    /// context.with_source(SourceKey(2), |context| context.vec_push(log_path, 2));
+    /// context.with_source(SourceKey(1), |context| context.vec_push(log_path, 3));
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&log_path), &[1, 3, 2]);
+    /// ```
+    pub fn apply_order(mut self, apply_order: ApplyOrder) -> Self {
+        self.context.set_apply_order(apply_order);
+        self
+    }
+
+    /// Set the maximum number of rounds a single [`Context::apply`] runs.
+    /// Defaults to 16. See [`Context::set_max_apply_rounds`].
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// // Every round queues another change, so only one round is applied.
+    /// let mut context = Context::builder(MyState { value: 0 })
+    ///     .max_apply_rounds(1)
+    ///     .post_apply_hook(|context, _| {
+    ///         context.update_value_with(MyState::path().value(), |value| *value += 1);
+    ///     })
+    ///     .build();
+    ///
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&MyState::path().value()), &0);
+    /// assert_eq!(context.pending_changes(), 1);
+    /// ```
+    pub fn max_apply_rounds(mut self, max_apply_rounds: usize) -> Self {
+        self.context.set_max_apply_rounds(max_apply_rounds);
+        self
+    }
+
+    /// Record a [`ChangeSet`](crate::ChangeSet) of all applied changes. See
+    /// [`Context::set_record_changes`].
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut context = Context::builder(MyState { value: 0 }).record_changes(true).build();
+    ///
+    /// context.update_value(MyState::path().value(), 5);
+    /// let report = context.apply();
+    ///
+    /// assert!(report.change_set().contains_path(MyState::path().value()));
+    /// ```
+    pub fn record_changes(mut self, record_changes: bool) -> Self {
+        self.context.set_record_changes(record_changes);
+        self
+    }
+
+    /// Set a handler that is called with every error returned by a fallible
+    /// change. See [`Context::set_error_handler`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::num::ParseIntError;
+    /// use std::rc::Rc;
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let errors = Rc::new(Cell::new(0));
+    /// let error_count = errors.clone();
+    ///
+    /// let mut context = Context::builder(MyState { value: 0 })
+    ///     .error_handler(move |_| error_count.set(error_count.get() + 1))
+    ///     .build();
+    ///
+    /// context.update_value_try(MyState::path().value(), |value| {
+    ///     *value = "nope".parse::<u32>()?;
+    ///     Ok::<_, ParseIntError>(())
+    /// });
+    /// context.apply();
+    ///
+    /// assert_eq!(errors.get(), 1);
+    /// ```
+    pub fn error_handler(mut self, error_handler: impl FnMut(&dyn Error) + 'static) -> Self {
+        self.context.set_error_handler(error_handler);
+        self
+    }
+
+    /// Panic in [`Context::apply`] if any fallible change returned an error.
+    /// See [`Context::set_strict`].
+    ///
+    /// Example:
+    /// ```should_panic
+    /// use std::num::ParseIntError;
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut context = Context::builder(MyState { value: 0 }).strict(true).build();
+    ///
+    /// context.update_value_try(MyState::path().value(), |value| {
+    ///     *value = "nope".parse::<u32>()?;
+    ///     Ok::<_, ParseIntError>(())
+    /// });
+    /// context.apply();
+    /// ```
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.context.set_strict(strict);
+        self
+    }
+
+    /// Register a hook that runs at the start of every [`Context::apply`].
+    /// See [`Context::add_pre_apply_hook`].
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     frame: u32,
+    /// }
+    ///
+    /// let mut context = Context::builder(MyState { frame: 0 })
+    ///     .pre_apply_hook(|context| context.update_value_with(MyState::path().frame(), |frame| *frame += 1))
+    ///     .build();
+    ///
+    /// context.apply();
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&MyState::path().frame()), &2);
+    /// ```
+    pub fn pre_apply_hook(mut self, hook: impl FnMut(&Context<State>) + 'static) -> Self {
+        self.context.add_pre_apply_hook(hook);
+        self
+    }
+
+    /// Register a hook that runs at the end of every [`Context::apply`]. See
+    /// [`Context::add_post_apply_hook`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let applied = Rc::new(Cell::new(0));
+    /// let applied_count = applied.clone();
+    ///
+    /// let mut context = Context::builder(MyState { value: 0 })
+    ///     .post_apply_hook(move |_, report| applied_count.set(applied_count.get() + report.applied_changes()))
+    ///     .build();
+    ///
+    /// context.update_value(MyState::path().value(), 5);
+    /// context.apply();
+    ///
+    /// assert_eq!(applied.get(), 1);
+    /// ```
+    pub fn post_apply_hook(mut self, hook: impl FnMut(&Context<State>, &ApplyReport) + 'static) -> Self {
+        self.context.add_post_apply_hook(hook);
+        self
+    }
+
+    /// Create the [`Context`] with the configured options.
+    pub fn build(self) -> Context<State> {
+        self.context
+    }
+}
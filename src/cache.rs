@@ -0,0 +1,377 @@
+//! Module providing [`Versioned`] values and [`Cached`] values derived from
+//! them.
+//!
+//! A [`Versioned`] value gets a new [`Version`] every time it is accessed
+//! mutably. A [`Cached`] value remembers the version of the source it was
+//! computed from and is considered invalid once the source changes, so there
+//! is no need to invalidate caches manually. Versions are unique across all
+//! [`Versioned`] values, so a cache never appears valid for a different source,
+//! even if the source was replaced.
+//!
+//! Invalid caches are not recomputed automatically. Instead,
+//! [`Context::refresh_cache`](crate::Context::refresh_cache) queues
+//! recomputing the value if it is no longer valid.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Cached, CachedExt, Context, RustState, Versioned, VersionedExt};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     numbers: Versioned<Vec<u32>>,
+//!     sum: Cached<u32>,
+//! }
+//!
+//! let mut context = Context::new(State {
+//!     numbers: Versioned::new(vec![1, 2, 3]),
+//!     sum: Cached::new(),
+//! });
+//!
+//! let numbers_path = State::path().numbers();
+//! let sum_path = State::path().sum().cached_for(numbers_path);
+//!
+//! context.refresh_cache(State::path().sum(), numbers_path, |numbers| numbers.iter().sum());
+//! context.apply();
+//!
+//! assert_eq!(context.try_get(&sum_path), Some(&6));
+//!
+//! // Changing the numbers invalidates the sum.
+//! context.vec_push(numbers_path.versioned_value(), 4);
+//! context.apply();
+//!
+//! assert_eq!(context.try_get(&sum_path), None);
+//!
+//! context.refresh_cache(State::path().sum(), numbers_path, |numbers| numbers.iter().sum());
+//! context.apply();
+//!
+//! assert_eq!(context.try_get(&sum_path), Some(&10));
+//!
+//! // Replacing the numbers also invalidates the sum.
+//! context.update_value(numbers_path, Versioned::new(vec![1, 2, 3, 4]));
+//! context.apply();
+//!
+//! assert_eq!(context.try_get(&sum_path), None);
+//! ```
+
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// The version of a [`Versioned`] value.
+///
+/// Every version is only ever handed out once, so two different
+/// [`Versioned`] values never share a version unless one is a clone of the
+/// other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Version(u64);
+
+impl Version {
+    fn next() -> Self {
+        static NEXT_VERSION: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT_VERSION.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// A value with a version that changes on every mutable access.
+#[derive(Debug, Clone)]
+pub struct Versioned<T> {
+    value: T,
+    version: Version,
+}
+
+impl<T: Default> Default for Versioned<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> Versioned<T> {
+    /// Create a new versioned value with a new, unique version.
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            version: Version::next(),
+        }
+    }
+
+    /// Get the current version of the value.
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// Get the value without changing the version.
+    pub fn get(&self) -> &T {
+        &self.value
+    }
+
+    /// Get a mutable reference to the value, changing the version.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.version = Version::next();
+        &mut self.value
+    }
+}
+
+/// A value computed from a [`Versioned`] source.
+///
+/// The cached value is only valid as long as the version of the source
+/// matches the version it was computed from.
+#[derive(Debug, Clone)]
+pub struct Cached<T> {
+    entry: Option<(T, Version)>,
+}
+
+impl<T> Default for Cached<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Cached<T> {
+    /// Create a new, invalid cache.
+    pub fn new() -> Self {
+        Self { entry: None }
+    }
+
+    /// Get the cached value if it is still valid for the source.
+    pub fn get<Source>(&self, source: &Versioned<Source>) -> Option<&T> {
+        self.get_for_version(source.version())
+    }
+
+    /// Set the cached value, computed from the current version of the source.
+    pub fn set<Source>(&mut self, value: T, source: &Versioned<Source>) {
+        self.entry = Some((value, source.version()));
+    }
+
+    /// Check if the cached value is still valid for the source.
+    pub fn is_valid<Source>(&self, source: &Versioned<Source>) -> bool {
+        self.get(source).is_some()
+    }
+
+    /// Invalidate the cached value, regardless of the source.
+    pub fn invalidate(&mut self) {
+        self.entry = None;
+    }
+
+    pub(crate) fn get_for_version(&self, version: Version) -> Option<&T> {
+        self.entry
+            .as_ref()
+            .filter(|(_, cached_version)| *cached_version == version)
+            .map(|(value, _)| value)
+    }
+
+    fn get_mut_for_version(&mut self, version: Version) -> Option<&mut T> {
+        self.entry
+            .as_mut()
+            .filter(|(_, cached_version)| *cached_version == version)
+            .map(|(value, _)| value)
+    }
+
+    pub(crate) fn set_for_version(&mut self, value: T, version: Version) {
+        self.entry = Some((value, version));
+    }
+}
+
+/// A path to the value inside a [`Versioned`]. Following this path mutably
+/// increments the version.
+///
+/// This type is not accessible outside this module. Instead,
+/// [`VersionedExt`] is used to construct it and receive an `impl Path<State,
+/// T>`.
+struct VersionedValue<State, VersionedPath, T, const SAFE: bool> {
+    versioned_path: VersionedPath,
+    _marker: PhantomData<(State, T)>,
+}
+
+impl<State, VersionedPath, T, const SAFE: bool> Clone for VersionedValue<State, VersionedPath, T, SAFE>
+where
+    VersionedPath: Path<State, Versioned<T>, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            versioned_path: self.versioned_path.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, VersionedPath, T, const SAFE: bool> Copy for VersionedValue<State, VersionedPath, T, SAFE> where
+    VersionedPath: Path<State, Versioned<T>, SAFE> + Copy
+{
+}
+
+impl<State, VersionedPath, T, const SAFE: bool> Selector<State, T, SAFE> for VersionedValue<State, VersionedPath, T, SAFE>
+where
+    State: 'static,
+    VersionedPath: Path<State, Versioned<T>, SAFE>,
+    T: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a T> {
+        self.follow(state)
+    }
+}
+
+impl<State, VersionedPath, T, const SAFE: bool> PathRead<State, T, SAFE> for VersionedValue<State, VersionedPath, T, SAFE>
+where
+    State: 'static,
+    VersionedPath: Path<State, Versioned<T>, SAFE>,
+    T: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a T> {
+        self.versioned_path.follow(state).map(Versioned::get)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.versioned_path.key()?))
+    }
+}
+
+impl<State, VersionedPath, T, const SAFE: bool> PathWrite<State, T, SAFE> for VersionedValue<State, VersionedPath, T, SAFE>
+where
+    State: 'static,
+    VersionedPath: Path<State, Versioned<T>, SAFE>,
+    T: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut T> {
+        self.versioned_path.follow_mut(state).map(Versioned::get_mut)
+    }
+}
+
+/// Extension trait providing `.versioned_value()` for [`Versioned<T>`] paths.
+pub trait VersionedExt<State, T, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Versioned<T>, SAFE> + Copy,
+    T: 'static,
+{
+    /// Convert a `Path<State, Versioned<T>>` into a `Path<State, T>`.
+    /// Following the path mutably increments the version.
+    fn versioned_value(self) -> impl Path<State, T, SAFE> + Copy {
+        VersionedValue {
+            versioned_path: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Inner, const SAFE: bool> VersionedExt<State, Inner, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Versioned<Inner>, SAFE> + Copy,
+    Inner: 'static,
+{
+}
+
+/// A path to the value inside a [`Cached`] that only resolves while the
+/// cached value is valid for its source.
+///
+/// This type is not accessible outside this module. Instead,
+/// [`CachedExt`] is used to construct it and receive an `impl Path<State,
+/// T>`.
+struct CachedValue<State, CachePath, SourcePath, T, Source, const CACHE_SAFE: bool, const SOURCE_SAFE: bool> {
+    cache_path: CachePath,
+    source_path: SourcePath,
+    _marker: PhantomData<(State, T, Source)>,
+}
+
+impl<State, CachePath, SourcePath, T, Source, const CACHE_SAFE: bool, const SOURCE_SAFE: bool> Clone
+    for CachedValue<State, CachePath, SourcePath, T, Source, CACHE_SAFE, SOURCE_SAFE>
+where
+    CachePath: Path<State, Cached<T>, CACHE_SAFE>,
+    SourcePath: Path<State, Versioned<Source>, SOURCE_SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            cache_path: self.cache_path.clone(),
+            source_path: self.source_path.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, CachePath, SourcePath, T, Source, const CACHE_SAFE: bool, const SOURCE_SAFE: bool> Copy
+    for CachedValue<State, CachePath, SourcePath, T, Source, CACHE_SAFE, SOURCE_SAFE>
+where
+    CachePath: Path<State, Cached<T>, CACHE_SAFE> + Copy,
+    SourcePath: Path<State, Versioned<Source>, SOURCE_SAFE> + Copy,
+{
+}
+
+impl<State, CachePath, SourcePath, T, Source, const CACHE_SAFE: bool, const SOURCE_SAFE: bool> Selector<State, T, false>
+    for CachedValue<State, CachePath, SourcePath, T, Source, CACHE_SAFE, SOURCE_SAFE>
+where
+    State: 'static,
+    CachePath: Path<State, Cached<T>, CACHE_SAFE>,
+    SourcePath: Path<State, Versioned<Source>, SOURCE_SAFE>,
+    T: 'static,
+    Source: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a T> {
+        self.follow(state)
+    }
+}
+
+impl<State, CachePath, SourcePath, T, Source, const CACHE_SAFE: bool, const SOURCE_SAFE: bool> PathRead<State, T, false>
+    for CachedValue<State, CachePath, SourcePath, T, Source, CACHE_SAFE, SOURCE_SAFE>
+where
+    State: 'static,
+    CachePath: Path<State, Cached<T>, CACHE_SAFE>,
+    SourcePath: Path<State, Versioned<Source>, SOURCE_SAFE>,
+    T: 'static,
+    Source: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a T> {
+        let version = self.source_path.follow(state)?.version();
+        self.cache_path.follow(state)?.get_for_version(version)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.cache_path.key()?).with(self.source_path.key()?))
+    }
+}
+
+impl<State, CachePath, SourcePath, T, Source, const CACHE_SAFE: bool, const SOURCE_SAFE: bool> PathWrite<State, T, false>
+    for CachedValue<State, CachePath, SourcePath, T, Source, CACHE_SAFE, SOURCE_SAFE>
+where
+    State: 'static,
+    CachePath: Path<State, Cached<T>, CACHE_SAFE>,
+    SourcePath: Path<State, Versioned<Source>, SOURCE_SAFE>,
+    T: 'static,
+    Source: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut T> {
+        let version = self.source_path.follow(state)?.version();
+        self.cache_path.follow_mut(state)?.get_mut_for_version(version)
+    }
+}
+
+/// Extension trait providing `.cached_for()` for [`Cached<T>`] paths.
+pub trait CachedExt<State, T, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Cached<T>, SAFE> + Copy,
+    T: 'static,
+{
+    /// Convert a `Path<State, Cached<T>>` into a `Path<State, T>` that only
+    /// resolves while the cached value is valid for the given source.
+    fn cached_for<SourcePath, Source, const SOURCE_SAFE: bool>(self, source_path: SourcePath) -> impl Path<State, T, false> + Copy
+    where
+        SourcePath: Path<State, Versioned<Source>, SOURCE_SAFE> + Copy,
+        Source: 'static,
+    {
+        CachedValue {
+            cache_path: self,
+            source_path,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Inner, const SAFE: bool> CachedExt<State, Inner, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Cached<Inner>, SAFE> + Copy,
+    Inner: 'static,
+{
+}
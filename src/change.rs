@@ -0,0 +1,943 @@
+//! Module providing the constructors for all changes that can be queued
+//! through a path.
+//!
+//! The [`Context`](crate::Context), the [`Batch`](crate::Batch), the
+//! [`ChangeBatch`](crate::ChangeBatch) and the
+//! [`ScopedContext`](crate::ScopedContext) all build their changes here, so
+//! every change behaves the same no matter where it was queued from.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::hash::Hash;
+use std::ops::Range;
+
+#[cfg(feature = "slotmap")]
+use slotmap::{Key, SlotMap};
+
+use crate::change_set::{Change, ChangeKind};
+#[cfg(feature = "sync")]
+use crate::lock::Lock;
+use crate::map::MissingId;
+use crate::vec::DuplicateId;
+use crate::{Cached, Extensions, Flags, MapItem, UnresolvedPath, VecItem, Versioned};
+
+/// The result of applying a single change.
+pub(crate) type ChangeResult = Result<(), Box<dyn Error>>;
+
+/// A boxed change to the state.
+pub(crate) type StateChange<State> = Box<dyn FnOnce(&mut State, &mut Callbacks) -> ChangeResult>;
+
+/// The callbacks of applied changes, like the one passed to
+/// [`Context::take`](crate::Context::take).
+///
+/// Callbacks are collected instead of being called right away, so changes
+/// applied by a [`preview`](crate::Context::preview) only call them once they
+/// are committed.
+#[derive(Default)]
+pub(crate) struct Callbacks(Vec<Box<dyn FnOnce()>>);
+
+impl Callbacks {
+    fn defer(&mut self, callback: impl FnOnce() + 'static) {
+        self.0.push(Box::new(callback));
+    }
+
+    /// Move the callbacks of another change behind the ones collected so far.
+    pub(crate) fn append(&mut self, other: Callbacks) {
+        self.0.extend(other.0);
+    }
+
+    /// Call all collected callbacks in the order their changes were applied.
+    pub(crate) fn call(self) {
+        self.0.into_iter().for_each(|callback| callback());
+    }
+}
+
+/// A change built by one of the constructors in this module, together with
+/// the [`Change`] it is recorded as in the [`ChangeSet`](crate::ChangeSet).
+///
+/// The change is not boxed, so it is [`Send`] whenever everything it captures
+/// is.
+pub(crate) struct PathChange<F> {
+    pub(crate) record: Change,
+    pub(crate) apply: F,
+    /// Whether the change reports an [`UnresolvedPath`] error instead of
+    /// ignoring a path that doesn't resolve.
+    pub(crate) fallible: bool,
+}
+
+impl<F> PathChange<F> {
+    /// Box the change so it can be queued.
+    pub(crate) fn boxed<State>(self) -> RecordedChange<State>
+    where
+        F: FnOnce(&mut State, &mut Callbacks) -> ChangeResult + 'static,
+    {
+        RecordedChange {
+            record: self.record,
+            apply: Box::new(self.apply),
+            fallible: self.fallible,
+        }
+    }
+}
+
+/// A boxed change together with the [`Change`] it is recorded as.
+pub(crate) struct RecordedChange<State> {
+    pub(crate) record: Change,
+    pub(crate) apply: StateChange<State>,
+    pub(crate) fallible: bool,
+}
+
+impl<State: 'static> RecordedChange<State> {
+    /// Convert a change to the target of a path into a change to the state the
+    /// path starts at.
+    ///
+    /// If the path doesn't resolve, fallible changes return an
+    /// [`UnresolvedPath`] error, just like they would for their own path.
+    pub(crate) fn scoped<Outer, Path, const SAFE: bool>(self, path: Path) -> RecordedChange<Outer>
+    where
+        Path: crate::Path<Outer, State, SAFE>,
+    {
+        let apply = self.apply;
+        let fallible = self.fallible;
+
+        RecordedChange {
+            record: self.record,
+            apply: Box::new(
+                move |state: &mut Outer, callbacks: &mut Callbacks| match path.follow_mut(state) {
+                    Some(sub) => apply(sub, callbacks),
+                    None if fallible => Err(UnresolvedPath::new::<State>().into()),
+                    None => unresolved(),
+                },
+            ),
+            fallible,
+        }
+    }
+}
+
+/// Handle a path that didn't resolve while applying an infallible change.
+fn unresolved() -> ChangeResult {
+    println!("Failed to update state");
+    Ok(())
+}
+
+/// Build a change that calls a closure with the target of a path.
+fn modify<State, Path, Target, F, const SAFE: bool>(
+    path: Path,
+    kind: ChangeKind,
+    closure: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Target, SAFE>,
+    Target: ?Sized + 'static,
+    F: FnOnce(&mut Target),
+{
+    PathChange {
+        record: Change::new::<Path, Target>(kind),
+        apply: move |state: &mut State, _: &mut Callbacks| match path.follow_mut(state) {
+            Some(target) => {
+                closure(target);
+                Ok(())
+            }
+            None => unresolved(),
+        },
+        fallible: false,
+    }
+}
+
+/// Build a change that calls a fallible closure with the target of a path.
+/// Returns an [`UnresolvedPath`] error if the path doesn't resolve.
+fn try_modify<State, Path, Target, F, const SAFE: bool>(
+    path: Path,
+    kind: ChangeKind,
+    closure: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Target, SAFE>,
+    Target: ?Sized + 'static,
+    F: FnOnce(&mut Target) -> ChangeResult,
+{
+    PathChange {
+        record: Change::new::<Path, Target>(kind),
+        apply: move |state: &mut State, _: &mut Callbacks| match path.follow_mut(state) {
+            Some(target) => closure(target),
+            None => Err(UnresolvedPath::new::<Target>().into()),
+        },
+        fallible: true,
+    }
+}
+
+/// Build a change that calls a closure with the target of a path and passes
+/// its result to a callback.
+fn modify_with_callback<State, Path, Target, Output, F, C, const SAFE: bool>(
+    path: Path,
+    kind: ChangeKind,
+    closure: F,
+    callback: C,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Target, SAFE>,
+    Target: ?Sized + 'static,
+    Output: 'static,
+    F: FnOnce(&mut Target) -> Output,
+    C: FnOnce(Output) + 'static,
+{
+    PathChange {
+        record: Change::new::<Path, Target>(kind),
+        apply: move |state: &mut State, callbacks: &mut Callbacks| match path.follow_mut(state) {
+            Some(target) => {
+                let output = closure(target);
+                callbacks.defer(move || callback(output));
+                Ok(())
+            }
+            None => unresolved(),
+        },
+        fallible: false,
+    }
+}
+
+pub(crate) fn update_value<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    value: Value,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Value, SAFE>,
+    Value: 'static,
+{
+    PathChange {
+        record: Change::new::<Path, Value>(ChangeKind::UpdateValue),
+        apply: move |state: &mut State, _: &mut Callbacks| match path.write(state, value) {
+            true => Ok(()),
+            false => unresolved(),
+        },
+        fallible: false,
+    }
+}
+
+pub(crate) fn update_value_with<State, Path, Value, F, const SAFE: bool>(
+    path: Path,
+    closure: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Value, SAFE>,
+    Value: ?Sized + 'static,
+    F: FnOnce(&mut Value),
+{
+    modify(path, ChangeKind::UpdateValue, closure)
+}
+
+pub(crate) fn update_value_try<State, Path, Value, F, E, const SAFE: bool>(
+    path: Path,
+    closure: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Value, SAFE>,
+    Value: 'static,
+    F: FnOnce(&mut Value) -> Result<(), E>,
+    E: Error + 'static,
+{
+    try_modify(path, ChangeKind::UpdateValue, move |value: &mut Value| {
+        closure(value).map_err(Into::into)
+    })
+}
+
+pub(crate) fn update_root<State, F>(closure: F) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    F: FnOnce(&mut State),
+{
+    PathChange {
+        record: Change::root::<State>(),
+        apply: move |state: &mut State, _: &mut Callbacks| {
+            closure(state);
+            Ok(())
+        },
+        fallible: false,
+    }
+}
+
+pub(crate) fn update_cell<State, Path, Value, F, const SAFE: bool>(
+    path: Path,
+    closure: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, RefCell<Value>, SAFE>,
+    Value: 'static,
+    F: FnOnce(&mut Value),
+{
+    modify(path, ChangeKind::UpdateValue, move |cell: &mut RefCell<Value>| {
+        closure(cell.get_mut())
+    })
+}
+
+#[cfg(feature = "sync")]
+pub(crate) fn update_locked<State, Path, Value, F, const SAFE: bool>(
+    path: Path,
+    closure: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Value, SAFE>,
+    Value: Lock + 'static,
+    F: FnOnce(&mut Value::Target),
+{
+    PathChange {
+        record: Change::new::<Path, Value>(ChangeKind::UpdateValue),
+        apply: move |state: &mut State, _: &mut Callbacks| match path.follow_mut(state).and_then(Lock::locked_mut) {
+            Some(target) => {
+                closure(target);
+                Ok(())
+            }
+            None => unresolved(),
+        },
+        fallible: false,
+    }
+}
+
+pub(crate) fn update_each<State, Traversal, Value, F>(
+    traversal: Traversal,
+    mut closure: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Traversal: crate::Traversal<State, Value>,
+    Value: ?Sized + 'static,
+    F: FnMut(&mut Value),
+{
+    PathChange {
+        record: Change::new::<Traversal, Value>(ChangeKind::UpdateValue),
+        apply: move |state: &mut State, _: &mut Callbacks| {
+            traversal.traverse_mut(state, &mut closure);
+            Ok(())
+        },
+        fallible: false,
+    }
+}
+
+pub(crate) fn update_each_indexed<State, Traversal, Key, Value, F>(
+    traversal: Traversal,
+    mut closure: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Traversal: crate::IndexedTraversal<State, Key, Value>,
+    Value: ?Sized + 'static,
+    F: FnMut(&Key, &mut Value),
+{
+    PathChange {
+        record: Change::new::<Traversal, Value>(ChangeKind::UpdateValue),
+        apply: move |state: &mut State, _: &mut Callbacks| {
+            traversal.traverse_indexed_mut(state, &mut closure);
+            Ok(())
+        },
+        fallible: false,
+    }
+}
+
+pub(crate) fn take<State, Path, Value, F, const SAFE: bool>(
+    path: Path,
+    callback: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Value, SAFE>,
+    Value: Default + 'static,
+    F: FnOnce(Value) + 'static,
+{
+    modify_with_callback(path, ChangeKind::UpdateValue, std::mem::take, callback)
+}
+
+pub(crate) fn replace<State, Path, Value, F, const SAFE: bool>(
+    path: Path,
+    value: Value,
+    callback: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Value, SAFE>,
+    Value: 'static,
+    F: FnOnce(Value) + 'static,
+{
+    modify_with_callback(
+        path,
+        ChangeKind::UpdateValue,
+        move |previous: &mut Value| std::mem::replace(previous, value),
+        callback,
+    )
+}
+
+pub(crate) fn refresh_cache<State, CachePath, SourcePath, Value, Source, F, const CACHE_SAFE: bool, const SOURCE_SAFE: bool>(
+    cache_path: CachePath,
+    source_path: SourcePath,
+    compute: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    CachePath: crate::Path<State, Cached<Value>, CACHE_SAFE>,
+    SourcePath: crate::Path<State, Versioned<Source>, SOURCE_SAFE>,
+    F: FnOnce(&Source) -> Value,
+{
+    PathChange {
+        record: Change::new::<CachePath, Cached<Value>>(ChangeKind::UpdateValue),
+        apply: move |state: &mut State, _: &mut Callbacks| {
+            let Some(source) = source_path.follow(state) else {
+                return unresolved();
+            };
+
+            let version = source.version();
+
+            let Some(cache) = cache_path.follow(state) else {
+                return unresolved();
+            };
+
+            if cache.get_for_version(version).is_some() {
+                return Ok(());
+            }
+
+            let value = compute(source.get());
+
+            match cache_path.follow_mut(state) {
+                Some(cache) => {
+                    cache.set_for_version(value, version);
+                    Ok(())
+                }
+                None => unresolved(),
+            }
+        },
+        fallible: false,
+    }
+}
+
+pub(crate) fn vec_push<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    value: Value,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Vec<Value>, SAFE>,
+    Value: 'static,
+{
+    modify(path, ChangeKind::VecPush, move |vector: &mut Vec<Value>| vector.push(value))
+}
+
+pub(crate) fn vec_remove<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    id: Value::Id,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Vec<Value>, SAFE>,
+    Value: VecItem + 'static,
+{
+    modify(path, ChangeKind::VecRemove, move |vector: &mut Vec<Value>| {
+        vector.retain(|item| item.get_id() != id)
+    })
+}
+
+pub(crate) fn vec_remove_with<State, Path, Value, F, const SAFE: bool>(
+    path: Path,
+    id: Value::Id,
+    callback: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Vec<Value>, SAFE>,
+    Value: VecItem + 'static,
+    F: FnOnce(Vec<Value>) + 'static,
+{
+    modify_with_callback(
+        path,
+        ChangeKind::VecRemove,
+        move |vector: &mut Vec<Value>| {
+            let (removed, kept) = std::mem::take(vector).into_iter().partition(|item| item.get_id() == id);
+            *vector = kept;
+            removed
+        },
+        callback,
+    )
+}
+
+pub(crate) fn vec_clear<State, Path, Value, const SAFE: bool>(
+    path: Path,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Vec<Value>, SAFE>,
+    Value: 'static,
+{
+    modify(path, ChangeKind::VecClear, Vec::clear)
+}
+
+pub(crate) fn vec_sort_by<State, Path, Value, F, const SAFE: bool>(
+    path: Path,
+    comparator: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Vec<Value>, SAFE>,
+    Value: 'static,
+    F: FnMut(&Value, &Value) -> Ordering,
+{
+    modify(path, ChangeKind::VecSort, move |vector: &mut Vec<Value>| {
+        vector.sort_by(comparator)
+    })
+}
+
+pub(crate) fn vec_extend<State, Path, Value, Items, const SAFE: bool>(
+    path: Path,
+    items: Items,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Vec<Value>, SAFE>,
+    Value: 'static,
+    Items: IntoIterator<Item = Value>,
+{
+    modify(path, ChangeKind::VecExtend, move |vector: &mut Vec<Value>| vector.extend(items))
+}
+
+pub(crate) fn vec_retain<State, Path, Value, F, const SAFE: bool>(
+    path: Path,
+    predicate: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Vec<Value>, SAFE>,
+    Value: 'static,
+    F: FnMut(&Value) -> bool,
+{
+    modify(path, ChangeKind::VecRetain, move |vector: &mut Vec<Value>| {
+        vector.retain(predicate)
+    })
+}
+
+pub(crate) fn vec_swap_remove<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    id: Value::Id,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Vec<Value>, SAFE>,
+    Value: VecItem + 'static,
+{
+    modify(path, ChangeKind::VecRemove, move |vector: &mut Vec<Value>| {
+        if let Some(index) = vector.iter().position(|item| item.get_id() == id) {
+            vector.swap_remove(index);
+        }
+    })
+}
+
+pub(crate) fn vec_move<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    id: Value::Id,
+    new_index: usize,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Vec<Value>, SAFE>,
+    Value: VecItem + 'static,
+{
+    try_modify(path, ChangeKind::VecMove, move |vector: &mut Vec<Value>| {
+        let Some(index) = vector.iter().position(|item| item.get_id() == id) else {
+            return Err(MissingId::new::<Value>().into());
+        };
+
+        let item = vector.remove(index);
+        vector.insert(new_index.min(vector.len()), item);
+        Ok(())
+    })
+}
+
+pub(crate) fn vec_dedup<State, Path, Value, const SAFE: bool>(
+    path: Path,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Vec<Value>, SAFE>,
+    Value: VecItem + 'static,
+{
+    modify(path, ChangeKind::VecDedup, |vector: &mut Vec<Value>| {
+        let mut seen = HashSet::with_capacity(vector.len());
+        vector.retain(|item| seen.insert(item.get_id()));
+    })
+}
+
+pub(crate) fn vec_truncate<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    len: usize,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Vec<Value>, SAFE>,
+    Value: 'static,
+{
+    modify(path, ChangeKind::VecTruncate, move |vector: &mut Vec<Value>| {
+        vector.truncate(len)
+    })
+}
+
+pub(crate) fn vec_pop<State, Path, Value, F, const SAFE: bool>(
+    path: Path,
+    callback: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Vec<Value>, SAFE>,
+    Value: 'static,
+    F: FnOnce(Option<Value>) + 'static,
+{
+    modify_with_callback(path, ChangeKind::VecPop, Vec::pop, callback)
+}
+
+pub(crate) fn vec_push_unique<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    value: Value,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Vec<Value>, SAFE>,
+    Value: VecItem + 'static,
+{
+    try_modify(path, ChangeKind::VecPush, move |vector: &mut Vec<Value>| {
+        let id = value.get_id();
+
+        if vector.iter().any(|item| item.get_id() == id) {
+            return Err(DuplicateId::new::<Value>().into());
+        }
+
+        vector.push(value);
+        Ok(())
+    })
+}
+
+pub(crate) fn string_push_str<State, Path, const SAFE: bool>(
+    path: Path,
+    string: String,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, String, SAFE>,
+{
+    modify(path, ChangeKind::StringEdit, move |target: &mut String| {
+        target.push_str(&string)
+    })
+}
+
+pub(crate) fn string_clear<State, Path, const SAFE: bool>(path: Path) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, String, SAFE>,
+{
+    modify(path, ChangeKind::StringEdit, String::clear)
+}
+
+pub(crate) fn string_replace_range<State, Path, const SAFE: bool>(
+    path: Path,
+    range: Range<usize>,
+    replacement: String,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, String, SAFE>,
+{
+    PathChange {
+        record: Change::new::<Path, String>(ChangeKind::StringEdit),
+        apply: move |state: &mut State, _: &mut Callbacks| match path.follow_mut(state) {
+            Some(target) if target.get(range.clone()).is_some() => {
+                target.replace_range(range, &replacement);
+                Ok(())
+            }
+            _ => unresolved(),
+        },
+        fallible: false,
+    }
+}
+
+pub(crate) fn flag_set<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    flag: Value,
+    value: bool,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Value, SAFE>,
+    Value: Flags,
+{
+    modify(path, ChangeKind::UpdateValue, move |flags: &mut Value| {
+        flags.set_flag(flag, value)
+    })
+}
+
+pub(crate) fn flag_toggle<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    flag: Value,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Value, SAFE>,
+    Value: Flags,
+{
+    modify(path, ChangeKind::UpdateValue, move |flags: &mut Value| flags.toggle_flag(flag))
+}
+
+pub(crate) fn map_insert<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    id: Value::Id,
+    value: Value,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+    Value: MapItem + 'static,
+{
+    modify(path, ChangeKind::MapInsert, move |map: &mut HashMap<Value::Id, Value>| {
+        map.insert(id, value);
+    })
+}
+
+pub(crate) fn map_insert_default<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    id: Value::Id,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+    Value: MapItem + Default + 'static,
+{
+    modify(path, ChangeKind::MapInsert, move |map: &mut HashMap<Value::Id, Value>| {
+        map.entry(id).or_default();
+    })
+}
+
+pub(crate) fn map_replace<State, Path, Value, F, const SAFE: bool>(
+    path: Path,
+    id: Value::Id,
+    value: Value,
+    callback: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+    Value: MapItem + 'static,
+    F: FnOnce(Option<Value>) + 'static,
+{
+    modify_with_callback(
+        path,
+        ChangeKind::MapInsert,
+        move |map: &mut HashMap<Value::Id, Value>| map.insert(id, value),
+        callback,
+    )
+}
+
+pub(crate) fn map_remove<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    id: Value::Id,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+    Value: MapItem + 'static,
+{
+    modify(path, ChangeKind::MapRemove, move |map: &mut HashMap<Value::Id, Value>| {
+        map.remove(&id);
+    })
+}
+
+pub(crate) fn map_clear<State, Path, Value, const SAFE: bool>(
+    path: Path,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+    Value: MapItem + 'static,
+{
+    modify(path, ChangeKind::MapClear, HashMap::clear)
+}
+
+pub(crate) fn map_retain<State, Path, Value, F, const SAFE: bool>(
+    path: Path,
+    predicate: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+    Value: MapItem + 'static,
+    F: FnMut(&Value::Id, &mut Value) -> bool,
+{
+    modify(path, ChangeKind::MapRetain, move |map: &mut HashMap<Value::Id, Value>| {
+        map.retain(predicate)
+    })
+}
+
+pub(crate) fn map_get_or_insert_with<State, Path, Value, F, const SAFE: bool>(
+    path: Path,
+    id: Value::Id,
+    create: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+    Value: MapItem + 'static,
+    F: FnOnce() -> Value,
+{
+    modify(path, ChangeKind::MapInsert, move |map: &mut HashMap<Value::Id, Value>| {
+        map.entry(id).or_insert_with(create);
+    })
+}
+
+pub(crate) fn map_update<State, Path, Value, F, const SAFE: bool>(
+    path: Path,
+    id: Value::Id,
+    closure: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+    Value: MapItem + 'static,
+    F: FnOnce(&mut Value),
+{
+    try_modify(
+        path,
+        ChangeKind::MapUpdate,
+        move |map: &mut HashMap<Value::Id, Value>| match map.get_mut(&id) {
+            Some(value) => {
+                closure(value);
+                Ok(())
+            }
+            None => Err(MissingId::new::<Value>().into()),
+        },
+    )
+}
+
+pub(crate) fn btree_insert<State, Path, Key, Value, const SAFE: bool>(
+    path: Path,
+    key: Key,
+    value: Value,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, BTreeMap<Key, Value>, SAFE>,
+    Key: Ord + 'static,
+    Value: 'static,
+{
+    modify(path, ChangeKind::MapInsert, move |map: &mut BTreeMap<Key, Value>| {
+        map.insert(key, value);
+    })
+}
+
+pub(crate) fn btree_remove<State, Path, Key, Value, const SAFE: bool>(
+    path: Path,
+    key: Key,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, BTreeMap<Key, Value>, SAFE>,
+    Key: Ord + 'static,
+    Value: 'static,
+{
+    modify(path, ChangeKind::MapRemove, move |map: &mut BTreeMap<Key, Value>| {
+        map.remove(&key);
+    })
+}
+
+pub(crate) fn set_insert<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    value: Value,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, HashSet<Value>, SAFE>,
+    Value: Eq + Hash + 'static,
+{
+    modify(path, ChangeKind::SetInsert, move |set: &mut HashSet<Value>| {
+        set.insert(value);
+    })
+}
+
+pub(crate) fn set_remove<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    value: Value,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, HashSet<Value>, SAFE>,
+    Value: Eq + Hash + 'static,
+{
+    modify(path, ChangeKind::SetRemove, move |set: &mut HashSet<Value>| {
+        set.remove(&value);
+    })
+}
+
+pub(crate) fn set_clear<State, Path, Value, const SAFE: bool>(
+    path: Path,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, HashSet<Value>, SAFE>,
+    Value: 'static,
+{
+    modify(path, ChangeKind::SetClear, HashSet::clear)
+}
+
+#[cfg(feature = "slotmap")]
+pub(crate) fn slotmap_insert<State, Path, K, Value, F, const SAFE: bool>(
+    path: Path,
+    value: Value,
+    callback: F,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, SlotMap<K, Value>, SAFE>,
+    K: Key + 'static,
+    Value: 'static,
+    F: FnOnce(K) + 'static,
+{
+    modify_with_callback(
+        path,
+        ChangeKind::MapInsert,
+        move |map: &mut SlotMap<K, Value>| map.insert(value),
+        callback,
+    )
+}
+
+#[cfg(feature = "slotmap")]
+pub(crate) fn slotmap_remove<State, Path, K, Value, const SAFE: bool>(
+    path: Path,
+    key: K,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, SlotMap<K, Value>, SAFE>,
+    K: Key + 'static,
+    Value: 'static,
+{
+    modify(path, ChangeKind::MapRemove, move |map: &mut SlotMap<K, Value>| {
+        map.remove(key);
+    })
+}
+
+pub(crate) fn map_vec_push<State, Path, Key, Value, const SAFE: bool>(
+    path: Path,
+    key: Key,
+    value: Value,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, HashMap<Key, Vec<Value>>, SAFE>,
+    Key: Eq + Hash + 'static,
+    Value: 'static,
+{
+    modify(path, ChangeKind::VecPush, move |map: &mut HashMap<Key, Vec<Value>>| {
+        map.entry(key).or_default().push(value)
+    })
+}
+
+pub(crate) fn btree_set_insert<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    value: Value,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, BTreeSet<Value>, SAFE>,
+    Value: Ord + 'static,
+{
+    modify(path, ChangeKind::SetInsert, move |set: &mut BTreeSet<Value>| {
+        set.insert(value);
+    })
+}
+
+pub(crate) fn btree_set_remove<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    value: Value,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, BTreeSet<Value>, SAFE>,
+    Value: Ord + 'static,
+{
+    modify(path, ChangeKind::SetRemove, move |set: &mut BTreeSet<Value>| {
+        set.remove(&value);
+    })
+}
+
+pub(crate) fn insert_extension<State, Path, Value, const SAFE: bool>(
+    path: Path,
+    value: Value,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Extensions, SAFE>,
+    Value: 'static,
+{
+    modify(path, ChangeKind::UpdateValue, move |extensions: &mut Extensions| {
+        extensions.insert(value);
+    })
+}
+
+pub(crate) fn remove_extension<State, Value, Path, const SAFE: bool>(
+    path: Path,
+) -> PathChange<impl FnOnce(&mut State, &mut Callbacks) -> ChangeResult>
+where
+    Path: crate::Path<State, Extensions, SAFE>,
+    Value: 'static,
+{
+    modify(path, ChangeKind::UpdateValue, |extensions: &mut Extensions| {
+        extensions.remove::<Value>();
+    })
+}
@@ -0,0 +1,171 @@
+//! Module providing the [`ChangeSet`], which describes the changes made by a
+//! call to [`Context::apply`](crate::Context::apply).
+
+use std::any::TypeId;
+
+/// The kind of a recorded [`Change`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// The value at the path was updated, for example with
+    /// [`Context::update_value`](crate::Context::update_value).
+    UpdateValue,
+    /// An item was pushed to the [`Vec`] at the path.
+    VecPush,
+    /// An item was removed from the [`Vec`] at the path.
+    VecRemove,
+    /// The [`Vec`] at the path was cleared.
+    VecClear,
+    /// The [`Vec`] at the path was sorted.
+    VecSort,
+    /// Multiple items were appended to the [`Vec`] at the path.
+    VecExtend,
+    /// Items matching a predicate were kept in the [`Vec`] at the path.
+    VecRetain,
+    /// An item was moved to a different index in the [`Vec`] at the path.
+    VecMove,
+    /// Items with duplicate ids were removed from the [`Vec`] at the path.
+    VecDedup,
+    /// The [`Vec`] at the path was truncated.
+    VecTruncate,
+    /// The last item was removed from the [`Vec`] at the path.
+    VecPop,
+    /// The string at the path was edited.
+    StringEdit,
+    /// An item was inserted into the map at the path.
+    MapInsert,
+    /// An item was removed from the map at the path.
+    MapRemove,
+    /// The map at the path was cleared.
+    MapClear,
+    /// Items matching a predicate were kept in the map at the path.
+    MapRetain,
+    /// An item in the map at the path was updated.
+    MapUpdate,
+    /// A value was inserted into the set at the path.
+    SetInsert,
+    /// A value was removed from the set at the path.
+    SetRemove,
+    /// All values were removed from the set at the path.
+    SetClear,
+    /// The entire state was updated with
+    /// [`Context::update_root`](crate::Context::update_root).
+    Root,
+}
+
+/// A single change recorded in a [`ChangeSet`].
+#[derive(Clone, Copy, Debug)]
+pub struct Change {
+    kind: ChangeKind,
+    path_type: Option<TypeId>,
+    target_type_name: &'static str,
+}
+
+impl Change {
+    pub(crate) fn new<Path: 'static, Target: ?Sized>(kind: ChangeKind) -> Self {
+        Self {
+            kind,
+            path_type: Some(TypeId::of::<Path>()),
+            target_type_name: std::any::type_name::<Target>(),
+        }
+    }
+
+    pub(crate) fn root<State>() -> Self {
+        Self {
+            kind: ChangeKind::Root,
+            path_type: None,
+            target_type_name: std::any::type_name::<State>(),
+        }
+    }
+
+    /// Get the kind of the change.
+    pub fn kind(&self) -> ChangeKind {
+        self.kind
+    }
+
+    /// Get the name of the type the path points to, for example
+    /// `alloc::vec::Vec<u32>` for [`ChangeKind::VecPush`].
+    pub fn target_type_name(&self) -> &'static str {
+        self.target_type_name
+    }
+
+    /// Check if the change was made through a path of the same type as `path`.
+    ///
+    /// Every derived path has its own type, but dynamic lookups into the same
+    /// collection share a type, regardless of the id they look up.
+    pub fn is_path<Path: 'static>(&self, _path: Path) -> bool {
+        self.path_type == Some(TypeId::of::<Path>())
+    }
+}
+
+/// The changes made by a call to [`Context::apply`](crate::Context::apply), in
+/// the order they were applied.
+///
+/// Changes are only recorded if enabled with
+/// [`Context::set_record_changes`](crate::Context::set_record_changes).
+/// Changes that returned an error or were rejected by a validator are not
+/// recorded, and neither are [`Command`](crate::Command)s. All changes of a
+/// [`Batch`](crate::Batch) or [`ChangeBatch`](crate::ChangeBatch) are recorded
+/// if the batch applied without an error.
+///
+/// Example:
+/// ```
+/// use rust_state::{ChangeKind, Context, RustState};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct MyState {
+///     name: String,
+///     values: Vec<u32>,
+/// }
+///
+/// let mut context = Context::new(MyState {
+///     name: "Before".to_owned(),
+///     values: Vec::new(),
+/// });
+///
+/// context.set_record_changes(true);
+/// context.vec_push(MyState::path().values(), 10);
+///
+/// let report = context.apply();
+/// let change_set = report.change_set();
+///
+/// assert_eq!(change_set.len(), 1);
+/// assert_eq!(change_set.iter().next().unwrap().kind(), ChangeKind::VecPush);
+/// assert!(change_set.contains_path(MyState::path().values()));
+/// assert!(!change_set.contains_path(MyState::path().name()));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ChangeSet {
+    changes: Vec<Change>,
+}
+
+impl ChangeSet {
+    pub(crate) fn push(&mut self, change: Change) {
+        self.changes.push(change);
+    }
+
+    pub(crate) fn extend(&mut self, other: ChangeSet) {
+        self.changes.extend(other.changes);
+    }
+
+    /// Iterate over all recorded changes.
+    pub fn iter(&self) -> impl Iterator<Item = &Change> {
+        self.changes.iter()
+    }
+
+    /// Get the number of recorded changes.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Check if no changes were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Check if any change was made through a path of the same type as `path`.
+    /// See [`Change::is_path`].
+    pub fn contains_path<Path: Copy + 'static>(&self, path: Path) -> bool {
+        self.changes.iter().any(|change| change.is_path(path))
+    }
+}
@@ -0,0 +1,301 @@
+//! Module providing the [`Command`] trait for changes that can be undone.
+//!
+//! Commands are queued using [`Context::execute`](crate::Context::execute).
+//! Once applied, they are recorded in the history of the context and can be
+//! reverted using [`Context::undo`](crate::Context::undo) and re-applied
+//! using [`Context::redo`](crate::Context::redo).
+
+use std::any::Any;
+use std::error::Error;
+use std::marker::PhantomData;
+
+use crate::UnresolvedPath;
+
+/// A change to the state that can be reverted.
+///
+/// Example:
+/// ```
+/// use std::error::Error;
+///
+/// use rust_state::{Command, Context, RustState};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct MyState {
+///     counter: u32,
+/// }
+///
+/// struct Increment;
+///
+/// impl Command<MyState> for Increment {
+///     fn apply(&mut self, state: &mut MyState) -> Result<(), Box<dyn Error>> {
+///         state.counter += 1;
+///         Ok(())
+///     }
+///
+///     fn revert(&mut self, state: &mut MyState) -> Result<(), Box<dyn Error>> {
+///         state.counter -= 1;
+///         Ok(())
+///     }
+/// }
+///
+/// let mut context = Context::new(MyState { counter: 0 });
+/// let counter_path = MyState::path().counter();
+///
+/// context.execute(Increment);
+/// context.execute(Increment);
+/// context.apply();
+///
+/// assert_eq!(context.get(&counter_path), &2);
+///
+/// context.undo();
+/// context.apply();
+///
+/// assert_eq!(context.get(&counter_path), &1);
+///
+/// context.redo();
+/// context.apply();
+///
+/// assert_eq!(context.get(&counter_path), &2);
+/// ```
+pub trait Command<State>: Any {
+    /// Apply the command to the state.
+    ///
+    /// The command is only added to the history if it applied successfully.
+    fn apply(&mut self, state: &mut State) -> Result<(), Box<dyn Error>>;
+
+    /// Revert the command. This is only called after
+    /// [`apply`](Command::apply) succeeded.
+    ///
+    /// If reverting fails, the command stays on the undo stack.
+    fn revert(&mut self, state: &mut State) -> Result<(), Box<dyn Error>>;
+}
+
+/// [`Command`] that sets the value for a given path.
+///
+/// The previous value is stored in the command when it is applied and
+/// restored when it is reverted.
+///
+/// Example:
+/// ```
+/// use rust_state::{Context, RustState, SetValue};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct MyState {
+///     name: String,
+/// }
+///
+/// let mut context = Context::new(MyState { name: "Before".to_owned() });
+/// let name_path = MyState::path().name();
+///
+/// context.execute(SetValue::new(name_path, "After".to_owned()));
+/// context.apply();
+///
+/// assert_eq!(context.get(&name_path), "After");
+///
+/// context.undo();
+/// context.apply();
+///
+/// assert_eq!(context.get(&name_path), "Before");
+/// ```
+///
+/// If the path can't be followed, the command fails and is not added to the
+/// history:
+/// ```
+/// use rust_state::{Context, OptionExt, RustState, SetValue};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct MyState {
+///     name: Option<String>,
+/// }
+///
+/// let mut context = Context::new(MyState { name: None });
+/// let name_path = MyState::path().name().unwrapped();
+///
+/// context.execute(SetValue::new(name_path, "After".to_owned()));
+/// let report = context.apply();
+///
+/// assert!(report.has_errors());
+/// assert!(!context.can_undo());
+/// ```
+pub struct SetValue<State, Path, Value, const SAFE: bool> {
+    path: Path,
+    value: Value,
+    _marker: PhantomData<State>,
+}
+
+impl<State, Path, Value, const SAFE: bool> SetValue<State, Path, Value, SAFE>
+where
+    Path: crate::Path<State, Value, SAFE>,
+{
+    /// Create a new command setting the value for the given path.
+    pub fn new(path: Path, value: Value) -> Self {
+        Self {
+            path,
+            value,
+            _marker: PhantomData,
+        }
+    }
+
+    fn swap(&mut self, state: &mut State) -> Result<(), Box<dyn Error>> {
+        let reference = self.path.follow_mut(state).ok_or(UnresolvedPath::new::<Value>())?;
+        std::mem::swap(reference, &mut self.value);
+        Ok(())
+    }
+}
+
+impl<State, Path, Value, const SAFE: bool> Command<State> for SetValue<State, Path, Value, SAFE>
+where
+    State: 'static,
+    Path: crate::Path<State, Value, SAFE>,
+    Value: 'static,
+{
+    fn apply(&mut self, state: &mut State) -> Result<(), Box<dyn Error>> {
+        self.swap(state)
+    }
+
+    fn revert(&mut self, state: &mut State) -> Result<(), Box<dyn Error>> {
+        self.swap(state)
+    }
+}
+
+/// Applied commands that can be undone and reverted commands that can be
+/// redone.
+pub(crate) struct History<State> {
+    undo_stack: Vec<Box<dyn Command<State>>>,
+    redo_stack: Vec<Box<dyn Command<State>>>,
+}
+
+impl<State> Default for History<State> {
+    fn default() -> Self {
+        Self {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+        }
+    }
+}
+
+/// A [`Command`] that was applied or reverted, but isn't moved in the
+/// [`History`] yet. It's only recorded once the resulting state passed
+/// validation, so a rejected change leaves the history untouched.
+pub(crate) enum HistoryChange<State> {
+    Executed(Box<dyn Command<State>>),
+    Undone(Box<dyn Command<State>>),
+    Redone(Box<dyn Command<State>>),
+}
+
+impl<State: 'static> History<State> {
+    /// Revert the last command. If that fails, it stays on the undo stack.
+    pub(crate) fn undo(&mut self, state: &mut State) -> Result<Option<HistoryChange<State>>, Box<dyn Error>> {
+        let Some(mut command) = self.undo_stack.pop() else {
+            return Ok(None);
+        };
+
+        match command.revert(state) {
+            Ok(()) => Ok(Some(HistoryChange::Undone(command))),
+            Err(error) => {
+                self.undo_stack.push(command);
+                Err(error)
+            }
+        }
+    }
+
+    /// Re-apply the last reverted command. If that fails, it stays on the redo
+    /// stack.
+    pub(crate) fn redo(&mut self, state: &mut State) -> Result<Option<HistoryChange<State>>, Box<dyn Error>> {
+        let Some(mut command) = self.redo_stack.pop() else {
+            return Ok(None);
+        };
+
+        match command.apply(state) {
+            Ok(()) => Ok(Some(HistoryChange::Redone(command))),
+            Err(error) => {
+                self.redo_stack.push(command);
+                Err(error)
+            }
+        }
+    }
+
+    /// Record a change to the history once the state it produced was
+    /// accepted.
+    pub(crate) fn commit(&mut self, change: HistoryChange<State>) {
+        match change {
+            HistoryChange::Executed(command) => {
+                self.undo_stack.push(command);
+                self.redo_stack.clear();
+            }
+            HistoryChange::Undone(command) => self.redo_stack.push(command),
+            HistoryChange::Redone(command) => self.undo_stack.push(command),
+        }
+    }
+
+    /// Discard a change whose state was rejected. Undone and redone commands
+    /// are moved back to where they came from.
+    pub(crate) fn discard(&mut self, change: HistoryChange<State>) {
+        match change {
+            HistoryChange::Executed(_) => {}
+            HistoryChange::Undone(command) => self.undo_stack.push(command),
+            HistoryChange::Redone(command) => self.redo_stack.push(command),
+        }
+    }
+
+    /// Move the commands executed on another history, like the scratch history
+    /// of a preview, onto this one, as if they were executed here.
+    pub(crate) fn append(&mut self, other: History<State>) {
+        if !other.undo_stack.is_empty() {
+            self.undo_stack.extend(other.undo_stack);
+            self.redo_stack.clear();
+        }
+    }
+
+    pub(crate) fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+    }
+
+    /// Clone the commands that can be undone and redone. Returns `None` if any
+    /// of them is not a `C`.
+    #[cfg(feature = "serde")]
+    pub(crate) fn export<C>(&self) -> Option<(Vec<C>, Vec<C>)>
+    where
+        C: Command<State> + Clone,
+    {
+        let export = |stack: &[Box<dyn Command<State>>]| {
+            stack
+                .iter()
+                .map(|command| (&**command as &dyn Any).downcast_ref::<C>().cloned())
+                .collect::<Option<Vec<_>>>()
+        };
+
+        Some((export(&self.undo_stack)?, export(&self.redo_stack)?))
+    }
+
+    /// Create a history from exported commands.
+    #[cfg(feature = "serde")]
+    pub(crate) fn import<C>(undo_stack: Vec<C>, redo_stack: Vec<C>) -> Self
+    where
+        C: Command<State>,
+    {
+        let import = |stack: Vec<C>| {
+            stack
+                .into_iter()
+                .map(|command| Box::new(command) as Box<dyn Command<State>>)
+                .collect()
+        };
+
+        Self {
+            undo_stack: import(undo_stack),
+            redo_stack: import(redo_stack),
+        }
+    }
+}
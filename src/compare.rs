@@ -0,0 +1,125 @@
+//! Module providing an extension trait to compare the target of a path with a
+//! constant.
+//!
+//! The comparisons are boolean selectors, so widgets that only need to know
+//! if a value matches don't have to borrow and compare it themselves. To
+//! check if a collection is empty, see
+//! [`LenExt::is_empty`](crate::LenExt::is_empty).
+//!
+//! Example:
+//! ```
+//! use rust_state::{CompareExt, Context, RustState};
+//!
+//! #[derive(Clone, Copy, PartialEq, Eq)]
+//! enum Tab {
+//!     General,
+//!     Audio,
+//! }
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     tab: Tab,
+//!     health: u32,
+//! }
+//!
+//! let mut context = Context::new(State { tab: Tab::General, health: 50 });
+//!
+//! let audio_selected = State::path().tab().eq_to(Tab::Audio);
+//! let is_healthy = State::path().health().greater_than(25);
+//! let is_critical = State::path().health().less_than(10);
+//!
+//! assert!(!*context.get(&audio_selected));
+//! assert!(*context.get(&is_healthy));
+//! assert!(!*context.get(&is_critical));
+//!
+//! context.update_value(State::path().tab(), Tab::Audio);
+//! context.update_value(State::path().health(), 5);
+//! context.apply();
+//!
+//! assert!(*context.get(&audio_selected));
+//! assert!(!*context.get(&is_healthy));
+//! assert!(*context.get(&is_critical));
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{PathRead, Selector};
+
+/// A boolean selector comparing the target of a path with a constant.
+///
+/// This type is not accessible outside this module, instead [`CompareExt`]
+/// can be used to construct it and receive an `impl Selector<State, bool>`.
+struct Comparison<State, InnerPath, To, const SAFE: bool> {
+    path: InnerPath,
+    value: To,
+    compare: fn(&To, &To) -> bool,
+    _marker: PhantomData<State>,
+}
+
+impl<State, InnerPath, To, const SAFE: bool> Selector<State, bool, SAFE> for Comparison<State, InnerPath, To, SAFE>
+where
+    State: 'static,
+    InnerPath: PathRead<State, To, SAFE>,
+    To: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a bool> {
+        let matches = (self.compare)(self.path.follow(state)?, &self.value);
+        Some(if matches { &true } else { &false })
+    }
+}
+
+pub trait CompareExt<State, To, const SAFE: bool>
+where
+    State: 'static,
+    Self: PathRead<State, To, SAFE>,
+    To: 'static,
+{
+    /// Selector that resolves to `true` if the target is equal to `value`.
+    fn eq_to(self, value: To) -> impl Selector<State, bool, SAFE>
+    where
+        To: PartialEq,
+    {
+        Comparison {
+            path: self,
+            value,
+            compare: PartialEq::eq,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Selector that resolves to `true` if the target is greater than
+    /// `value`.
+    fn greater_than(self, value: To) -> impl Selector<State, bool, SAFE>
+    where
+        To: PartialOrd,
+    {
+        Comparison {
+            path: self,
+            value,
+            compare: PartialOrd::gt,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Selector that resolves to `true` if the target is less than `value`.
+    fn less_than(self, value: To) -> impl Selector<State, bool, SAFE>
+    where
+        To: PartialOrd,
+    {
+        Comparison {
+            path: self,
+            value,
+            compare: PartialOrd::lt,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, To, const SAFE: bool> CompareExt<State, To, SAFE> for T
+where
+    State: 'static,
+    T: PathRead<State, To, SAFE>,
+    To: 'static,
+{
+}
@@ -0,0 +1,165 @@
+//! Module providing an extension trait to compose two paths.
+//!
+//! A path from `State` to `Mid` can be composed with a path from `Mid` to `To`
+//! to get a path from `State` to `To`. This allows defining paths relative to
+//! an inner type and attaching them to any path that resolves to that type.
+//! The composed path is only safe if both paths are safe.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, OptionExt, PathComposeExt, RustState};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     left: Settings,
+//!     right: Settings,
+//! }
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct Settings {
+//!     volume: u32,
+//!     preset: Option<u32>,
+//! }
+//!
+//! let context = Context::new(State {
+//!     left: Settings { volume: 10, preset: None },
+//!     right: Settings { volume: 20, preset: Some(3) },
+//! });
+//!
+//! let volume_path = Settings::path().volume();
+//! let left_volume = State::path().left().then(volume_path);
+//! let right_volume = State::path().right().then(volume_path);
+//!
+//! assert_eq!(context.get(&left_volume), &10);
+//! assert_eq!(context.get(&right_volume), &20);
+//!
+//! let preset_path = Settings::path().preset().unwrapped();
+//! let left_preset = State::path().left().then(preset_path);
+//! let right_preset = State::path().right().then(preset_path);
+//!
+//! assert_eq!(context.try_get(&left_preset), None);
+//! assert_eq!(context.try_get(&right_preset), Some(&3));
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// Two paths composed into one.
+///
+/// [`PathComposeExt::then`] is used to construct it. It implements
+/// `Path<State, To, true>` if both paths are safe and `Path<State, To, false>`
+/// otherwise.
+pub struct ComposedPath<State, First, Second, Mid, To: ?Sized, const FIRST_SAFE: bool, const SECOND_SAFE: bool> {
+    first: First,
+    second: Second,
+    _marker: PhantomData<(State, Mid, To)>,
+}
+
+impl<State, First, Second, Mid, To: ?Sized, const FIRST_SAFE: bool, const SECOND_SAFE: bool> Clone
+    for ComposedPath<State, First, Second, Mid, To, FIRST_SAFE, SECOND_SAFE>
+where
+    First: Path<State, Mid, FIRST_SAFE>,
+    Second: Path<Mid, To, SECOND_SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            first: self.first.clone(),
+            second: self.second.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, First, Second, Mid, To: ?Sized, const FIRST_SAFE: bool, const SECOND_SAFE: bool> Copy
+    for ComposedPath<State, First, Second, Mid, To, FIRST_SAFE, SECOND_SAFE>
+where
+    First: Path<State, Mid, FIRST_SAFE> + Copy,
+    Second: Path<Mid, To, SECOND_SAFE> + Copy,
+{
+}
+
+macro_rules! implement_composed_path {
+    ($first_safe:literal, $second_safe:literal => $safe:literal) => {
+        impl<State, First, Second, Mid, To> Selector<State, To, $safe>
+            for ComposedPath<State, First, Second, Mid, To, $first_safe, $second_safe>
+        where
+            State: 'static,
+            First: Path<State, Mid, $first_safe>,
+            Second: Path<Mid, To, $second_safe>,
+            Mid: 'static,
+            To: ?Sized + 'static,
+        {
+            fn select<'a>(&'a self, state: &'a State) -> Option<&'a To> {
+                self.follow(state)
+            }
+        }
+
+        impl<State, First, Second, Mid, To> PathRead<State, To, $safe>
+            for ComposedPath<State, First, Second, Mid, To, $first_safe, $second_safe>
+        where
+            State: 'static,
+            First: Path<State, Mid, $first_safe>,
+            Second: Path<Mid, To, $second_safe>,
+            Mid: 'static,
+            To: ?Sized + 'static,
+        {
+            fn follow<'a>(&self, state: &'a State) -> Option<&'a To> {
+                self.second.follow(self.first.follow(state)?)
+            }
+
+            fn key(&self) -> Option<PathKey> {
+                Some(PathKey::new::<Self>().with(self.first.key()?).with(self.second.key()?))
+            }
+        }
+
+        impl<State, First, Second, Mid, To> PathWrite<State, To, $safe>
+            for ComposedPath<State, First, Second, Mid, To, $first_safe, $second_safe>
+        where
+            State: 'static,
+            First: Path<State, Mid, $first_safe>,
+            Second: Path<Mid, To, $second_safe>,
+            Mid: 'static,
+            To: ?Sized + 'static,
+        {
+            fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut To> {
+                self.second.follow_mut(self.first.follow_mut(state)?)
+            }
+        }
+    };
+}
+
+implement_composed_path!(true, true => true);
+implement_composed_path!(true, false => false);
+implement_composed_path!(false, true => false);
+implement_composed_path!(false, false => false);
+
+pub trait PathComposeExt<State, Mid, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Mid, SAFE>,
+    Mid: 'static,
+{
+    /// Compose this path with a path that starts at its target.
+    fn then<Second, To, const SECOND_SAFE: bool>(self, second: Second) -> ComposedPath<State, Self, Second, Mid, To, SAFE, SECOND_SAFE>
+    where
+        Second: Path<Mid, To, SECOND_SAFE>,
+        To: ?Sized + 'static,
+    {
+        ComposedPath {
+            first: self,
+            second,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Mid, const SAFE: bool> PathComposeExt<State, Mid, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Mid, SAFE>,
+    Mid: 'static,
+{
+}
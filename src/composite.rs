@@ -0,0 +1,715 @@
+//! Module providing the [`CompositeContext`], which combines the contexts of
+//! two independent state roots behind a single API.
+//!
+//! Paths are dispatched to the correct context based on the root they start
+//! at.
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::hash::Hash;
+use std::ops::Range;
+
+#[cfg(feature = "slotmap")]
+use slotmap::{Key, SlotMap};
+
+#[cfg(feature = "sync")]
+use crate::lock::Lock;
+use crate::{ApplyReport, Cached, Context, Extensions, Flags, MapItem, VecItem, Versioned};
+
+/// Index of the first root of a [`CompositeContext`].
+pub struct FirstRoot;
+
+/// Index of the second root of a [`CompositeContext`].
+pub struct SecondRoot;
+
+/// Access to the [`Context`] of a state root.
+///
+/// `Index` is either [`FirstRoot`] or [`SecondRoot`] and is always inferred.
+/// It only exists so the implementations for both roots don't conflict.
+pub trait CompositeRoot<State, Index> {
+    /// Get the context of the state root.
+    fn context(&self) -> &Context<State>;
+
+    /// Get the context of the state root mutably.
+    fn context_mut(&mut self) -> &mut Context<State>;
+}
+
+/// Combines the [`Context`]s of two independent state roots.
+///
+/// Example:
+/// ```
+/// use rust_state::{CompositeContext, Context, RustState};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct Settings {
+///     volume: f32,
+/// }
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct Session {
+///     player_name: String,
+/// }
+///
+/// let mut context = CompositeContext::new(
+///     Context::new(Settings { volume: 0.5 }),
+///     Context::new(Session { player_name: "Player".to_owned() }),
+/// );
+///
+/// context.update_value(Settings::path().volume(), 1.0);
+/// context.update_value(Session::path().player_name(), "Renamed".to_owned());
+/// context.apply();
+///
+/// assert_eq!(context.get(&Settings::path().volume()), &1.0);
+/// assert_eq!(context.get(&Session::path().player_name()), "Renamed");
+/// ```
+pub struct CompositeContext<First, Second> {
+    first: Context<First>,
+    second: Context<Second>,
+}
+
+impl<First, Second> CompositeRoot<First, FirstRoot> for CompositeContext<First, Second> {
+    fn context(&self) -> &Context<First> {
+        &self.first
+    }
+
+    fn context_mut(&mut self) -> &mut Context<First> {
+        &mut self.first
+    }
+}
+
+impl<First, Second> CompositeRoot<Second, SecondRoot> for CompositeContext<First, Second> {
+    fn context(&self) -> &Context<Second> {
+        &self.second
+    }
+
+    fn context_mut(&mut self) -> &mut Context<Second> {
+        &mut self.second
+    }
+}
+
+impl<First: 'static, Second: 'static> CompositeContext<First, Second> {
+    /// Create a new composite context from the contexts of two state roots.
+    pub fn new(first: Context<First>, second: Context<Second>) -> Self {
+        Self { first, second }
+    }
+
+    /// Get the [`Context`] of one of the state roots, for functionality not
+    /// exposed on the composite context itself.
+    pub fn root<State, Index>(&self) -> &Context<State>
+    where
+        Self: CompositeRoot<State, Index>,
+    {
+        self.context()
+    }
+
+    /// Get the [`Context`] of one of the state roots mutably.
+    pub fn root_mut<State, Index>(&mut self) -> &mut Context<State>
+    where
+        Self: CompositeRoot<State, Index>,
+    {
+        self.context_mut()
+    }
+
+    /// See [`Context::get`].
+    pub fn get<'a, Selector, Output, State, Index>(&'a self, selector: &'a Selector) -> &'a Output
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Selector: crate::Selector<State, Output>,
+        Output: ?Sized,
+    {
+        self.context().get(selector)
+    }
+
+    /// See [`Context::try_get`].
+    pub fn try_get<'a, Selector, Output, State, Index>(&'a self, selector: &'a Selector) -> Option<&'a Output>
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Selector: crate::Selector<State, Output, false>,
+        Output: ?Sized,
+    {
+        self.context().try_get(selector)
+    }
+
+    /// See [`Context::update_value`].
+    pub fn update_value<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Value, SAFE>,
+        Value: 'static,
+    {
+        self.context().update_value(path, value);
+    }
+
+    /// See [`Context::update_value_with`].
+    pub fn update_value_with<Path, Value, F, State, Index, const SAFE: bool>(&self, path: Path, closure: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Value, SAFE>,
+        Value: 'static,
+        F: Fn(&mut Value) + 'static,
+    {
+        self.context().update_value_with(path, closure);
+    }
+
+    /// See [`Context::update_value_try`].
+    pub fn update_value_try<Path, Value, F, E, State, Index, const SAFE: bool>(&self, path: Path, closure: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Value, SAFE>,
+        Value: 'static,
+        F: FnOnce(&mut Value) -> Result<(), E> + 'static,
+        E: Error + 'static,
+    {
+        self.context().update_value_try(path, closure);
+    }
+
+    /// See [`Context::update_cell`].
+    pub fn update_cell<Path, Value, F, State, Index, const SAFE: bool>(&self, path: Path, closure: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, RefCell<Value>, SAFE>,
+        Value: 'static,
+        F: FnOnce(&mut Value) + 'static,
+    {
+        self.context().update_cell(path, closure);
+    }
+
+    /// See [`Context::update_locked`].
+    #[cfg(feature = "sync")]
+    pub fn update_locked<Path, Value, F, State, Index, const SAFE: bool>(&self, path: Path, closure: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Value, SAFE>,
+        Value: Lock + 'static,
+        F: FnOnce(&mut Value::Target) + 'static,
+    {
+        self.context().update_locked(path, closure);
+    }
+
+    /// See [`Context::update_each`].
+    pub fn update_each<Traversal, Value, F, State, Index>(&self, traversal: Traversal, closure: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Traversal: crate::Traversal<State, Value>,
+        Value: ?Sized + 'static,
+        F: FnMut(&mut Value) + 'static,
+    {
+        self.context().update_each(traversal, closure);
+    }
+
+    /// See [`Context::update_each_indexed`].
+    pub fn update_each_indexed<Traversal, Key, Value, F, State, Index>(&self, traversal: Traversal, closure: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Traversal: crate::IndexedTraversal<State, Key, Value>,
+        Key: 'static,
+        Value: ?Sized + 'static,
+        F: FnMut(&Key, &mut Value) + 'static,
+    {
+        self.context().update_each_indexed(traversal, closure);
+    }
+
+    /// See [`Context::take`].
+    pub fn take<Path, Value, F, State, Index, const SAFE: bool>(&self, path: Path, callback: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Value, SAFE>,
+        Value: Default + 'static,
+        F: FnOnce(Value) + 'static,
+    {
+        self.context().take(path, callback);
+    }
+
+    /// See [`Context::replace`].
+    pub fn replace<Path, Value, F, State, Index, const SAFE: bool>(&self, path: Path, value: Value, callback: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Value, SAFE>,
+        Value: 'static,
+        F: FnOnce(Value) + 'static,
+    {
+        self.context().replace(path, value, callback);
+    }
+
+    /// See [`Context::refresh_cache`].
+    pub fn refresh_cache<CachePath, SourcePath, Value, Source, F, State, Index, const CACHE_SAFE: bool, const SOURCE_SAFE: bool>(
+        &self,
+        cache_path: CachePath,
+        source_path: SourcePath,
+        compute: F,
+    ) where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        CachePath: crate::Path<State, Cached<Value>, CACHE_SAFE>,
+        SourcePath: crate::Path<State, Versioned<Source>, SOURCE_SAFE>,
+        Value: 'static,
+        Source: 'static,
+        F: FnOnce(&Source) -> Value + 'static,
+    {
+        self.context().refresh_cache(cache_path, source_path, compute);
+    }
+
+    /// See [`Context::vec_push`].
+    pub fn vec_push<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.context().vec_push(path, value);
+    }
+
+    /// See [`Context::vec_remove`].
+    pub fn vec_remove<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.context().vec_remove(path, id);
+    }
+
+    /// See [`Context::vec_remove_with`].
+    pub fn vec_remove_with<Path, Value, F, State, Index, const SAFE: bool>(&self, path: Path, id: Value::Id, callback: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+        F: FnOnce(Vec<Value>) + 'static,
+    {
+        self.context().vec_remove_with(path, id, callback);
+    }
+
+    /// See [`Context::vec_clear`].
+    pub fn vec_clear<Path, Value, State, Index, const SAFE: bool>(&self, path: Path)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.context().vec_clear(path);
+    }
+
+    /// See [`Context::vec_sort_by`].
+    pub fn vec_sort_by<Path, Value, F, State, Index, const SAFE: bool>(&self, path: Path, comparator: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+        F: FnMut(&Value, &Value) -> Ordering + 'static,
+    {
+        self.context().vec_sort_by(path, comparator);
+    }
+
+    /// See [`Context::vec_extend`].
+    pub fn vec_extend<Path, Value, Items, State, Index, const SAFE: bool>(&self, path: Path, items: Items)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+        Items: IntoIterator<Item = Value> + 'static,
+    {
+        self.context().vec_extend(path, items);
+    }
+
+    /// See [`Context::vec_retain`].
+    pub fn vec_retain<Path, Value, F, State, Index, const SAFE: bool>(&self, path: Path, predicate: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+        F: FnMut(&Value) -> bool + 'static,
+    {
+        self.context().vec_retain(path, predicate);
+    }
+
+    /// See [`Context::vec_swap_remove`].
+    pub fn vec_swap_remove<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.context().vec_swap_remove(path, id);
+    }
+
+    /// See [`Context::vec_move`].
+    pub fn vec_move<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, id: Value::Id, new_index: usize)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.context().vec_move(path, id, new_index);
+    }
+
+    /// See [`Context::vec_dedup`].
+    pub fn vec_dedup<Path, Value, State, Index, const SAFE: bool>(&self, path: Path)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.context().vec_dedup(path);
+    }
+
+    /// See [`Context::vec_truncate`].
+    pub fn vec_truncate<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, len: usize)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.context().vec_truncate(path, len);
+    }
+
+    /// See [`Context::vec_pop`].
+    pub fn vec_pop<Path, Value, F, State, Index, const SAFE: bool>(&self, path: Path, callback: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+        F: FnOnce(Option<Value>) + 'static,
+    {
+        self.context().vec_pop(path, callback);
+    }
+
+    /// See [`Context::vec_push_unique`].
+    pub fn vec_push_unique<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.context().vec_push_unique(path, value);
+    }
+
+    /// See [`Context::string_push_str`].
+    pub fn string_push_str<Path, State, Index, const SAFE: bool>(&self, path: Path, string: impl Into<String>)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, String, SAFE>,
+    {
+        self.context().string_push_str(path, string);
+    }
+
+    /// See [`Context::string_clear`].
+    pub fn string_clear<Path, State, Index, const SAFE: bool>(&self, path: Path)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, String, SAFE>,
+    {
+        self.context().string_clear(path);
+    }
+
+    /// See [`Context::string_replace_range`].
+    pub fn string_replace_range<Path, State, Index, const SAFE: bool>(
+        &self,
+        path: Path,
+        range: Range<usize>,
+        replacement: impl Into<String>,
+    ) where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, String, SAFE>,
+    {
+        self.context().string_replace_range(path, range, replacement);
+    }
+
+    /// See [`Context::flag_set`].
+    pub fn flag_set<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, flag: Value, value: bool)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Value, SAFE>,
+        Value: Flags,
+    {
+        self.context().flag_set(path, flag, value);
+    }
+
+    /// See [`Context::flag_toggle`].
+    pub fn flag_toggle<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, flag: Value)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Value, SAFE>,
+        Value: Flags,
+    {
+        self.context().flag_toggle(path, flag);
+    }
+
+    /// See [`Context::map_insert`].
+    pub fn map_insert<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, id: Value::Id, value: Value)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+    {
+        self.context().map_insert(path, id, value);
+    }
+
+    /// See [`Context::map_insert_default`].
+    pub fn map_insert_default<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + Default + 'static,
+    {
+        self.context().map_insert_default(path, id);
+    }
+
+    /// See [`Context::map_replace`].
+    pub fn map_replace<Path, Value, F, State, Index, const SAFE: bool>(&self, path: Path, id: Value::Id, value: Value, callback: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnOnce(Option<Value>) + 'static,
+    {
+        self.context().map_replace(path, id, value, callback);
+    }
+
+    /// See [`Context::map_remove`].
+    pub fn map_remove<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+    {
+        self.context().map_remove(path, id);
+    }
+
+    /// See [`Context::map_clear`].
+    pub fn map_clear<Path, Value, State, Index, const SAFE: bool>(&self, path: Path)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+    {
+        self.context().map_clear(path);
+    }
+
+    /// See [`Context::map_retain`].
+    pub fn map_retain<Path, Value, F, State, Index, const SAFE: bool>(&self, path: Path, predicate: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnMut(&Value::Id, &mut Value) -> bool + 'static,
+    {
+        self.context().map_retain(path, predicate);
+    }
+
+    /// See [`Context::map_get_or_insert_with`].
+    pub fn map_get_or_insert_with<Path, Value, F, State, Index, const SAFE: bool>(&self, path: Path, id: Value::Id, create: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnOnce() -> Value + 'static,
+    {
+        self.context().map_get_or_insert_with(path, id, create);
+    }
+
+    /// See [`Context::map_update`].
+    pub fn map_update<Path, Value, F, State, Index, const SAFE: bool>(&self, path: Path, id: Value::Id, closure: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnOnce(&mut Value) + 'static,
+    {
+        self.context().map_update(path, id, closure);
+    }
+
+    /// See [`Context::btree_insert`].
+    pub fn btree_insert<Path, Key, Value, State, Index, const SAFE: bool>(&self, path: Path, key: Key, value: Value)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, BTreeMap<Key, Value>, SAFE>,
+        Key: Ord + 'static,
+        Value: 'static,
+    {
+        self.context().btree_insert(path, key, value);
+    }
+
+    /// See [`Context::btree_remove`].
+    pub fn btree_remove<Path, Key, Value, State, Index, const SAFE: bool>(&self, path: Path, key: Key)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, BTreeMap<Key, Value>, SAFE>,
+        Key: Ord + 'static,
+        Value: 'static,
+    {
+        self.context().btree_remove(path, key);
+    }
+
+    /// See [`Context::set_insert`].
+    pub fn set_insert<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, HashSet<Value>, SAFE>,
+        Value: Eq + Hash + 'static,
+    {
+        self.context().set_insert(path, value);
+    }
+
+    /// See [`Context::set_remove`].
+    pub fn set_remove<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, HashSet<Value>, SAFE>,
+        Value: Eq + Hash + 'static,
+    {
+        self.context().set_remove(path, value);
+    }
+
+    /// See [`Context::set_clear`].
+    pub fn set_clear<Path, Value, State, Index, const SAFE: bool>(&self, path: Path)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, HashSet<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.context().set_clear(path);
+    }
+
+    /// See [`Context::slotmap_insert`].
+    #[cfg(feature = "slotmap")]
+    pub fn slotmap_insert<Path, K, Value, F, State, Index, const SAFE: bool>(&self, path: Path, value: Value, callback: F)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, SlotMap<K, Value>, SAFE>,
+        K: Key + 'static,
+        Value: 'static,
+        F: FnOnce(K) + 'static,
+    {
+        self.context().slotmap_insert(path, value, callback);
+    }
+
+    /// See [`Context::slotmap_remove`].
+    #[cfg(feature = "slotmap")]
+    pub fn slotmap_remove<Path, K, Value, State, Index, const SAFE: bool>(&self, path: Path, key: K)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, SlotMap<K, Value>, SAFE>,
+        K: Key + 'static,
+        Value: 'static,
+    {
+        self.context().slotmap_remove(path, key);
+    }
+
+    /// See [`Context::map_vec_push`].
+    pub fn map_vec_push<Path, Key, Value, State, Index, const SAFE: bool>(&self, path: Path, key: Key, value: Value)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, HashMap<Key, Vec<Value>>, SAFE>,
+        Key: Eq + Hash + 'static,
+        Value: 'static,
+    {
+        self.context().map_vec_push(path, key, value);
+    }
+
+    /// See [`Context::btree_set_insert`].
+    pub fn btree_set_insert<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, BTreeSet<Value>, SAFE>,
+        Value: Ord + 'static,
+    {
+        self.context().btree_set_insert(path, value);
+    }
+
+    /// See [`Context::btree_set_remove`].
+    pub fn btree_set_remove<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, BTreeSet<Value>, SAFE>,
+        Value: Ord + 'static,
+    {
+        self.context().btree_set_remove(path, value);
+    }
+
+    /// See [`Context::insert_extension_at`].
+    pub fn insert_extension_at<Path, Value, State, Index, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Extensions, SAFE>,
+        Value: 'static,
+    {
+        self.context().insert_extension_at(path, value);
+    }
+
+    /// See [`Context::remove_extension_at`].
+    pub fn remove_extension_at<Value, Path, State, Index, const SAFE: bool>(&self, path: Path)
+    where
+        Self: CompositeRoot<State, Index>,
+        State: 'static,
+        Path: crate::Path<State, Extensions, SAFE>,
+        Value: 'static,
+    {
+        self.context().remove_extension_at::<Value, Path, SAFE>(path);
+    }
+
+    /// Apply the pending changes of both contexts, first to second. Returns
+    /// the combined [`ApplyReport`].
+    pub fn apply(&mut self) -> ApplyReport {
+        let mut report = self.first.apply();
+        report.merge(self.second.apply());
+        report
+    }
+
+    /// Apply any pending changes and return both root states.
+    pub fn into_inner(self) -> (First, Second) {
+        (self.first.into_inner(), self.second.into_inner())
+    }
+}
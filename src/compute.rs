@@ -0,0 +1,107 @@
+//! Module providing [`Compute`], selectors that derive owned values from the
+//! state.
+
+use std::marker::PhantomData;
+
+use crate::Selector;
+
+/// A `Compute` derives an owned value from the state.
+///
+/// A [`Selector`] can only return references into the state, which rules out
+/// values that are not stored anywhere, like the length of a collection. A
+/// `Compute` returns the value itself instead and is read with
+/// [`Context::compute`](crate::Context::compute) and
+/// [`Context::try_compute`](crate::Context::try_compute).
+///
+/// Example:
+/// ```
+/// use rust_state::{Compute, Context, RustState};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct MyState {
+///     items: Vec<u32>,
+/// }
+///
+/// struct Sum;
+///
+/// impl Compute<MyState, u32> for Sum {
+///     fn compute(&self, state: &MyState) -> Option<u32> {
+///         Some(state.items.iter().sum())
+///     }
+/// }
+///
+/// let context = Context::new(MyState { items: vec![1, 2, 3] });
+///
+/// assert_eq!(context.compute(&Sum), 6);
+/// ```
+pub trait Compute<State, Output, const SAFE: bool = true>: 'static {
+    fn compute(&self, state: &State) -> Option<Output>;
+}
+
+/// A compute applying a function to the output of a selector.
+///
+/// This type is not accessible outside this module, instead
+/// [`SelectorComputeExt`] can be used to construct it and receive a `impl
+/// Compute<State, Output>`.
+struct ComputeWith<Inner, Function, To: ?Sized> {
+    selector: Inner,
+    function: Function,
+    _marker: PhantomData<To>,
+}
+
+impl<State, Inner, Function, To, Output, const SAFE: bool> Compute<State, Output, SAFE> for ComputeWith<Inner, Function, To>
+where
+    Inner: Selector<State, To, SAFE>,
+    Function: Fn(&To) -> Output + 'static,
+    To: ?Sized + 'static,
+{
+    fn compute(&self, state: &State) -> Option<Output> {
+        self.selector.select(state).map(&self.function)
+    }
+}
+
+pub trait SelectorComputeExt<State, To, const SAFE: bool>
+where
+    Self: Selector<State, To, SAFE> + Sized,
+    To: ?Sized + 'static,
+{
+    /// Computed selector deriving an owned value from the output of this
+    /// selector.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState, SelectorComputeExt};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     names: Vec<String>,
+    /// }
+    ///
+    /// let context = Context::new(MyState {
+    ///     names: vec!["Alice".to_owned(), "Bob".to_owned()],
+    /// });
+    ///
+    /// let joined = MyState::path().names().compute_with(|names: &Vec<String>| names.join(", "));
+    ///
+    /// assert_eq!(context.compute(&joined), "Alice, Bob");
+    /// ```
+    fn compute_with<Function, Output>(self, function: Function) -> impl Compute<State, Output, SAFE>
+    where
+        Function: Fn(&To) -> Output + 'static,
+    {
+        ComputeWith {
+            selector: self,
+            function,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, To, const SAFE: bool> SelectorComputeExt<State, To, SAFE> for T
+where
+    T: Selector<State, To, SAFE>,
+    To: ?Sized + 'static,
+{
+}
@@ -1,17 +1,200 @@
 //! Module providing the [`Context`], which is the base type for state
 //! management.
 
-use std::cell::UnsafeCell;
-use std::collections::HashMap;
+use std::any::{Any, TypeId};
+use std::borrow::Cow;
+use std::cell::{Cell, OnceCell, Ref, RefCell};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::hash::Hash;
+use std::ops::Range;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
-use crate::{MapItem, VecItem};
+#[cfg(feature = "slotmap")]
+use slotmap::{Key, SlotMap};
+
+use crate::change::{self, Callbacks, ChangeResult, PathChange, RecordedChange, StateChange};
+use crate::change_set::Change;
+use crate::command::{History, HistoryChange};
+#[cfg(feature = "sync")]
+use crate::lock::Lock;
+#[cfg(feature = "metrics")]
+use crate::metrics::Metrics;
+use crate::persist::Autosave;
+use crate::validator::{PathValidator, Validator};
+use crate::vec::{IdReuseCheck, IdReuseTracker, VecIdIndex, VecIdIndexCache};
+use crate::{
+    ApplyReport, BTreeMapLookupExt, Batch, Cached, ChangeBatch, Command, ContextBuilder, Extensions, ExtensionsExt, Flags, MapItem,
+    MapLookupExt, Memoized, PathKey, PathRegistry, ReadOnlyContext, RootPath, ScopedContext, SelectAll, VecIndexExt, VecItem, VecLookupExt,
+    Versioned,
+};
+
+/// Default for [`Context::set_max_apply_rounds`].
+const DEFAULT_MAX_APPLY_ROUNDS: usize = 16;
 
 /// Marker trait for the root of the state.
 ///
 /// This is only used when creating a new [`Context`].
 pub trait StateMarker {}
 
-type StateChange<State> = Box<dyn FnOnce(&mut State)>;
+type CoalescedChanges<Value> = Rc<RefCell<Vec<Box<dyn FnOnce(&mut Value)>>>>;
+
+/// Coalesced changes are keyed by the path and value type as well, so
+/// different paths queued with the same [`ChangeKey`] are kept apart.
+type CoalesceKey = (ChangeKey, PathKey, TypeId);
+
+type ErrorHandler = Box<dyn FnMut(&dyn Error)>;
+
+type PreApplyHook<State> = Box<dyn FnMut(&Context<State>)>;
+
+type PostApplyHook<State> = Box<dyn FnMut(&Context<State>, &ApplyReport)>;
+
+/// Key identifying where a change was queued from, for example the id of a
+/// component.
+///
+/// Changes queued from the same source are never reordered relative to each
+/// other when applying them. See [`Context::with_source`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SourceKey(pub u64);
+
+/// Key identifying a keyed change. Of all pending changes with the same key,
+/// only the last one is applied.
+///
+/// See [`Context::update_value_keyed`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ChangeKey(pub u64);
+
+/// Priority of a queued change. Pending changes with a higher priority are
+/// applied before changes with a lower priority.
+///
+/// Changes from the same [`SourceKey`] are never reordered. A change is
+/// therefore never applied before an earlier change from the same source, even
+/// if it has a higher priority.
+///
+/// See [`Context::with_priority`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Priority {
+    /// For structural changes, like inserting items that other changes might
+    /// target.
+    High,
+    #[default]
+    Normal,
+    Low,
+}
+
+/// Delay of a queued change. See [`Context::with_delay`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Delay {
+    /// Apply the change during the given number of [`Context::apply`] calls
+    /// from now. `Frames(1)` applies the change during the next call, just like
+    /// a change without a delay.
+    Frames(u32),
+    /// Apply the change during the first [`Context::apply`] call at or after
+    /// the given instant.
+    Until(Instant),
+}
+
+impl Delay {
+    /// Advance the delay by one [`Context::apply`] call. Returns the remaining
+    /// delay, or `None` if the change is due.
+    fn advance(self, now: Instant) -> Option<Self> {
+        match self {
+            Delay::Frames(frames) if frames > 1 => Some(Delay::Frames(frames - 1)),
+            Delay::Until(instant) if instant > now => Some(self),
+            _ => None,
+        }
+    }
+}
+
+/// The order in which [`Context::apply`] applies the pending changes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ApplyOrder {
+    /// Apply all changes in the order they were queued.
+    #[default]
+    Insertion,
+    /// Apply changes grouped by their [`SourceKey`]. Groups are ordered by
+    /// their first queued change and changes without a source form a group of
+    /// their own. The order of changes within a group is preserved.
+    ///
+    /// Grouping happens within each [`Priority`].
+    GroupedBySource,
+}
+
+/// Limit for the number of changes applied by a single [`Context::apply`].
+enum Budget {
+    Unlimited,
+    Changes(usize),
+    Until(Instant),
+}
+
+impl Budget {
+    fn is_exhausted(&self) -> bool {
+        match self {
+            Budget::Unlimited => false,
+            Budget::Changes(changes) => *changes == 0,
+            Budget::Until(deadline) => Instant::now() >= *deadline,
+        }
+    }
+
+    fn consume(&mut self) {
+        if let Budget::Changes(changes) = self {
+            *changes = changes.saturating_sub(1);
+        }
+    }
+}
+
+/// A state change, or an operation on the [`History`].
+enum QueuedStateChange<State> {
+    Change(StateChange<State>),
+    Command(Box<dyn Command<State>>),
+    Undo,
+    Redo,
+}
+
+impl<State: 'static> QueuedStateChange<State> {
+    /// Apply the change. Operations on the [`History`] are returned instead of
+    /// being recorded, so they can be discarded if the state is rejected.
+    fn apply(
+        self,
+        state: &mut State,
+        history: &mut History<State>,
+        callbacks: &mut Callbacks,
+    ) -> Result<Option<HistoryChange<State>>, Box<dyn Error>> {
+        match self {
+            Self::Change(state_change) => state_change(state, callbacks).map(|()| None),
+            Self::Command(mut command) => {
+                command.apply(state)?;
+                Ok(Some(HistoryChange::Executed(command)))
+            }
+            Self::Undo => history.undo(state),
+            Self::Redo => history.redo(state),
+        }
+    }
+}
+
+/// A queued change together with the [`SourceKey`] it was queued from, its
+/// [`Priority`], its [`ChangeKey`] and the [`Change`]s it is recorded as.
+struct QueuedChange<State> {
+    source: Option<SourceKey>,
+    priority: Priority,
+    key: Option<ChangeKey>,
+    records: Vec<Change>,
+    state_change: QueuedStateChange<State>,
+}
+
+/// Changes that were applied to a copy of the state by
+/// [`Context::preview`], but not committed yet.
+///
+/// Commands are executed on a scratch history and callbacks are collected, so
+/// neither reach the [`Context`] before the preview is committed.
+struct Preview<State> {
+    state: State,
+    report: ApplyReport,
+    history: History<State>,
+    callbacks: Callbacks,
+}
 
 /// A wrapper around the root state. Can be read and mutated using
 /// [`Path`](crate::Path)s.
@@ -19,9 +202,47 @@ type StateChange<State> = Box<dyn FnOnce(&mut State)>;
 /// The context allows you to work on data in the state and even keep
 /// immutable references to it while simultaneously queuing state
 /// changes on that same data.
+///
+/// # Aliasing
+///
+/// Changes are queued through a shared reference, but they never alias the
+/// state or each other:
+///
+/// - The queue lives in a [`RefCell`] and is only borrowed for the duration of
+///   a single push or count. No user code runs while it is borrowed, so queuing
+///   can never fail, even from inside closures passed to
+///   [`with_source`](Self::with_source) or similar functions.
+/// - Queued changes only ever receive `&mut State` while being applied. Since
+///   [`apply`](Self::apply) takes `&mut self`, no reference obtained from the
+///   context can be alive at that point.
+/// - The context is not [`Sync`], so changes can't be queued from multiple
+///   threads at once. Use a [`ChangeBatch`] to build changes on other threads.
 pub struct Context<State> {
     state: State,
-    state_changes: UnsafeCell<Vec<StateChange<State>>>,
+    state_changes: RefCell<Vec<QueuedChange<State>>>,
+    coalesced_changes: RefCell<HashMap<CoalesceKey, Box<dyn Any>>>,
+    preview: RefCell<Option<Preview<State>>>,
+    history: History<State>,
+    current_source: Cell<Option<SourceKey>>,
+    current_priority: Cell<Priority>,
+    current_delay: Cell<Option<Delay>>,
+    delayed_changes: RefCell<Vec<(Delay, QueuedChange<State>)>>,
+    apply_order: ApplyOrder,
+    max_apply_rounds: usize,
+    record_changes: bool,
+    id_reuse_checks: Vec<Box<dyn IdReuseCheck<State>>>,
+    vec_indices: HashMap<PathKey, Box<dyn VecIdIndexCache>>,
+    version: u64,
+    path_registry: PathRegistry<State>,
+    validators: Vec<Box<dyn Validator<State>>>,
+    pre_apply_hooks: Vec<PreApplyHook<State>>,
+    post_apply_hooks: Vec<PostApplyHook<State>>,
+    error_handler: Option<ErrorHandler>,
+    autosave: Option<Autosave<State>>,
+    strict: bool,
+    extensions: OnceCell<Box<Context<Extensions>>>,
+    #[cfg(feature = "metrics")]
+    metrics: Cell<Metrics>,
 }
 
 impl<State: StateMarker> Context<State> {
@@ -55,15 +276,410 @@ impl<State: StateMarker> Context<State> {
     pub fn new(state: State) -> Self {
         Self {
             state,
-            state_changes: UnsafeCell::new(Vec::new()),
+            state_changes: RefCell::new(Vec::new()),
+            coalesced_changes: RefCell::new(HashMap::new()),
+            preview: RefCell::new(None),
+            history: History::default(),
+            current_source: Cell::new(None),
+            current_priority: Cell::new(Priority::default()),
+            current_delay: Cell::new(None),
+            delayed_changes: RefCell::new(Vec::new()),
+            apply_order: ApplyOrder::default(),
+            max_apply_rounds: DEFAULT_MAX_APPLY_ROUNDS,
+            record_changes: false,
+            id_reuse_checks: Vec::new(),
+            vec_indices: HashMap::new(),
+            version: 0,
+            path_registry: PathRegistry::default(),
+            validators: Vec::new(),
+            pre_apply_hooks: Vec::new(),
+            post_apply_hooks: Vec::new(),
+            error_handler: None,
+            autosave: None,
+            strict: false,
+            extensions: OnceCell::new(),
+            #[cfg(feature = "metrics")]
+            metrics: Cell::new(Metrics::default()),
         }
     }
+
+    /// Create a [`ContextBuilder`] to configure a new context for a root
+    /// state.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut context = Context::builder(MyState { value: 0 }).max_apply_rounds(4).build();
+    ///
+    /// context.update_value(MyState::path().value(), 5);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&MyState::path().value()), &5);
+    /// ```
+    pub fn builder(state: State) -> ContextBuilder<State> {
+        ContextBuilder::new(state)
+    }
 }
 
-impl<State> Context<State> {
-    fn push_change(&self, state_change: StateChange<State>) {
-        let state_changes = unsafe { &mut *self.state_changes.get() };
-        state_changes.push(state_change);
+impl<State: 'static> Context<State> {
+    pub(crate) fn reserve_changes(&mut self, additional: usize) {
+        self.state_changes.get_mut().reserve(additional);
+    }
+
+    /// Queue a change built by one of the constructors in [`change`].
+    fn queue<F>(&self, change: PathChange<F>)
+    where
+        F: FnOnce(&mut State, &mut Callbacks) -> ChangeResult + 'static,
+    {
+        self.queue_keyed(None, change);
+    }
+
+    fn queue_keyed<F>(&self, key: Option<ChangeKey>, change: PathChange<F>)
+    where
+        F: FnOnce(&mut State, &mut Callbacks) -> ChangeResult + 'static,
+    {
+        let records = self.records([change.record]);
+        self.push_queued_change(key, records, QueuedStateChange::Change(Box::new(change.apply)));
+    }
+
+    /// Queue a change that was already boxed, for example by a
+    /// [`ScopedContext`].
+    pub(crate) fn queue_recorded(&self, change: RecordedChange<State>) {
+        let records = self.records([change.record]);
+        self.push_queued_change(None, records, QueuedStateChange::Change(change.apply));
+    }
+
+    /// Queue multiple changes as a single change that applies all of them in
+    /// order. Used for [`Batch`]es and [`ChangeBatch`]es.
+    pub(crate) fn queue_batch(&self, changes: Vec<RecordedChange<State>>) {
+        let records = self.records(changes.iter().map(|change| change.record));
+        let state_changes: Vec<_> = changes.into_iter().map(|change| change.apply).collect();
+
+        let state_change = Box::new(move |state: &mut State, callbacks: &mut Callbacks| {
+            state_changes
+                .into_iter()
+                .map(|state_change| state_change(state, callbacks))
+                .fold(Ok(()), Result::and)
+        });
+
+        self.push_queued_change(None, records, QueuedStateChange::Change(state_change));
+    }
+
+    /// Keep the [`Change`]s for the [`ChangeSet`](crate::ChangeSet), if
+    /// recording is enabled.
+    fn records(&self, records: impl IntoIterator<Item = Change>) -> Vec<Change> {
+        match self.record_changes {
+            true => records.into_iter().collect(),
+            false => Vec::new(),
+        }
+    }
+
+    /// Get mutable access to the state, committing any pending
+    /// [`preview`](Self::preview) first so it doesn't overwrite the mutation.
+    fn state_mut(&mut self) -> &mut State {
+        self.commit_preview();
+
+        self.vec_indices.values().for_each(|index| index.invalidate());
+        self.version = self.version.wrapping_add(1);
+
+        &mut self.state
+    }
+
+    /// Take all pending changes out of the queue.
+    fn take_pending(&mut self) -> Vec<QueuedChange<State>> {
+        self.coalesced_changes.get_mut().clear();
+        std::mem::take(self.state_changes.get_mut())
+    }
+
+    fn push_queued_change(&self, key: Option<ChangeKey>, records: Vec<Change>, state_change: QueuedStateChange<State>) {
+        let queued_change = QueuedChange {
+            source: self.current_source.get(),
+            priority: self.current_priority.get(),
+            key,
+            records,
+            state_change,
+        };
+
+        #[cfg(feature = "metrics")]
+        {
+            let mut metrics = self.metrics.get();
+            metrics.record_queued();
+            self.metrics.set(metrics);
+        }
+
+        match self.current_delay.get() {
+            Some(delay) => self.delayed_changes.borrow_mut().push((delay, queued_change)),
+            None => self.state_changes.borrow_mut().push(queued_change),
+        }
+    }
+
+    /// Tag all changes queued inside the closure with a [`SourceKey`].
+    ///
+    /// The relative order of changes from the same source is always preserved
+    /// when applying, even if the [`ApplyOrder`] reorders changes.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{ApplyOrder, Context, RustState, SourceKey};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     text: String,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { text: String::new() });
+    /// let text_path = MyState::path().text();
+    ///
+    /// context.set_apply_order(ApplyOrder::GroupedBySource);
+    ///
+    /// context.with_source(SourceKey(1), |context| context.update_value_with(text_path, |text| text.push('a')));
+    /// context.with_source(SourceKey(2), |context| context.update_value_with(text_path, |text| text.push('x')));
+    /// context.with_source(SourceKey(1), |context| context.update_value_with(text_path, |text| text.push('b')));
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&text_path), "abx");
+    /// ```
+    pub fn with_source<R>(&self, source: SourceKey, closure: impl FnOnce(&Self) -> R) -> R {
+        let previous_source = self.current_source.replace(Some(source));
+        let result = closure(self);
+        self.current_source.set(previous_source);
+        result
+    }
+
+    /// Queue all changes inside the closure with a given [`Priority`].
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, Priority, RustState, VecItem, VecLookupExt};
+    ///
+    /// struct TestItem {
+    ///     id: u32,
+    ///     name: &'static str,
+    /// }
+    ///
+    /// impl VecItem for TestItem {
+    ///     type Id = u32;
+    ///
+    ///     fn get_id(&self) -> Self::Id {
+    ///         self.id
+    ///     }
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: Vec<TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { items: Vec::new() });
+    /// let items_path = MyState::path().items();
+    ///
+    /// // Queued first, but targets an item that doesn't exist yet.
+    /// context.update_value_with(items_path.lookup(10), |item| item.name = "Renamed");
+    ///
+    /// context.with_priority(Priority::High, |context| {
+    ///     context.vec_push(items_path, TestItem { id: 10, name: "New" });
+    /// });
+    ///
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&items_path)[0].name, "Renamed");
+    /// ```
+    pub fn with_priority<R>(&self, priority: Priority, closure: impl FnOnce(&Self) -> R) -> R {
+        let previous_priority = self.current_priority.replace(priority);
+        let result = closure(self);
+        self.current_priority.set(previous_priority);
+        result
+    }
+
+    /// Delay all changes queued inside the closure.
+    ///
+    /// Delayed changes don't count as pending until they are due. Once due,
+    /// they are applied before the changes that were queued since.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, Delay, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     opacity: f32,
+    ///     visible: bool,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { opacity: 1.0, visible: true });
+    ///
+    /// context.update_value(MyState::path().opacity(), 0.0);
+    /// context.with_delay(Delay::Frames(3), |context| {
+    ///     context.update_value(MyState::path().visible(), false);
+    /// });
+    ///
+    /// context.apply();
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&MyState::path().visible()), &true);
+    ///
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&MyState::path().visible()), &false);
+    /// ```
+    pub fn with_delay<R>(&self, delay: Delay, closure: impl FnOnce(&Self) -> R) -> R {
+        let previous_delay = self.current_delay.replace(Some(delay));
+        let result = closure(self);
+        self.current_delay.set(previous_delay);
+        result
+    }
+
+    /// Get the number of delayed changes that are not due yet.
+    pub fn delayed_changes(&self) -> usize {
+        self.delayed_changes.borrow().len()
+    }
+
+    /// Enable or disable recording a [`ChangeSet`](crate::ChangeSet) of all
+    /// applied changes, available through
+    /// [`ApplyReport::change_set`].
+    ///
+    /// Only changes queued while recording is enabled are recorded.
+    pub fn set_record_changes(&mut self, record_changes: bool) {
+        self.record_changes = record_changes;
+    }
+
+    /// Set a function that persists the state after every
+    /// [`apply`](Self::apply) that applied any changes.
+    ///
+    /// Replaces any previously set persist function.
+    ///
+    /// Example:
+    /// ```
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    ///
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { value: 5 });
+    /// let saved = Rc::new(RefCell::new(Vec::new()));
+    ///
+    /// let save_target = saved.clone();
+    /// context.on_apply_persist(move |state: &MyState| save_target.borrow_mut().push(state.value));
+    ///
+    /// context.apply();
+    /// context.update_value(MyState::path().value(), 10);
+    /// context.apply();
+    ///
+    /// assert_eq!(*saved.borrow(), [10]);
+    /// ```
+    pub fn on_apply_persist(&mut self, persist: impl FnMut(&State) + 'static) {
+        self.on_apply_persist_debounced(Duration::ZERO, persist);
+    }
+
+    /// Set a function that persists the state once no changes were applied for
+    /// the `debounce` duration.
+    ///
+    /// The state is only persisted during [`apply`](Self::apply), so it
+    /// needs to be called regularly. Use
+    /// [`flush_persist`](Self::flush_persist) to persist unsaved changes
+    /// immediately, for example before exiting.
+    pub fn on_apply_persist_debounced(&mut self, debounce: Duration, persist: impl FnMut(&State) + 'static) {
+        self.autosave = Some(Autosave::new(debounce, persist));
+    }
+
+    /// Persist the state immediately if any changes were applied since it was
+    /// last persisted. Pending changes are not applied.
+    pub fn flush_persist(&mut self) {
+        if let Some(autosave) = &mut self.autosave {
+            autosave.flush(&self.state);
+        }
+    }
+
+    /// Set a handler that is called with every error returned by a fallible
+    /// change, like the ones queued with
+    /// [`update_value_try`](Self::update_value_try), at the end of
+    /// [`apply`](Self::apply).
+    ///
+    /// The errors are still returned in the [`ApplyReport`].
+    pub fn set_error_handler(&mut self, error_handler: impl FnMut(&dyn Error) + 'static) {
+        self.error_handler = Some(Box::new(error_handler));
+    }
+
+    /// Enable or disable strict mode. In strict mode, [`apply`](Self::apply)
+    /// panics if any fallible change returned an error, after the error
+    /// handler was called.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Get the [`Metrics`] collected since the context was created or the
+    /// metrics were last reset.
+    ///
+    /// Only available with the `metrics` feature.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> Metrics {
+        self.metrics.get()
+    }
+
+    /// Reset all [`Metrics`] to zero.
+    ///
+    /// Only available with the `metrics` feature.
+    #[cfg(feature = "metrics")]
+    pub fn reset_metrics(&self) {
+        self.metrics.take();
+    }
+
+    /// Set the order in which [`apply`](Self::apply) applies pending changes.
+    pub fn set_apply_order(&mut self, apply_order: ApplyOrder) {
+        self.apply_order = apply_order;
+    }
+
+    /// Set the maximum number of rounds a single [`apply`](Self::apply) runs
+    /// before leaving changes queued by post-apply hooks pending. Defaults to
+    /// 16.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    ///     doubled: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { value: 1, doubled: 2 });
+    ///
+    /// // Keep `doubled` in sync with `value`.
+    /// context.add_post_apply_hook(|context, _| {
+    ///     let doubled = context.get(&MyState::path().value()) * 2;
+    ///
+    ///     if *context.get(&MyState::path().doubled()) != doubled {
+    ///         context.update_value(MyState::path().doubled(), doubled);
+    ///     }
+    /// });
+    /// context.set_max_apply_rounds(4);
+    ///
+    /// context.update_value(MyState::path().value(), 5);
+    /// let report = context.apply();
+    ///
+    /// assert_eq!(report.applied_changes(), 2);
+    /// assert_eq!(context.get(&MyState::path().doubled()), &10);
+    /// ```
+    pub fn set_max_apply_rounds(&mut self, max_apply_rounds: usize) {
+        self.max_apply_rounds = max_apply_rounds;
     }
 
     /// Update the value for a given path.
@@ -91,17 +707,52 @@ impl<State> Context<State> {
         Path: crate::Path<State, Value, SAFE>,
         Value: 'static,
     {
-        self.push_change(Box::new(move |state: &mut State| match path.follow_mut(state) {
-            Some(reference) => *reference = value,
-            None => println!("Failed to update state"),
-        }));
+        self.queue(change::update_value(path, value));
     }
 
-    /// Update the value for a given path with a closure.
+    /// Update the value for a given path, discarding all previously queued
+    /// changes with the same [`ChangeKey`].
+    ///
+    /// This is useful if the same value is updated many times between two
+    /// calls to [`apply`](Self::apply), for example while dragging a slider.
+    /// Only the last update is applied, in the position it was queued in.
     ///
     /// Example:
     /// ```
-    /// use rust_state::{Context, RustState};
+    /// use rust_state::{ChangeKey, Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     volume: f32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { volume: 0.0 });
+    /// let volume_path = MyState::path().volume();
+    /// let volume_key = ChangeKey(0);
+    ///
+    /// for step in 1..=100 {
+    ///     context.update_value_keyed(volume_key, volume_path, step as f32 / 100.0);
+    /// }
+    ///
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&volume_path), &1.0);
+    /// ```
+    pub fn update_value_keyed<Path, Value, const SAFE: bool>(&self, key: ChangeKey, path: Path, value: Value)
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: 'static,
+    {
+        self.queue_keyed(Some(key), change::update_value(path, value));
+    }
+
+    /// Update the value for a given path after a [`Delay`]. See
+    /// [`with_delay`](Self::with_delay).
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, Delay, RustState};
     ///
     /// #[derive(RustState)]
     /// #[state_root]
@@ -112,73 +763,3231 @@ impl<State> Context<State> {
     /// let mut context = Context::new(MyState { value: 5 });
     /// let value_path = MyState::path().value();
     ///
-    /// context.update_value_with(value_path, |value| *value *= 2);
+    /// context.update_value_after(value_path, 10, Delay::Frames(2));
     /// context.apply();
     ///
-    /// assert_eq!(context.get(&value_path), &10);
+    /// assert_eq!(context.get(&value_path), &5);
+    ///
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&value_path), &10);
+    /// ```
+    pub fn update_value_after<Path, Value, const SAFE: bool>(&self, path: Path, value: Value, delay: Delay)
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: 'static,
+    {
+        self.with_delay(delay, |context| context.update_value(path, value));
+    }
+
+    /// Update the value for a given path with a closure.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { value: 5 });
+    /// let value_path = MyState::path().value();
+    ///
+    /// context.update_value_with(value_path, |value| *value *= 2);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&value_path), &10);
     /// ```
     pub fn update_value_with<Path, Value, F, const SAFE: bool>(&self, path: Path, closure: F)
     where
-        Path: crate::Path<State, Value, SAFE>,
-        F: Fn(&mut Value) + 'static,
+        Path: crate::Path<State, Value, SAFE>,
+        Value: 'static,
+        F: Fn(&mut Value) + 'static,
+    {
+        self.queue(change::update_value_with(path, closure));
+    }
+
+    /// Update the value for a given path with a closure, coalescing it with
+    /// other pending changes queued with the same [`ChangeKey`].
+    ///
+    /// All coalesced closures are applied in the order they were queued, but
+    /// the path is only followed once. The combined change is applied at the
+    /// position of the first one. Only changes to the same path are coalesced,
+    /// so the same key can be used for different paths.
+    ///
+    /// Keys used here are independent of the ones used by
+    /// [`update_value_keyed`](Self::update_value_keyed). Delayed changes and
+    /// changes to paths without a [`key`](crate::PathRead::key) are never
+    /// coalesced.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{ChangeKey, Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    ///     other: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { value: 5, other: 0 });
+    /// let value_path = MyState::path().value();
+    ///
+    /// context.update_value_with_coalesced(ChangeKey(0), value_path, |value| *value += 1);
+    /// context.update_value_with_coalesced(ChangeKey(0), value_path, |value| *value *= 2);
+    ///
+    /// assert_eq!(context.pending_changes(), 1);
+    ///
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&value_path), &12);
+    ///
+    /// // Changes to different paths are kept apart, even with the same key.
+    /// let other_path = MyState::path().other();
+    ///
+    /// context.update_value_with_coalesced(ChangeKey(1), value_path, |value| *value += 1);
+    /// context.update_value_with_coalesced(ChangeKey(1), other_path, |value| *value += 10);
+    ///
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&value_path), &13);
+    /// assert_eq!(context.get(&other_path), &10);
+    /// ```
+    pub fn update_value_with_coalesced<Path, Value, F, const SAFE: bool>(&self, key: ChangeKey, path: Path, closure: F)
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: 'static,
+        F: Fn(&mut Value) + 'static,
+    {
+        if self.current_delay.get().is_some() {
+            return self.update_value_with(path, closure);
+        }
+
+        let Some(path_key) = path.key() else {
+            return self.update_value_with(path, closure);
+        };
+        let key = (key, path_key, TypeId::of::<Value>());
+
+        let existing = self
+            .coalesced_changes
+            .borrow()
+            .get(&key)
+            .and_then(|changes| changes.downcast_ref::<CoalescedChanges<Value>>())
+            .cloned();
+
+        if let Some(changes) = existing {
+            changes.borrow_mut().push(Box::new(closure));
+            return;
+        }
+
+        let changes: CoalescedChanges<Value> = Rc::new(RefCell::new(vec![Box::new(closure)]));
+        self.coalesced_changes.borrow_mut().insert(key, Box::new(changes.clone()));
+
+        self.queue(change::update_value_with(path, move |value: &mut Value| {
+            changes.take().into_iter().for_each(|closure| closure(value))
+        }));
+    }
+
+    /// Update the value for a given path with a closure that can fail.
+    ///
+    /// Errors returned by the closure are collected in the [`ApplyReport`]
+    /// returned by [`apply`](Self::apply). If the path doesn't resolve, an
+    /// [`UnresolvedPath`](crate::UnresolvedPath) error is collected instead.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     input: String,
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     input: "not a number".to_owned(),
+    ///     value: 5,
+    /// });
+    ///
+    /// let input = context.get(&MyState::path().input()).clone();
+    /// context.update_value_try(MyState::path().value(), move |value| {
+    ///     *value = input.parse()?;
+    ///     Ok::<_, std::num::ParseIntError>(())
+    /// });
+    ///
+    /// let report = context.apply();
+    ///
+    /// assert_eq!(report.errors().len(), 1);
+    /// assert_eq!(context.get(&MyState::path().value()), &5);
+    /// ```
+    ///
+    /// Paths that don't resolve are reported as errors:
+    /// ```
+    /// use rust_state::{Context, OptionExt, RustState, UnresolvedPath};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     selected: Option<u32>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { selected: None });
+    ///
+    /// context.update_value_try(MyState::path().selected().unwrapped(), |selected| {
+    ///     *selected += 1;
+    ///     Ok::<_, UnresolvedPath>(())
+    /// });
+    ///
+    /// let report = context.apply();
+    ///
+    /// assert_eq!(report.errors().len(), 1);
+    /// assert!(report.errors()[0].is::<UnresolvedPath>());
+    /// ```
+    pub fn update_value_try<Path, Value, F, E, const SAFE: bool>(&self, path: Path, closure: F)
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: 'static,
+        F: FnOnce(&mut Value) -> Result<(), E> + 'static,
+        E: Error + 'static,
+    {
+        self.queue(change::update_value_try(path, closure));
+    }
+
+    /// Queue a [`Command`]. Once applied, the command is recorded in the
+    /// history and can be reverted using [`undo`](Self::undo).
+    ///
+    /// Executing a command clears all commands that could be redone.
+    pub fn execute(&self, command: impl Command<State> + 'static) {
+        self.push_queued_change(None, Vec::new(), QueuedStateChange::Command(Box::new(command)));
+    }
+
+    /// Queue reverting the last applied [`Command`]. Does nothing if there is
+    /// no command to undo when the change is applied.
+    pub fn undo(&self) {
+        self.push_queued_change(None, Vec::new(), QueuedStateChange::Undo);
+    }
+
+    /// Queue re-applying the last reverted [`Command`]. Does nothing if there
+    /// is no command to redo when the change is applied.
+    pub fn redo(&self) {
+        self.push_queued_change(None, Vec::new(), QueuedStateChange::Redo);
+    }
+
+    /// Check if there is an applied [`Command`] that can be undone. Pending
+    /// changes are not taken into account.
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    /// Check if there is a reverted [`Command`] that can be redone. Pending
+    /// changes are not taken into account.
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Clear the history of applied and reverted [`Command`]s.
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// Export the state and the history of applied and reverted [`Command`]s
+    /// as a [`Session`](crate::Session), so the editing session can be
+    /// restored with [`import_session`](Self::import_session), for example
+    /// after a crash.
+    ///
+    /// Returns `None` if the history contains a command that is not a `C`.
+    /// Pending changes and recorded [`ChangeSet`](crate::ChangeSet)s are not
+    /// part of the session.
+    ///
+    /// Example:
+    /// ```
+    /// use std::error::Error;
+    ///
+    /// use rust_state::{Command, Context, RustState};
+    ///
+    /// #[derive(Clone, RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     counter: u32,
+    /// }
+    ///
+    /// #[derive(Clone)]
+    /// enum Edit {
+    ///     Add(u32),
+    /// }
+    ///
+    /// impl Command<MyState> for Edit {
+    ///     fn apply(&mut self, state: &mut MyState) -> Result<(), Box<dyn Error>> {
+    ///         let Edit::Add(amount) = self;
+    ///         state.counter += *amount;
+    ///         Ok(())
+    ///     }
+    ///
+    ///     fn revert(&mut self, state: &mut MyState) -> Result<(), Box<dyn Error>> {
+    ///         let Edit::Add(amount) = self;
+    ///         state.counter -= *amount;
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { counter: 0 });
+    /// let counter_path = MyState::path().counter();
+    ///
+    /// context.execute(Edit::Add(1));
+    /// context.execute(Edit::Add(2));
+    /// context.undo();
+    /// context.apply();
+    ///
+    /// let session = context.export_session::<Edit>().unwrap();
+    ///
+    /// let mut restored = Context::new(MyState { counter: 0 });
+    /// restored.import_session(session);
+    ///
+    /// assert_eq!(restored.get(&counter_path), &1);
+    /// assert!(restored.can_undo());
+    ///
+    /// restored.redo();
+    /// restored.apply();
+    ///
+    /// assert_eq!(restored.get(&counter_path), &3);
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn export_session<C>(&self) -> Option<crate::Session<State, C>>
+    where
+        State: Clone,
+        C: Command<State> + Clone,
+    {
+        let (undo_stack, redo_stack) = self.history.export()?;
+
+        Some(crate::Session {
+            state: self.state.clone(),
+            undo_stack,
+            redo_stack,
+        })
+    }
+
+    /// Replace the state and the history of [`Command`]s with the ones of a
+    /// [`Session`](crate::Session) exported by
+    /// [`export_session`](Self::export_session).
+    ///
+    /// Pending changes are kept and applied to the imported state. Validators
+    /// accept the imported state as valid.
+    #[cfg(feature = "serde")]
+    pub fn import_session<C>(&mut self, session: crate::Session<State, C>)
+    where
+        C: Command<State>,
+    {
+        *self.state_mut() = session.state;
+        self.history = History::import(session.undo_stack, session.redo_stack);
+
+        let state = &self.state;
+        self.validators.iter_mut().for_each(|validator| validator.accept(state));
+    }
+
+    /// Update the entire state with a closure.
+    ///
+    /// This is useful for changes that need to coordinate multiple fields,
+    /// since the closure is applied as a single change.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     todo: Vec<String>,
+    ///     done: Vec<String>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     todo: vec!["Write docs".to_owned()],
+    ///     done: Vec::new(),
+    /// });
+    ///
+    /// context.update_root(|state: &mut MyState| {
+    ///     let item = state.todo.remove(0);
+    ///     state.done.push(item);
+    /// });
+    /// context.apply();
+    ///
+    /// assert!(context.get(&MyState::path().todo()).is_empty());
+    /// assert_eq!(context.get(&MyState::path().done()), &["Write docs"]);
+    /// ```
+    pub fn update_root<F>(&self, closure: F)
+    where
+        F: FnOnce(&mut State) + 'static,
+    {
+        self.queue(change::update_root(closure));
+    }
+
+    /// Update the value inside a [`RefCell`] at a given path with a closure.
+    ///
+    /// Since changes are applied with mutable access to the state, the cell is
+    /// never borrowed at runtime.
+    ///
+    /// Example:
+    /// ```
+    /// use std::cell::RefCell;
+    ///
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     legacy: RefCell<Vec<u32>>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     legacy: RefCell::new(vec![1, 2]),
+    /// });
+    /// let legacy_path = MyState::path().legacy();
+    ///
+    /// context.update_cell(legacy_path, |items: &mut Vec<u32>| items.push(3));
+    /// context.apply();
+    ///
+    /// assert_eq!(context.with_cell(legacy_path, |items: &Vec<u32>| items.len()), Some(3));
+    /// ```
+    pub fn update_cell<Path, Value, F, const SAFE: bool>(&self, path: Path, closure: F)
+    where
+        Path: crate::Path<State, RefCell<Value>, SAFE>,
+        Value: 'static,
+        F: FnOnce(&mut Value) + 'static,
+    {
+        self.queue(change::update_cell(path, closure));
+    }
+
+    /// Update the value behind a [`Lock`] at a given path with a closure.
+    ///
+    /// Since changes are applied with mutable access to the state, the lock
+    /// is never acquired.
+    ///
+    /// Only available with the `sync` feature.
+    ///
+    /// Example:
+    /// ```
+    /// use std::sync::RwLock;
+    ///
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     counter: RwLock<u32>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { counter: RwLock::new(0) });
+    /// let counter_path = MyState::path().counter();
+    ///
+    /// context.update_locked(counter_path, |counter: &mut u32| *counter += 1);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.with_locked(counter_path, |counter: &u32| *counter), Some(1));
+    /// ```
+    #[cfg(feature = "sync")]
+    pub fn update_locked<Path, Value, F, const SAFE: bool>(&self, path: Path, closure: F)
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: Lock + 'static,
+        F: FnOnce(&mut Value::Target) + 'static,
+    {
+        self.queue(change::update_locked(path, closure));
+    }
+
+    /// Update every target of a [`Traversal`](crate::Traversal) with a
+    /// closure.
+    pub fn update_each<Traversal, Value, F>(&self, traversal: Traversal, closure: F)
+    where
+        Traversal: crate::Traversal<State, Value>,
+        Value: ?Sized + 'static,
+        F: FnMut(&mut Value) + 'static,
+    {
+        self.queue(change::update_each(traversal, closure));
+    }
+
+    /// Update every target of an
+    /// [`IndexedTraversal`](crate::IndexedTraversal) with a closure that also
+    /// receives the key of the target.
+    pub fn update_each_indexed<Traversal, Key, Value, F>(&self, traversal: Traversal, closure: F)
+    where
+        Traversal: crate::IndexedTraversal<State, Key, Value>,
+        Key: 'static,
+        Value: ?Sized + 'static,
+        F: FnMut(&Key, &mut Value) + 'static,
+    {
+        self.queue(change::update_each_indexed(traversal, closure));
+    }
+
+    /// Take the value at a given path, leaving [`Default::default`] in its
+    /// place. The previous value is passed to the callback when the change is
+    /// applied.
+    ///
+    /// This allows moving large values out of the state without cloning them.
+    ///
+    /// Example:
+    /// ```
+    /// use std::sync::mpsc::channel;
+    ///
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     document: String,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { document: "Loaded".to_owned() });
+    /// let document_path = MyState::path().document();
+    /// let (sender, receiver) = channel();
+    ///
+    /// context.take(document_path, move |document| sender.send(document).unwrap());
+    /// context.apply();
+    ///
+    /// assert_eq!(receiver.recv().unwrap(), "Loaded");
+    /// assert_eq!(context.get(&document_path), "");
+    /// ```
+    pub fn take<Path, Value, F, const SAFE: bool>(&self, path: Path, callback: F)
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: Default + 'static,
+        F: FnOnce(Value) + 'static,
+    {
+        self.queue(change::take(path, callback));
+    }
+
+    /// Replace the value at a given path. The previous value is passed to the
+    /// callback when the change is applied.
+    ///
+    /// This is useful if the previous value needs to be cleaned up when it is
+    /// overwritten.
+    ///
+    /// Example:
+    /// ```
+    /// use std::sync::mpsc::channel;
+    ///
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { value: 5 });
+    /// let value_path = MyState::path().value();
+    /// let (sender, receiver) = channel();
+    ///
+    /// context.replace(value_path, 10, move |previous| sender.send(previous).unwrap());
+    /// context.apply();
+    ///
+    /// assert_eq!(receiver.recv().unwrap(), 5);
+    /// assert_eq!(context.get(&value_path), &10);
+    /// ```
+    pub fn replace<Path, Value, F, const SAFE: bool>(&self, path: Path, value: Value, callback: F)
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: 'static,
+        F: FnOnce(Value) + 'static,
+    {
+        self.queue(change::replace(path, value, callback));
+    }
+
+    /// Recompute a [`Cached`] value from its [`Versioned`] source, if the
+    /// cached value is no longer valid.
+    ///
+    /// The closure is only called when the change is applied and the cache
+    /// is invalid. Caches are never recomputed automatically, so this needs to
+    /// be queued whenever an up to date value is required.
+    pub fn refresh_cache<CachePath, SourcePath, Value, Source, F, const CACHE_SAFE: bool, const SOURCE_SAFE: bool>(
+        &self,
+        cache_path: CachePath,
+        source_path: SourcePath,
+        compute: F,
+    ) where
+        CachePath: crate::Path<State, Cached<Value>, CACHE_SAFE>,
+        SourcePath: crate::Path<State, Versioned<Source>, SOURCE_SAFE>,
+        Value: 'static,
+        Source: 'static,
+        F: FnOnce(&Source) -> Value + 'static,
+    {
+        self.queue(change::refresh_cache(cache_path, source_path, compute));
+    }
+
+    /// Push an item to a [`Vec`].
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState, VecItem};
+    ///
+    /// struct TestItem {
+    ///     id: u32,
+    /// }
+    ///
+    /// impl VecItem for TestItem {
+    ///     type Id = u32;
+    ///
+    ///     fn get_id(&self) -> Self::Id {
+    ///         self.id
+    ///     }
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: Vec<TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { items: Vec::new() });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.vec_push(items_path, TestItem { id: 10 });
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&items_path).len(), 1);
+    /// ```
+    pub fn vec_push<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.queue(change::vec_push(path, value));
+    }
+
+    /// Remove an item from a [`Vec`].
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState, VecItem};
+    ///
+    /// struct TestItem {
+    ///     id: u32,
+    /// }
+    ///
+    /// impl VecItem for TestItem {
+    ///     type Id = u32;
+    ///
+    ///     fn get_id(&self) -> Self::Id {
+    ///         self.id
+    ///     }
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: Vec<TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { items: vec![TestItem { id: 10 }] });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.vec_remove(items_path, 10);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&items_path).len(), 0);
+    /// ```
+    pub fn vec_remove<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.queue(change::vec_remove(path, id));
+    }
+
+    /// Remove an item from a [`Vec`] and pass the removed items to the
+    /// callback. The callback receives an empty vector if there was no item
+    /// with the given id.
+    ///
+    /// Example:
+    /// ```
+    /// use std::sync::mpsc::channel;
+    ///
+    /// use rust_state::{Context, RustState, VecItem};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct TestItem {
+    ///     id: u32,
+    /// }
+    ///
+    /// impl VecItem for TestItem {
+    ///     type Id = u32;
+    ///
+    ///     fn get_id(&self) -> Self::Id {
+    ///         self.id
+    ///     }
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: Vec<TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     items: vec![TestItem { id: 10 }, TestItem { id: 11 }],
+    /// });
+    /// let items_path = MyState::path().items();
+    /// let (sender, receiver) = channel();
+    ///
+    /// context.vec_remove_with(items_path, 10, move |removed| sender.send(removed).unwrap());
+    /// context.apply();
+    ///
+    /// assert_eq!(receiver.recv().unwrap(), vec![TestItem { id: 10 }]);
+    /// assert_eq!(context.get(&items_path).len(), 1);
+    /// ```
+    pub fn vec_remove_with<Path, Value, F, const SAFE: bool>(&self, path: Path, id: Value::Id, callback: F)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+        F: FnOnce(Vec<Value>) + 'static,
+    {
+        self.queue(change::vec_remove_with(path, id, callback));
+    }
+
+    /// Remove all items from a [`Vec`].
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     values: Vec<u32>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { values: vec![1, 2, 3] });
+    /// let values_path = MyState::path().values();
+    ///
+    /// context.vec_clear(values_path);
+    /// context.apply();
+    ///
+    /// assert!(context.get(&values_path).is_empty());
+    /// ```
+    pub fn vec_clear<Path, Value, const SAFE: bool>(&self, path: Path)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.queue(change::vec_clear(path));
+    }
+
+    /// Sort a [`Vec`] with a comparator. The sort is stable.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     values: Vec<u32>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { values: vec![2, 3, 1] });
+    /// let values_path = MyState::path().values();
+    ///
+    /// context.vec_sort_by(values_path, |left, right| right.cmp(left));
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&values_path), &[3, 2, 1]);
+    /// ```
+    pub fn vec_sort_by<Path, Value, F, const SAFE: bool>(&self, path: Path, comparator: F)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+        F: FnMut(&Value, &Value) -> Ordering + 'static,
+    {
+        self.queue(change::vec_sort_by(path, comparator));
+    }
+
+    /// Append all items of an iterator to a [`Vec`] in a single change.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     values: Vec<u32>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { values: vec![1] });
+    /// let values_path = MyState::path().values();
+    ///
+    /// context.vec_extend(values_path, 2..5);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&values_path), &[1, 2, 3, 4]);
+    /// ```
+    pub fn vec_extend<Path, Value, Items, const SAFE: bool>(&self, path: Path, items: Items)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+        Items: IntoIterator<Item = Value> + 'static,
+    {
+        self.queue(change::vec_extend(path, items));
+    }
+
+    /// Only keep the items of a [`Vec`] for which the predicate returns `true`.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// struct Todo {
+    ///     completed: bool,
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     todos: Vec<Todo>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     todos: vec![Todo { completed: true }, Todo { completed: false }],
+    /// });
+    /// let todos_path = MyState::path().todos();
+    ///
+    /// context.vec_retain(todos_path, |todo: &Todo| !todo.completed);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&todos_path).len(), 1);
+    /// ```
+    pub fn vec_retain<Path, Value, F, const SAFE: bool>(&self, path: Path, predicate: F)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+        F: FnMut(&Value) -> bool + 'static,
+    {
+        self.queue(change::vec_retain(path, predicate));
+    }
+
+    /// Remove an item from a [`Vec`] by swapping it with the last item.
+    ///
+    /// This is O(1) after finding the item, but does not preserve the order of
+    /// the items. Use [`vec_remove`](Self::vec_remove) if the order matters.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState, VecItem};
+    ///
+    /// struct TestItem {
+    ///     id: u32,
+    /// }
+    ///
+    /// impl VecItem for TestItem {
+    ///     type Id = u32;
+    ///
+    ///     fn get_id(&self) -> Self::Id {
+    ///         self.id
+    ///     }
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: Vec<TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     items: vec![TestItem { id: 10 }, TestItem { id: 11 }, TestItem { id: 12 }],
+    /// });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.vec_swap_remove(items_path, 10);
+    /// context.apply();
+    ///
+    /// let ids: Vec<u32> = context.get(&items_path).iter().map(|item| item.id).collect();
+    /// assert_eq!(ids, [12, 11]);
+    /// ```
+    pub fn vec_swap_remove<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.queue(change::vec_swap_remove(path, id));
+    }
+
+    /// Move an item of a [`Vec`] to a new index, shifting the items in between.
+    ///
+    /// Indices past the end move the item to the end. If there is no item with
+    /// the given id, a [`MissingId`](crate::MissingId) error is returned in the
+    /// [`ApplyReport`].
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, MissingId, RustState, VecItem};
+    ///
+    /// struct TestItem {
+    ///     id: u32,
+    /// }
+    ///
+    /// impl VecItem for TestItem {
+    ///     type Id = u32;
+    ///
+    ///     fn get_id(&self) -> Self::Id {
+    ///         self.id
+    ///     }
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: Vec<TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     items: vec![TestItem { id: 10 }, TestItem { id: 11 }, TestItem { id: 12 }],
+    /// });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.vec_move(items_path, 12, 0);
+    /// context.vec_move(items_path, 13, 0);
+    /// let report = context.apply();
+    ///
+    /// let ids: Vec<u32> = context.get(&items_path).iter().map(|item| item.id).collect();
+    /// assert_eq!(ids, [12, 10, 11]);
+    /// assert!(report.errors()[0].is::<MissingId>());
+    /// ```
+    pub fn vec_move<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id, new_index: usize)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.queue(change::vec_move(path, id, new_index));
+    }
+
+    /// Remove all items of a [`Vec`] that have the same id as an earlier item.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState, VecItem};
+    ///
+    /// struct TestItem {
+    ///     id: u32,
+    ///     name: &'static str,
+    /// }
+    ///
+    /// impl VecItem for TestItem {
+    ///     type Id = u32;
+    ///
+    ///     fn get_id(&self) -> Self::Id {
+    ///         self.id
+    ///     }
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: Vec<TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     items: vec![
+    ///         TestItem { id: 10, name: "First" },
+    ///         TestItem { id: 11, name: "Other" },
+    ///         TestItem { id: 10, name: "Duplicate" },
+    ///     ],
+    /// });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.vec_dedup(items_path);
+    /// context.apply();
+    ///
+    /// let names: Vec<&str> = context.get(&items_path).iter().map(|item| item.name).collect();
+    /// assert_eq!(names, ["First", "Other"]);
+    /// ```
+    pub fn vec_dedup<Path, Value, const SAFE: bool>(&self, path: Path)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.queue(change::vec_dedup(path));
+    }
+
+    /// Shorten a [`Vec`] to at most `len` items, dropping the rest.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     recent_files: Vec<&'static str>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     recent_files: vec!["a.txt", "b.txt", "c.txt"],
+    /// });
+    /// let recent_files_path = MyState::path().recent_files();
+    ///
+    /// context.vec_truncate(recent_files_path, 2);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&recent_files_path), &["a.txt", "b.txt"]);
+    /// ```
+    pub fn vec_truncate<Path, Value, const SAFE: bool>(&self, path: Path, len: usize)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.queue(change::vec_truncate(path, len));
+    }
+
+    /// Remove the last item of a [`Vec`]. The removed item, or `None` if the
+    /// vector was empty, is passed to the callback when the change is applied.
+    ///
+    /// Example:
+    /// ```
+    /// use std::sync::mpsc::channel;
+    ///
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     messages: Vec<String>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     messages: vec!["Hello".to_owned(), "World".to_owned()],
+    /// });
+    /// let messages_path = MyState::path().messages();
+    /// let (sender, receiver) = channel();
+    ///
+    /// context.vec_pop(messages_path, move |message| sender.send(message).unwrap());
+    /// context.apply();
+    ///
+    /// assert_eq!(receiver.recv().unwrap().as_deref(), Some("World"));
+    /// assert_eq!(context.get(&messages_path).len(), 1);
+    /// ```
+    pub fn vec_pop<Path, Value, F, const SAFE: bool>(&self, path: Path, callback: F)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: 'static,
+        F: FnOnce(Option<Value>) + 'static,
+    {
+        self.queue(change::vec_pop(path, callback));
+    }
+
+    /// Push an item to a [`Vec`], unless an item with the same id already
+    /// exists when the change is applied.
+    ///
+    /// If the item is not inserted, a [`DuplicateId`](crate::DuplicateId) error
+    /// is returned in the [`ApplyReport`].
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, DuplicateId, RustState, VecItem};
+    ///
+    /// struct TestItem {
+    ///     id: u32,
+    /// }
+    ///
+    /// impl VecItem for TestItem {
+    ///     type Id = u32;
+    ///
+    ///     fn get_id(&self) -> Self::Id {
+    ///         self.id
+    ///     }
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: Vec<TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { items: Vec::new() });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.vec_push_unique(items_path, TestItem { id: 10 });
+    /// context.vec_push_unique(items_path, TestItem { id: 10 });
+    /// let report = context.apply();
+    ///
+    /// assert_eq!(context.get(&items_path).len(), 1);
+    /// assert!(report.errors()[0].is::<DuplicateId>());
+    /// ```
+    pub fn vec_push_unique<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.queue(change::vec_push_unique(path, value));
+    }
+
+    /// Append a string slice to a [`String`].
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     name: String,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { name: String::from("rust") });
+    /// let name_path = MyState::path().name();
+    ///
+    /// context.string_push_str(name_path, "_state");
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&name_path), "rust_state");
+    /// ```
+    pub fn string_push_str<Path, const SAFE: bool>(&self, path: Path, string: impl Into<String>)
+    where
+        Path: crate::Path<State, String, SAFE>,
+    {
+        self.queue(change::string_push_str(path, string.into()));
+    }
+
+    /// Remove all contents of a [`String`].
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     name: String,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { name: String::from("rust_state") });
+    /// let name_path = MyState::path().name();
+    ///
+    /// context.string_clear(name_path);
+    /// context.apply();
+    ///
+    /// assert!(context.get(&name_path).is_empty());
+    /// ```
+    pub fn string_clear<Path, const SAFE: bool>(&self, path: Path)
+    where
+        Path: crate::Path<State, String, SAFE>,
+    {
+        self.queue(change::string_clear(path));
+    }
+
+    /// Replace a byte range of a [`String`] with another string.
+    ///
+    /// If the range is out of bounds or does not lie on [`char`] boundaries,
+    /// the string is left unchanged.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     name: String,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { name: String::from("rust_state") });
+    /// let name_path = MyState::path().name();
+    ///
+    /// context.string_replace_range(name_path, 5..10, "context");
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&name_path), "rust_context");
+    /// ```
+    pub fn string_replace_range<Path, const SAFE: bool>(&self, path: Path, range: Range<usize>, replacement: impl Into<String>)
+    where
+        Path: crate::Path<State, String, SAFE>,
+    {
+        self.queue(change::string_replace_range(path, range, replacement.into()));
+    }
+
+    /// Set or clear a flag of a bit field.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// const VISIBLE: u32 = 1 << 3;
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     flags: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { flags: 0 });
+    /// let flags_path = MyState::path().flags();
+    ///
+    /// context.flag_set(flags_path, VISIBLE, true);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&flags_path), &VISIBLE);
+    /// ```
+    pub fn flag_set<Path, Value, const SAFE: bool>(&self, path: Path, flag: Value, value: bool)
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: Flags,
+    {
+        self.queue(change::flag_set(path, flag, value));
+    }
+
+    /// Toggle a flag of a bit field.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// const VISIBLE: u32 = 1 << 3;
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     flags: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { flags: VISIBLE });
+    /// let flags_path = MyState::path().flags();
+    ///
+    /// context.flag_toggle(flags_path, VISIBLE);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&flags_path), &0);
+    /// ```
+    pub fn flag_toggle<Path, Value, const SAFE: bool>(&self, path: Path, flag: Value)
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: Flags,
+    {
+        self.queue(change::flag_toggle(path, flag));
+    }
+
+    /// Insert an item into a [`HashMap`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashMap;
+    /// use rust_state::{Context, RustState, MapItem};
+    ///
+    /// struct TestItem;
+    //
+    /// impl MapItem for TestItem {
+    ///     type Id = u32;
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: HashMap<u32, TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { items: HashMap::new() });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.map_insert(items_path, 10, TestItem);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&items_path).len(), 1);
+    /// ```
+    pub fn map_insert<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id, value: Value)
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+    {
+        self.queue(change::map_insert(path, id, value));
+    }
+
+    /// Insert an item with default value into a [`HashMap`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashMap;
+    /// use rust_state::{Context, RustState, MapItem};
+    ///
+    /// #[derive(Default)]
+    /// struct TestItem;
+    //
+    /// impl MapItem for TestItem {
+    ///     type Id = u32;
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: HashMap<u32, TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { items: HashMap::new() });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.map_insert_default(items_path, 10);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&items_path).len(), 1);
+    /// ```
+    pub fn map_insert_default<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + Default + 'static,
+    {
+        self.queue(change::map_insert_default(path, id));
+    }
+
+    /// Insert an item into a [`HashMap`] and pass the item it displaced, if
+    /// any, to the callback.
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashMap;
+    /// use std::sync::mpsc::channel;
+    ///
+    /// use rust_state::{Context, MapItem, RustState};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct TestItem(u32);
+    ///
+    /// impl MapItem for TestItem {
+    ///     type Id = u32;
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: HashMap<u32, TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     items: HashMap::from([(10, TestItem(1))]),
+    /// });
+    /// let items_path = MyState::path().items();
+    /// let (sender, receiver) = channel();
+    ///
+    /// context.map_replace(items_path, 10, TestItem(2), move |previous| sender.send(previous).unwrap());
+    /// context.apply();
+    ///
+    /// assert_eq!(receiver.recv().unwrap(), Some(TestItem(1)));
+    /// assert_eq!(context.get(&items_path)[&10], TestItem(2));
+    /// ```
+    pub fn map_replace<Path, Value, F, const SAFE: bool>(&self, path: Path, id: Value::Id, value: Value, callback: F)
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnOnce(Option<Value>) + 'static,
+    {
+        self.queue(change::map_replace(path, id, value, callback));
+    }
+
+    /// Remove an item from a [`HashMap`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashMap;
+    /// use rust_state::{Context, RustState, MapItem};
+    ///
+    /// struct TestItem;
+    //
+    /// impl MapItem for TestItem {
+    ///     type Id = u32;
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: HashMap<u32, TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { items: HashMap::from([(10,
+    /// TestItem)]) }); let items_path = MyState::path().items();
+    ///
+    /// context.map_remove(items_path, 10);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&items_path).len(), 0);
+    /// ```
+    pub fn map_remove<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+    {
+        self.queue(change::map_remove(path, id));
+    }
+
+    /// Remove all items from a [`HashMap`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use rust_state::{Context, MapItem, RustState};
+    ///
+    /// struct TestItem;
+    ///
+    /// impl MapItem for TestItem {
+    ///     type Id = u32;
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: HashMap<u32, TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     items: HashMap::from([(10, TestItem), (11, TestItem)]),
+    /// });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.map_clear(items_path);
+    /// context.apply();
+    ///
+    /// assert!(context.get(&items_path).is_empty());
+    /// ```
+    pub fn map_clear<Path, Value, const SAFE: bool>(&self, path: Path)
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+    {
+        self.queue(change::map_clear(path));
+    }
+
+    /// Only keep the items of a [`HashMap`] for which the predicate returns
+    /// `true`.
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use rust_state::{Context, MapItem, RustState};
+    ///
+    /// struct Session {
+    ///     expired: bool,
+    /// }
+    ///
+    /// impl MapItem for Session {
+    ///     type Id = u32;
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     sessions: HashMap<u32, Session>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     sessions: HashMap::from([(10, Session { expired: true }), (11, Session { expired: false })]),
+    /// });
+    /// let sessions_path = MyState::path().sessions();
+    ///
+    /// context.map_retain(sessions_path, |_, session: &mut Session| !session.expired);
+    /// context.apply();
+    ///
+    /// assert!(context.get(&sessions_path).contains_key(&11));
+    /// assert_eq!(context.get(&sessions_path).len(), 1);
+    /// ```
+    pub fn map_retain<Path, Value, F, const SAFE: bool>(&self, path: Path, predicate: F)
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnMut(&Value::Id, &mut Value) -> bool + 'static,
+    {
+        self.queue(change::map_retain(path, predicate));
+    }
+
+    /// Insert an item into a [`HashMap`] if there is no item with the same id
+    /// when the change is applied. The item is only created if it is inserted.
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use rust_state::{Context, MapItem, RustState};
+    ///
+    /// struct TestItem {
+    ///     name: String,
+    /// }
+    ///
+    /// impl MapItem for TestItem {
+    ///     type Id = u32;
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: HashMap<u32, TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     items: HashMap::from([(10, TestItem { name: "Existing".to_owned() })]),
+    /// });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.map_get_or_insert_with(items_path, 10, || TestItem { name: "New".to_owned() });
+    /// context.map_get_or_insert_with(items_path, 11, || TestItem { name: "New".to_owned() });
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&items_path)[&10].name, "Existing");
+    /// assert_eq!(context.get(&items_path)[&11].name, "New");
+    /// ```
+    pub fn map_get_or_insert_with<Path, Value, F, const SAFE: bool>(&self, path: Path, id: Value::Id, create: F)
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnOnce() -> Value + 'static,
+    {
+        self.queue(change::map_get_or_insert_with(path, id, create));
+    }
+
+    /// Update an item of a [`HashMap`] with a closure.
+    ///
+    /// The map is only followed once. If there is no item with the given id, a
+    /// [`MissingId`](crate::MissingId) error is returned in the
+    /// [`ApplyReport`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use rust_state::{Context, MapItem, MissingId, RustState};
+    ///
+    /// struct TestItem {
+    ///     count: u32,
+    /// }
+    ///
+    /// impl MapItem for TestItem {
+    ///     type Id = u32;
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: HashMap<u32, TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     items: HashMap::from([(10, TestItem { count: 0 })]),
+    /// });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.map_update(items_path, 10, |item: &mut TestItem| item.count += 1);
+    /// context.map_update(items_path, 11, |item: &mut TestItem| item.count += 1);
+    /// let report = context.apply();
+    ///
+    /// assert_eq!(context.get(&items_path)[&10].count, 1);
+    /// assert!(report.errors()[0].is::<MissingId>());
+    /// ```
+    pub fn map_update<Path, Value, F, const SAFE: bool>(&self, path: Path, id: Value::Id, closure: F)
+    where
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnOnce(&mut Value) + 'static,
+    {
+        self.queue(change::map_update(path, id, closure));
+    }
+
+    /// Insert an item into a [`BTreeMap`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rust_state::{BTreeMapLookupExt, Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: BTreeMap<u32, &'static str>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { items: BTreeMap::new() });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.btree_insert(items_path, 20, "second");
+    /// context.btree_insert(items_path, 10, "first");
+    /// context.apply();
+    ///
+    /// let first_path = items_path.first();
+    /// assert_eq!(context.try_get(&first_path), Some(&"first"));
+    /// ```
+    pub fn btree_insert<Path, Key, Value, const SAFE: bool>(&self, path: Path, key: Key, value: Value)
+    where
+        Path: crate::Path<State, BTreeMap<Key, Value>, SAFE>,
+        Key: Ord + 'static,
+        Value: 'static,
+    {
+        self.queue(change::btree_insert(path, key, value));
+    }
+
+    /// Remove an item from a [`BTreeMap`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: BTreeMap<u32, &'static str>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     items: BTreeMap::from([(10, "first"), (20, "second")]),
+    /// });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.btree_remove(items_path, 10);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&items_path).len(), 1);
+    /// ```
+    pub fn btree_remove<Path, Key, Value, const SAFE: bool>(&self, path: Path, key: Key)
+    where
+        Path: crate::Path<State, BTreeMap<Key, Value>, SAFE>,
+        Key: Ord + 'static,
+        Value: 'static,
+    {
+        self.queue(change::btree_remove(path, key));
+    }
+
+    /// Insert a value into a [`HashSet`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashSet;
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     tags: HashSet<&'static str>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { tags: HashSet::new() });
+    /// let tags_path = MyState::path().tags();
+    ///
+    /// context.set_insert(tags_path, "important");
+    /// context.apply();
+    ///
+    /// assert!(context.get(&tags_path).contains("important"));
+    /// ```
+    pub fn set_insert<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<State, HashSet<Value>, SAFE>,
+        Value: Eq + Hash + 'static,
+    {
+        self.queue(change::set_insert(path, value));
+    }
+
+    /// Remove a value from a [`HashSet`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashSet;
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     tags: HashSet<&'static str>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     tags: HashSet::from(["important", "archived"]),
+    /// });
+    /// let tags_path = MyState::path().tags();
+    ///
+    /// context.set_remove(tags_path, "archived");
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&tags_path).len(), 1);
+    /// ```
+    pub fn set_remove<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<State, HashSet<Value>, SAFE>,
+        Value: Eq + Hash + 'static,
+    {
+        self.queue(change::set_remove(path, value));
+    }
+
+    /// Remove all values from a [`HashSet`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashSet;
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     tags: HashSet<&'static str>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     tags: HashSet::from(["important", "archived"]),
+    /// });
+    /// let tags_path = MyState::path().tags();
+    ///
+    /// context.set_clear(tags_path);
+    /// context.apply();
+    ///
+    /// assert!(context.get(&tags_path).is_empty());
+    /// ```
+    pub fn set_clear<Path, Value, const SAFE: bool>(&self, path: Path)
+    where
+        Path: crate::Path<State, HashSet<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.queue(change::set_clear(path));
+    }
+
+    /// Insert an item into a [`SlotMap`]. The callback receives the key of the
+    /// new item once the change is applied.
+    ///
+    /// Only available with the `slotmap` feature.
+    ///
+    /// Example:
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    ///
+    /// use rust_state::{Context, RustState, SlotMapLookupExt};
+    /// use slotmap::{DefaultKey, SlotMap};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: SlotMap<DefaultKey, u32>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { items: SlotMap::new() });
+    /// let items_path = MyState::path().items();
+    ///
+    /// let key = Rc::new(Cell::new(None));
+    /// let inserted_key = key.clone();
+    ///
+    /// context.slotmap_insert(items_path, 10, move |key| inserted_key.set(Some(key)));
+    /// context.apply();
+    ///
+    /// let item_path = items_path.lookup(key.get().unwrap());
+    /// assert_eq!(context.try_get(&item_path), Some(&10));
+    /// ```
+    #[cfg(feature = "slotmap")]
+    pub fn slotmap_insert<Path, K, Value, F, const SAFE: bool>(&self, path: Path, value: Value, callback: F)
+    where
+        Path: crate::Path<State, SlotMap<K, Value>, SAFE>,
+        K: Key + 'static,
+        Value: 'static,
+        F: FnOnce(K) + 'static,
+    {
+        self.queue(change::slotmap_insert(path, value, callback));
+    }
+
+    /// Remove an item from a [`SlotMap`].
+    ///
+    /// Only available with the `slotmap` feature.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    /// use slotmap::{DefaultKey, SlotMap};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: SlotMap<DefaultKey, u32>,
+    /// }
+    ///
+    /// let mut items = SlotMap::new();
+    /// let key = items.insert(10);
+    ///
+    /// let mut context = Context::new(MyState { items });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.slotmap_remove(items_path, key);
+    /// context.apply();
+    ///
+    /// assert!(context.get(&items_path).is_empty());
+    /// ```
+    #[cfg(feature = "slotmap")]
+    pub fn slotmap_remove<Path, K, Value, const SAFE: bool>(&self, path: Path, key: K)
+    where
+        Path: crate::Path<State, SlotMap<K, Value>, SAFE>,
+        K: Key + 'static,
+        Value: 'static,
+    {
+        self.queue(change::slotmap_remove(path, key));
+    }
+
+    /// Push an item into a [`Vec`] stored in a [`HashMap`], creating an empty
+    /// vector for the key if there is none yet.
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashMap;
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     messages: HashMap<&'static str, Vec<String>>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { messages: HashMap::new() });
+    /// let messages_path = MyState::path().messages();
+    ///
+    /// context.map_vec_push(messages_path, "general", "Hello".to_owned());
+    /// context.map_vec_push(messages_path, "general", "World".to_owned());
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&messages_path)["general"], vec!["Hello", "World"]);
+    /// ```
+    pub fn map_vec_push<Path, Key, Value, const SAFE: bool>(&self, path: Path, key: Key, value: Value)
+    where
+        Path: crate::Path<State, HashMap<Key, Vec<Value>>, SAFE>,
+        Key: Eq + Hash + 'static,
+        Value: 'static,
+    {
+        self.queue(change::map_vec_push(path, key, value));
+    }
+
+    /// Insert a value into a [`BTreeSet`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     ids: BTreeSet<u32>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { ids: BTreeSet::new() });
+    /// let ids_path = MyState::path().ids();
+    ///
+    /// context.btree_set_insert(ids_path, 10);
+    /// context.apply();
+    ///
+    /// assert!(context.get(&ids_path).contains(&10));
+    /// ```
+    pub fn btree_set_insert<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<State, BTreeSet<Value>, SAFE>,
+        Value: Ord + 'static,
+    {
+        self.queue(change::btree_set_insert(path, value));
+    }
+
+    /// Remove a value from a [`BTreeSet`].
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::BTreeSet;
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     ids: BTreeSet<u32>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     ids: BTreeSet::from([10, 20]),
+    /// });
+    /// let ids_path = MyState::path().ids();
+    ///
+    /// context.btree_set_remove(ids_path, 10);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&ids_path).len(), 1);
+    /// ```
+    pub fn btree_set_remove<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<State, BTreeSet<Value>, SAFE>,
+        Value: Ord + 'static,
+    {
+        self.queue(change::btree_set_remove(path, value));
+    }
+
+    /// Get the context holding the extensions of this context, a type map for
+    /// values that are not part of the state.
+    ///
+    /// This allows plugins to stash their own state without the root struct
+    /// knowing about them. The extensions are a separate [`Context`], so all
+    /// functions of the context can be used with paths from
+    /// [`extension_path`](Self::extension_path). Pending changes to the
+    /// extensions are applied at the end of every [`apply`](Self::apply),
+    /// regardless of its budget.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// struct PluginState {
+    ///     enabled: bool,
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct State {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(State { value: 0 });
+    /// let plugin_path = context.extension_path::<PluginState>();
+    ///
+    /// assert!(context.extensions().try_get(&plugin_path).is_none());
+    ///
+    /// context.insert_extension(PluginState { enabled: false });
+    /// context.extensions().update_value(plugin_path.enabled(), true);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.extensions().try_get(&plugin_path.enabled()), Some(&true));
+    ///
+    /// context.remove_extension::<PluginState>();
+    /// context.apply();
+    ///
+    /// assert!(context.extensions().try_get(&plugin_path).is_none());
+    /// ```
+    pub fn extensions(&self) -> &Context<Extensions> {
+        self.extensions.get_or_init(|| Box::new(Context::new(Extensions::default())))
+    }
+
+    /// Get a path to the extension of type `Extension`. See
+    /// [`extensions`](Self::extensions).
+    ///
+    /// This path is *not* safe. It returns `None` at runtime if there is no
+    /// extension of type `Extension`.
+    pub fn extension_path<Extension: 'static>(&self) -> impl crate::Path<Extensions, Extension, false> + Copy {
+        RootPath::new().extension::<Extension>()
+    }
+
+    /// Insert an extension, replacing any previous extension of the same type.
+    /// See [`extensions`](Self::extensions).
+    pub fn insert_extension<Extension: 'static>(&self, value: Extension) {
+        self.extensions().queue(change::insert_extension(RootPath::new(), value));
+    }
+
+    /// Remove the extension of type `Extension`. See
+    /// [`extensions`](Self::extensions).
+    pub fn remove_extension<Extension: 'static>(&self) {
+        self.extensions()
+            .queue(change::remove_extension::<Extensions, Extension, RootPath<Extensions>, true>(
+                RootPath::new(),
+            ));
+    }
+
+    /// Insert a value into [`Extensions`] stored in the state, replacing any
+    /// previous value of the same type.
+    ///
+    /// See [`Extensions`] for an example.
+    pub fn insert_extension_at<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<State, Extensions, SAFE>,
+        Value: 'static,
+    {
+        self.queue(change::insert_extension(path, value));
+    }
+
+    /// Remove the value of type `Value` from [`Extensions`] stored in the
+    /// state.
+    pub fn remove_extension_at<Value, Path, const SAFE: bool>(&self, path: Path)
+    where
+        Path: crate::Path<State, Extensions, SAFE>,
+        Value: 'static,
+    {
+        self.queue(change::remove_extension::<State, Value, Path, SAFE>(path));
+    }
+
+    /// Start a [`Batch`] of changes that are queued as a single change once
+    /// committed.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     name: &'static str,
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { name: "Before", value: 5 });
+    /// let name_path = MyState::path().name();
+    /// let value_path = MyState::path().value();
+    ///
+    /// context.batch().update_value(name_path, "After").update_value(value_path, 10).commit();
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&name_path), &"After");
+    /// assert_eq!(context.get(&value_path), &10);
+    /// ```
+    pub fn batch(&self) -> Batch<'_, State> {
+        Batch::new(self)
+    }
+
+    /// Get the number of pending changes.
+    ///
+    /// A committed [`Batch`] or [`ChangeBatch`] counts as a single change.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let context = Context::new(MyState { value: 5 });
+    /// let value_path = MyState::path().value();
+    ///
+    /// context.update_value(value_path, 10);
+    /// context.update_value(value_path, 20);
+    ///
+    /// assert_eq!(context.pending_changes(), 2);
+    ///
+    /// context.clear_pending();
+    ///
+    /// assert!(!context.has_pending());
+    /// ```
+    pub fn pending_changes(&self) -> usize {
+        let previewed_changes = self.preview.borrow().as_ref().map_or(0, |preview| preview.report.applied_changes());
+        self.state_changes.borrow().len() + previewed_changes
+    }
+
+    /// Check if there are any pending changes.
+    pub fn has_pending(&self) -> bool {
+        self.pending_changes() > 0
+    }
+
+    /// Discard all pending changes without applying them.
+    ///
+    /// Delayed changes that are not due yet are kept, use
+    /// [`clear_delayed`](Self::clear_delayed) to discard them.
+    pub fn clear_pending(&self) {
+        // Take the changes first, so they are dropped after the queue is released.
+        let state_changes = self.state_changes.take();
+        let coalesced_changes = self.coalesced_changes.take();
+        let preview = self.preview.take();
+        drop((state_changes, coalesced_changes, preview));
+    }
+
+    /// Discard all delayed changes that are not due yet.
+    pub fn clear_delayed(&self) {
+        let delayed_changes = self.delayed_changes.take();
+        drop(delayed_changes);
+    }
+
+    /// Queue all changes of a [`ChangeBatch`] as a single change.
+    ///
+    /// Empty batches are ignored.
+    pub fn enqueue_batch(&self, batch: ChangeBatch<State>)
+    where
+        State: 'static,
+    {
+        if !batch.is_empty() {
+            self.queue_batch(batch.into_changes());
+        }
+    }
+
+    /// Register a hook that runs at the start of every
+    /// [`apply`](Self::apply), before any changes are applied.
+    ///
+    /// Changes queued by the hook are applied in the same call.
+    ///
+    /// Example:
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    ///
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { value: 5 });
+    /// let pending = Rc::new(Cell::new(0));
+    ///
+    /// let hook_pending = pending.clone();
+    /// context.add_pre_apply_hook(move |context| hook_pending.set(context.pending_changes()));
+    ///
+    /// context.update_value(MyState::path().value(), 10);
+    /// context.apply();
+    ///
+    /// assert_eq!(pending.get(), 1);
+    /// ```
+    pub fn add_pre_apply_hook(&mut self, hook: impl FnMut(&Context<State>) + 'static) {
+        self.pre_apply_hooks.push(Box::new(hook));
+    }
+
+    /// Register a hook that runs at the end of every [`apply`](Self::apply),
+    /// after changes were applied.
+    ///
+    /// The hook runs after every round of changes and receives the report of
+    /// that round. Changes queued by the hook are applied in another round,
+    /// see [`apply`](Self::apply).
+    ///
+    /// Example:
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    ///
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { value: 5 });
+    /// let renders = Rc::new(Cell::new(0));
+    ///
+    /// let hook_renders = renders.clone();
+    /// context.add_post_apply_hook(move |_, report| {
+    ///     if report.has_changes() {
+    ///         hook_renders.set(hook_renders.get() + 1);
+    ///     }
+    /// });
+    ///
+    /// context.update_value(MyState::path().value(), 10);
+    /// context.apply();
+    /// context.apply();
+    ///
+    /// assert_eq!(renders.get(), 1);
+    /// ```
+    pub fn add_post_apply_hook(&mut self, hook: impl FnMut(&Context<State>, &ApplyReport) + 'static) {
+        self.post_apply_hooks.push(Box::new(hook));
+    }
+
+    /// Apply any pending changes.
+    ///
+    /// Returns an [`ApplyReport`] containing the number of applied changes and
+    /// the errors returned by fallible changes.
+    ///
+    /// # Cascading changes
+    ///
+    /// Changes are applied in rounds. Each round applies all changes that are
+    /// pending at its start and then runs the
+    /// [post-apply hooks](Self::add_post_apply_hook). If the hooks queue new
+    /// changes, another round is started, so cascading updates are applied in
+    /// the same call. After
+    /// [`set_max_apply_rounds`](Self::set_max_apply_rounds) rounds, any
+    /// remaining changes are left pending for the next call.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: &'static str,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { value: "Before" });
+    /// let value_path = MyState::path().value();
+    ///
+    /// context.update_value(value_path, "After");
+    ///
+    /// assert_eq!(context.get(&value_path), &"Before");
+    ///
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&value_path), &"After");
+    /// ```
+    pub fn apply(&mut self) -> ApplyReport {
+        self.apply_with_budget(Budget::Unlimited)
+    }
+
+    /// Apply at most `max_changes` pending changes. Any remaining changes stay
+    /// pending for the next call.
+    ///
+    /// This behaves just like [`apply`](Self::apply), including hooks and
+    /// delayed changes, but stops once the budget is used up. The number of
+    /// remaining changes is available through
+    /// [`ApplyReport::remaining_changes`].
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     values: Vec<u32>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { values: Vec::new() });
+    /// let values_path = MyState::path().values();
+    ///
+    /// (0..5).for_each(|value| context.vec_push(values_path, value));
+    ///
+    /// let report = context.apply_budgeted(3);
+    ///
+    /// assert_eq!(report.remaining_changes(), 2);
+    /// assert_eq!(context.get(&values_path), &[0, 1, 2]);
+    ///
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&values_path), &[0, 1, 2, 3, 4]);
+    /// ```
+    pub fn apply_budgeted(&mut self, max_changes: usize) -> ApplyReport {
+        self.apply_with_budget(Budget::Changes(max_changes))
+    }
+
+    /// Apply pending changes until `duration` has passed. Any remaining
+    /// changes stay pending for the next call.
+    ///
+    /// The time is checked before every change, so a single slow change can
+    /// still exceed the duration. See [`apply_budgeted`](Self::apply_budgeted).
+    pub fn apply_for(&mut self, duration: Duration) -> ApplyReport {
+        self.apply_with_budget(Budget::Until(Instant::now() + duration))
+    }
+
+    fn apply_with_budget(&mut self, budget: Budget) -> ApplyReport {
+        #[cfg(feature = "metrics")]
+        let start = Instant::now();
+
+        let mut report = self.apply_rounds(budget);
+
+        if let Some(extensions) = self.extensions.get_mut() {
+            report.merge(extensions.apply());
+        }
+
+        #[cfg(feature = "metrics")]
+        self.metrics.get_mut().record_apply(&report, start.elapsed());
+
+        if let Some(error_handler) = &mut self.error_handler {
+            report.errors().iter().for_each(|error| error_handler(error.as_ref()));
+        }
+
+        if let Some(autosave) = &mut self.autosave {
+            autosave.after_apply(&self.state, report.has_changes());
+        }
+
+        if self.strict && report.has_errors() {
+            panic!("{} changes failed to apply in strict mode", report.errors().len());
+        }
+
+        report
+    }
+
+    fn apply_rounds(&mut self, mut budget: Budget) -> ApplyReport {
+        self.queue_due_changes();
+
+        let mut pre_apply_hooks = std::mem::take(&mut self.pre_apply_hooks);
+        pre_apply_hooks.iter_mut().for_each(|hook| hook(self));
+        self.pre_apply_hooks = pre_apply_hooks;
+
+        let mut report = ApplyReport::default();
+
+        for _ in 0..self.max_apply_rounds {
+            let round_report = self.apply_round(&mut budget);
+
+            let mut post_apply_hooks = std::mem::take(&mut self.post_apply_hooks);
+            post_apply_hooks.iter_mut().for_each(|hook| hook(self, &round_report));
+            self.post_apply_hooks = post_apply_hooks;
+
+            report.merge(round_report);
+
+            if !self.has_pending() || budget.is_exhausted() {
+                report.set_remaining_changes(self.pending_changes());
+                return report;
+            }
+        }
+
+        report.set_remaining_changes(self.pending_changes());
+        report
+    }
+
+    /// Advance all delayed changes and queue the ones that are due in front of
+    /// the pending changes.
+    fn queue_due_changes(&mut self) {
+        let now = Instant::now();
+        let delayed_changes = std::mem::take(self.delayed_changes.get_mut());
+        let mut due_changes = Vec::new();
+
+        for (delay, queued_change) in delayed_changes {
+            match delay.advance(now) {
+                Some(remaining) => self.delayed_changes.get_mut().push((remaining, queued_change)),
+                None => due_changes.push(queued_change),
+            }
+        }
+
+        self.state_changes.get_mut().splice(0..0, due_changes);
+    }
+
+    /// Commit a pending [`preview`](Self::preview), moving its commands to the
+    /// history and calling its callbacks. Returns the report of the preview,
+    /// if there was one.
+    fn commit_preview(&mut self) -> Option<ApplyReport> {
+        let preview = self.preview.get_mut().take()?;
+
+        self.state = preview.state;
+        self.history.append(preview.history);
+        preview.callbacks.call();
+
+        Some(preview.report)
+    }
+
+    fn apply_round(&mut self, budget: &mut Budget) -> ApplyReport {
+        let mut report = ApplyReport::default();
+
+        let mut state_changed = false;
+
+        if let Some(preview_report) = self.commit_preview() {
+            report.merge(preview_report);
+            state_changed = true;
+        }
+
+        let state_changes = self.take_pending();
+        let mut callbacks = Callbacks::default();
+
+        if state_changed || !state_changes.is_empty() {
+            self.version = self.version.wrapping_add(1);
+        }
+
+        let (round_report, remaining_changes) = apply_changes(
+            &mut self.state,
+            &mut self.history,
+            &mut self.validators,
+            &mut callbacks,
+            state_changes,
+            self.apply_order,
+            budget,
+            false,
+        );
+        self.state_changes.get_mut().splice(0..0, remaining_changes);
+        report.merge(round_report);
+        callbacks.call();
+
+        if cfg!(debug_assertions) {
+            for check in &mut self.id_reuse_checks {
+                check.check(&self.state).into_iter().for_each(|error| report.push_error(error));
+            }
+        }
+
+        self.vec_indices.values().for_each(|index| index.invalidate());
+
+        report
+    }
+
+    /// Get a copy of the state with all pending changes applied, without
+    /// committing them.
+    ///
+    /// Since changes can only run once, the pending changes are applied to a
+    /// copy of the state that is kept until the next [`apply`](Self::apply),
+    /// which commits it, or [`clear_pending`](Self::clear_pending), which
+    /// discards it. Until then the changes still count as pending, and later
+    /// changes are applied on top of them.
+    ///
+    /// Callbacks, like the ones passed to [`take`](Self::take), are only called
+    /// and [`Command`]s are only added to the history once the preview is
+    /// committed. Undo and redo can't be previewed, so they and all changes
+    /// queued after them stay pending. Hooks and id reuse checks don't run for
+    /// previews. Mutable access to the state, for example through
+    /// [`update_now`](Self::update_now), commits the preview.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(Clone, RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { value: 5 });
+    /// let value_path = MyState::path().value();
+    ///
+    /// context.update_value(value_path, 10);
+    ///
+    /// assert_eq!(context.preview().value, 10);
+    /// assert_eq!(context.get(&value_path), &5);
+    /// assert!(context.has_pending());
+    ///
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&value_path), &10);
+    /// ```
+    ///
+    /// Discarding a preview:
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    ///
+    /// use rust_state::{Context, RustState, SetValue};
+    ///
+    /// #[derive(Clone, RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    ///     name: String,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { value: 5, name: "old".to_owned() });
+    /// let value_path = MyState::path().value();
+    /// let name_path = MyState::path().name();
+    /// let taken = Rc::new(Cell::new(false));
+    ///
+    /// context.execute(SetValue::new(value_path, 10));
+    /// context.take(name_path, {
+    ///     let taken = taken.clone();
+    ///     move |_| taken.set(true)
+    /// });
+    ///
+    /// assert_eq!(context.preview().value, 10);
+    /// assert!(!taken.get());
+    ///
+    /// context.clear_pending();
+    ///
+    /// assert_eq!(context.get(&value_path), &5);
+    /// assert!(!context.can_undo());
+    /// assert!(!taken.get());
+    /// ```
+    pub fn preview(&mut self) -> State
+    where
+        State: Clone,
+    {
+        let state_changes = self.take_pending();
+        let preview = self.preview.get_mut().get_or_insert_with(|| Preview {
+            state: self.state.clone(),
+            report: ApplyReport::default(),
+            history: History::default(),
+            callbacks: Callbacks::default(),
+        });
+
+        let (report, remaining_changes) = apply_changes(
+            &mut preview.state,
+            &mut preview.history,
+            &mut self.validators,
+            &mut preview.callbacks,
+            state_changes,
+            self.apply_order,
+            &mut Budget::Unlimited,
+            true,
+        );
+        preview.report.merge(report);
+        self.state_changes.get_mut().splice(0..0, remaining_changes);
+
+        preview.state.clone()
+    }
+
+    /// Apply any pending changes and return the root state.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let context = Context::new(MyState { value: 5 });
+    ///
+    /// context.update_value(MyState::path().value(), 10);
+    ///
+    /// assert_eq!(context.into_inner().value, 10);
+    /// ```
+    pub fn into_inner(mut self) -> State {
+        self.apply();
+        self.state
+    }
+
+    /// Detect re-used [`VecItem::Id`]s in a [`Vec`].
+    ///
+    /// [`VecItem::Id`]s are required to be unique and not be re-used,
+    /// otherwise lookup paths might resolve to a different item after an
+    /// update. After calling this function, every [`apply`](Self::apply) will
+    /// compare the ids in the vector to all ids previously seen and report
+    /// duplicate ids as [`DuplicateId`](crate::DuplicateId) and re-used ids as
+    /// [`ReusedId`](crate::ReusedId) errors in the [`ApplyReport`] and to the
+    /// [error handler](Self::set_error_handler).
+    ///
+    /// The check is only performed in debug builds.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, ReusedId, RustState, VecItem};
+    ///
+    /// struct TestItem {
+    ///     id: u32,
+    /// }
+    ///
+    /// impl VecItem for TestItem {
+    ///     type Id = u32;
+    ///
+    ///     fn get_id(&self) -> Self::Id {
+    ///         self.id
+    ///     }
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: Vec<TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { items: vec![TestItem { id: 10 }] });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.detect_id_reuse(items_path);
+    ///
+    /// context.vec_remove(items_path, 10);
+    /// assert!(!context.apply().has_errors());
+    ///
+    /// context.vec_push(items_path, TestItem { id: 10 });
+    /// let report = context.apply();
+    ///
+    /// if cfg!(debug_assertions) {
+    ///     assert!(report.errors()[0].is::<ReusedId>());
+    /// }
+    /// ```
+    pub fn detect_id_reuse<Path, Value, const SAFE: bool>(&mut self, path: Path)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        let mut tracker = IdReuseTracker::new(path);
+
+        if cfg!(debug_assertions) {
+            // Only record the current ids. Duplicates are still present on the next
+            // check, so they are reported then.
+            tracker.check(&self.state);
+        }
+
+        self.id_reuse_checks.push(Box::new(tracker));
+    }
+
+    /// Maintain an index from the ids of the items in a vector to their
+    /// position, so [`lookup_indexed`](Self::lookup_indexed) can find items
+    /// without scanning the vector.
+    ///
+    /// The index is rebuilt lazily on the first lookup after the vector might
+    /// have changed. Indices are stored by the [`key`](crate::PathRead::key)
+    /// of the path, so different paths to vectors of the same type get their
+    /// own index, and indexing the same path twice keeps the existing index.
+    /// Paths without a key can't be told apart and are not indexed.
+    ///
+    /// Only [`lookup_indexed`](Self::lookup_indexed) uses the index. Paths
+    /// created with [`lookup`](crate::VecLookupExt::lookup) still scan the
+    /// vector.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState, VecItem};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct TestItem {
+    ///     id: u32,
+    /// }
+    ///
+    /// impl VecItem for TestItem {
+    ///     type Id = u32;
+    ///
+    ///     fn get_id(&self) -> Self::Id {
+    ///         self.id
+    ///     }
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: Vec<TestItem>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     items: vec![TestItem { id: 10 }, TestItem { id: 11 }],
+    /// });
+    /// let items_path = MyState::path().items();
+    ///
+    /// context.index_vec(items_path);
+    /// assert_eq!(context.lookup_indexed(items_path, 11), Some(&TestItem { id: 11 }));
+    ///
+    /// context.vec_remove(items_path, 10);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.lookup_indexed(items_path, 10), None);
+    /// assert_eq!(context.lookup_indexed(items_path, 11), Some(&TestItem { id: 11 }));
+    /// ```
+    ///
+    /// Vectors of the same type at different paths are indexed separately:
+    /// ```
+    /// use rust_state::{Context, RustState, VecItem, VecLookupExt};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct Child {
+    ///     id: u32,
+    /// }
+    ///
+    /// impl VecItem for Child {
+    ///     type Id = u32;
+    ///
+    ///     fn get_id(&self) -> Self::Id {
+    ///         self.id
+    ///     }
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// struct Parent {
+    ///     id: u32,
+    ///     children: Vec<Child>,
+    /// }
+    ///
+    /// impl VecItem for Parent {
+    ///     type Id = u32;
+    ///
+    ///     fn get_id(&self) -> Self::Id {
+    ///         self.id
+    ///     }
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     parents: Vec<Parent>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     parents: vec![
+    ///         Parent { id: 1, children: vec![Child { id: 10 }] },
+    ///         Parent { id: 2, children: vec![Child { id: 20 }] },
+    ///     ],
+    /// });
+    /// let parents_path = MyState::path().parents();
+    ///
+    /// context.index_vec(parents_path.lookup(1).children());
+    ///
+    /// assert_eq!(context.lookup_indexed(parents_path.lookup(1).children(), 10), Some(&Child { id: 10 }));
+    /// assert_eq!(context.lookup_indexed(parents_path.lookup(2).children(), 20), Some(&Child { id: 20 }));
+    /// assert_eq!(context.lookup_indexed(parents_path.lookup(2).children(), 10), None);
+    /// ```
+    pub fn index_vec<Path, Value, const SAFE: bool>(&mut self, path: Path)
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        let Some(key) = path.key() else {
+            return;
+        };
+
+        self.vec_indices
+            .entry(key)
+            .or_insert_with(|| Box::new(VecIdIndex::<Path, Value, SAFE>::new(path)));
+    }
+
+    /// Get the item with the given id from a vector. Uses the index registered
+    /// with [`index_vec`](Self::index_vec) if there is one and falls back to
+    /// scanning the vector otherwise.
+    pub fn lookup_indexed<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id) -> Option<&Value>
+    where
+        Path: crate::Path<State, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        let index = path
+            .key()
+            .and_then(|key| self.vec_indices.get(&key))
+            .and_then(|index| index.as_any().downcast_ref::<VecIdIndex<Path, Value, SAFE>>());
+
+        match index {
+            Some(index) => index.lookup(&self.state, id),
+            None => path.follow(&self.state)?.iter().find(|item| item.get_id() == id),
+        }
+    }
+
+    /// Register a validator for the value at a given path.
+    ///
+    /// Whenever a change writes a new value to the path, the validator is
+    /// called with it. If it returns an error, the error is returned in the
+    /// [`ApplyReport`] and the previous values of all validated paths written
+    /// by the change are restored. Values that didn't exist before the change
+    /// can't be restored and are only reported.
+    ///
+    /// Writes of the change to paths without a validator are kept, so a
+    /// change that writes multiple values might be applied partially. Paths
+    /// that always need to be consistent with each other should be validated
+    /// together through a validator on a common parent.
+    ///
+    /// Every validator keeps a copy of the last valid value, which is compared
+    /// to the current value after every change and only cloned again when a
+    /// new value was accepted. Values changed without validation, for example
+    /// through [`update_now`](Self::update_now), are accepted on the next
+    /// [`apply`](Self::apply).
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     volume: f32,
+    /// }
+    ///
+    /// #[derive(Debug)]
+    /// struct OutOfRange;
+    ///
+    /// impl std::fmt::Display for OutOfRange {
+    ///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    ///         write!(f, "volume out of range")
+    ///     }
+    /// }
+    ///
+    /// impl std::error::Error for OutOfRange {}
+    ///
+    /// let mut context = Context::new(MyState { volume: 0.5 });
+    /// let volume_path = MyState::path().volume();
+    ///
+    /// context.add_validator(volume_path, |volume: &f32| match (0.0..=1.0).contains(volume) {
+    ///     true => Ok(()),
+    ///     false => Err(OutOfRange),
+    /// });
+    ///
+    /// context.update_value(volume_path, 2.0);
+    /// let report = context.apply();
+    ///
+    /// assert!(report.has_errors());
+    /// assert_eq!(context.get(&volume_path), &0.5);
+    /// ```
+    ///
+    /// Only validated paths are restored:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     name: String,
+    ///     volume: f32,
+    ///     muted: bool,
+    /// }
+    ///
+    /// #[derive(Debug)]
+    /// struct Invalid;
+    ///
+    /// impl std::fmt::Display for Invalid {
+    ///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    ///         write!(f, "invalid value")
+    ///     }
+    /// }
+    ///
+    /// impl std::error::Error for Invalid {}
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     name: "Speaker".to_owned(),
+    ///     volume: 0.5,
+    ///     muted: false,
+    /// });
+    /// let name_path = MyState::path().name();
+    /// let volume_path = MyState::path().volume();
+    /// let muted_path = MyState::path().muted();
+    ///
+    /// context.add_validator(name_path, |name: &String| if name.is_empty() { Err(Invalid) } else { Ok(()) });
+    /// context.add_validator(volume_path, |volume: &f32| if *volume > 1.0 { Err(Invalid) } else { Ok(()) });
+    ///
+    /// context
+    ///     .batch()
+    ///     .update_value(name_path, "Headphones".to_owned())
+    ///     .update_value(volume_path, 2.0)
+    ///     .update_value(muted_path, true)
+    ///     .commit();
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&name_path), "Speaker");
+    /// assert_eq!(context.get(&volume_path), &0.5);
+    /// assert_eq!(context.get(&muted_path), &true);
+    /// ```
+    ///
+    /// A rejected [`Command`] is not recorded in the history, and the callbacks
+    /// of rejected changes are never called:
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    ///
+    /// use rust_state::{Context, RustState, SetValue};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     volume: f32,
+    /// }
+    ///
+    /// #[derive(Debug)]
+    /// struct OutOfRange;
+    ///
+    /// impl std::fmt::Display for OutOfRange {
+    ///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    ///         write!(f, "volume out of range")
+    ///     }
+    /// }
+    ///
+    /// impl std::error::Error for OutOfRange {}
+    ///
+    /// let mut context = Context::new(MyState { volume: 0.5 });
+    /// let volume_path = MyState::path().volume();
+    ///
+    /// context.add_validator(volume_path, |volume: &f32| match (0.0..=1.0).contains(volume) {
+    ///     true => Ok(()),
+    ///     false => Err(OutOfRange),
+    /// });
+    ///
+    /// context.execute(SetValue::new(volume_path, 1.0));
+    /// context.undo();
+    /// context.execute(SetValue::new(volume_path, 5.0));
+    /// let report = context.apply();
+    ///
+    /// assert_eq!(report.errors().len(), 1);
+    /// assert_eq!(context.get(&volume_path), &0.5);
+    /// assert!(!context.can_undo());
+    /// assert!(context.can_redo());
+    ///
+    /// let replaced = Rc::new(Cell::new(false));
+    /// let replaced_inner = replaced.clone();
+    ///
+    /// context.replace(volume_path, 2.0, move |_| replaced_inner.set(true));
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&volume_path), &0.5);
+    /// assert!(!replaced.get());
+    /// ```
+    pub fn add_validator<Path, Value, F, E, const SAFE: bool>(&mut self, path: Path, validate: F)
+    where
+        Path: crate::Path<State, Value, SAFE>,
+        Value: Clone + PartialEq + 'static,
+        F: Fn(&Value) -> Result<(), E> + 'static,
+        E: Error + 'static,
+    {
+        self.validators.push(Box::new(PathValidator::new(path, validate, &self.state)));
+    }
+
+    /// Create a [`ScopedContext`] rooted at a safe sub-path of the state.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     settings: Settings,
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// struct Settings {
+    ///     volume: f32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { settings: Settings { volume: 0.5 } });
+    ///
+    /// {
+    ///     let settings = context.scope(MyState::path().settings());
+    ///     settings.update_value(settings.path().volume(), 1.0);
+    /// }
+    ///
+    /// context.apply();
+    ///
+    /// assert_eq!(context.get(&MyState::path().settings().volume()), &1.0);
+    /// ```
+    pub fn scope<Path, Sub>(&self, path: Path) -> ScopedContext<'_, Sub>
+    where
+        State: 'static,
+        Path: crate::Path<State, Sub>,
+        Sub: 'static,
+    {
+        self.try_scope_any(path).unwrap()
+    }
+
+    /// Try to create a [`ScopedContext`] rooted at an unsafe sub-path of the
+    /// state. Returns `None` if the path doesn't resolve.
+    ///
+    /// If the path no longer resolves when the changes are applied, fallible
+    /// changes return an [`UnresolvedPath`](crate::UnresolvedPath) error in
+    /// the [`ApplyReport`].
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, OptionExt, RustState, UnresolvedPath};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     settings: Option<Settings>,
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// struct Settings {
+    ///     volume: f32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState {
+    ///     settings: Some(Settings { volume: 0.5 }),
+    /// });
+    /// let settings_path = MyState::path().settings();
+    ///
+    /// context.update_value(settings_path, None);
+    ///
+    /// {
+    ///     let settings = context.try_scope(settings_path.unwrapped()).unwrap();
+    ///     settings.update_value_try(settings.path().volume(), |volume| {
+    ///         *volume = 1.0;
+    ///         Ok::<_, std::fmt::Error>(())
+    ///     });
+    /// }
+    ///
+    /// let report = context.apply();
+    ///
+    /// assert!(report.errors()[0].is::<UnresolvedPath>());
+    /// ```
+    pub fn try_scope<Path, Sub>(&self, path: Path) -> Option<ScopedContext<'_, Sub>>
+    where
+        State: 'static,
+        Path: crate::Path<State, Sub, false>,
+        Sub: 'static,
+    {
+        self.try_scope_any(path)
+    }
+
+    fn try_scope_any<Path, Sub, const SAFE: bool>(&self, path: Path) -> Option<ScopedContext<'_, Sub>>
+    where
+        State: 'static,
+        Path: crate::Path<State, Sub, SAFE>,
+        Sub: 'static,
+    {
+        let sub = path.follow(&self.state)?;
+
+        Some(ScopedContext::new(sub, move |change: RecordedChange<Sub>| {
+            self.queue_recorded(change.scoped(path.clone()));
+        }))
+    }
+
+    /// Get a [`ReadOnlyContext`] that can read the state but not queue any
+    /// changes.
+    pub fn freeze(&self) -> ReadOnlyContext<'_, State> {
+        ReadOnlyContext::new(&self.state)
+    }
+
+    /// Get the output of a safe selector.
+    ///
+    /// # Panics
+    ///
+    /// This function will panic if the safe selector was unable to read the
+    /// value. This might happen if
+    /// [`ManuallyAssertExt`](crate::ManuallyAssertExt) is used incorrectly
+    /// or the [`Selector`](crate::Selector) trait is implemented incorrectly.
+    pub fn get<'a, Selector, Output>(&'a self, selector: &'a Selector) -> &'a Output
+    where
+        Selector: crate::Selector<State, Output>,
+        Output: ?Sized,
+    {
+        selector.select(&self.state).unwrap()
+    }
+
+    /// Try to get the output of an unsafe selector.
+    ///
+    /// This is deliberately only implement for unsafe selectors and not for
+    /// all selectors to encourage using the safe [`get`](Self::get)
+    /// if possible.
+    ///
+    /// For a version of this function that accepts any selector see
+    /// [`try_get_any`](Self::try_get_any).
+    pub fn try_get<'a, Selector, Output>(&'a self, selector: &'a Selector) -> Option<&'a Output>
+    where
+        Selector: crate::Selector<State, Output, false>,
+        Output: ?Sized,
+    {
+        selector.select(&self.state)
+    }
+
+    /// Get the outputs of multiple safe selectors zipped with
+    /// [`ZipExt::zip`](crate::ZipExt::zip).
+    pub fn get_zip<'a, Zip>(&'a self, zip: &'a Zip) -> Zip::Output<'a>
+    where
+        Zip: SelectAll<State>,
+    {
+        zip.select_all(&self.state).unwrap()
+    }
+
+    /// Try to get the outputs of multiple unsafe selectors zipped with
+    /// [`ZipExt::zip`](crate::ZipExt::zip). Returns `None` if any of the
+    /// selectors doesn't resolve.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, OptionExt, RustState, ZipExt};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     width: Option<u32>,
+    ///     height: Option<u32>,
+    /// }
+    ///
+    /// let context = Context::new(MyState { width: Some(10), height: None });
+    /// let size = (MyState::path().width().unwrapped(), MyState::path().height().unwrapped()).zip();
+    ///
+    /// assert_eq!(context.try_get_zip(&size), None);
+    /// ```
+    pub fn try_get_zip<'a, Zip>(&'a self, zip: &'a Zip) -> Option<Zip::Output<'a>>
+    where
+        Zip: SelectAll<State, false>,
+    {
+        zip.select_all(&self.state)
+    }
+
+    /// Get the output of an unsafe selector, or `fallback` if the selector
+    /// doesn't resolve.
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashMap;
+    ///
+    /// use rust_state::{Context, MapItem, MapLookupExt, RustState};
+    ///
+    /// struct Player {
+    ///     name: String,
+    /// }
+    ///
+    /// impl MapItem for Player {
+    ///     type Id = u32;
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     players: HashMap<u32, Player>,
+    /// }
+    ///
+    /// let context = Context::new(MyState { players: HashMap::new() });
+    /// let player_path = MyState::path().players().lookup(0);
+    /// let fallback = Player { name: "Unknown".to_owned() };
+    ///
+    /// let player = context.get_or(&player_path, &fallback);
+    ///
+    /// assert_eq!(player.name, "Unknown");
+    /// ```
+    pub fn get_or<'a, Selector, Output>(&'a self, selector: &'a Selector, fallback: &'a Output) -> &'a Output
+    where
+        Selector: crate::Selector<State, Output, false>,
+        Output: ?Sized,
+    {
+        selector.select(&self.state).unwrap_or(fallback)
+    }
+
+    /// Get the output of an unsafe selector, or [`Default::default`] if the
+    /// selector doesn't resolve.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, OptionExt, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     selected: Option<u32>,
+    /// }
+    ///
+    /// let context = Context::new(MyState { selected: None });
+    ///
+    /// assert_eq!(*context.get_or_default(&MyState::path().selected().unwrapped()), 0);
+    /// ```
+    pub fn get_or_default<'a, Selector, Output>(&'a self, selector: &'a Selector) -> Cow<'a, Output>
+    where
+        Selector: crate::Selector<State, Output, false>,
+        Output: Clone + Default,
+    {
+        match selector.select(&self.state) {
+            Some(output) => Cow::Borrowed(output),
+            None => Cow::Owned(Output::default()),
+        }
+    }
+
+    /// Check if an unsafe path currently resolves.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, OptionExt, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     selected: Option<u32>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { selected: None });
+    /// let selected_path = MyState::path().selected().unwrapped();
+    ///
+    /// assert!(!context.exists(selected_path));
+    ///
+    /// context.update_value(MyState::path().selected(), Some(10));
+    /// context.apply();
+    ///
+    /// assert!(context.exists(selected_path));
+    /// ```
+    pub fn exists<Path, Output>(&self, path: Path) -> bool
+    where
+        Path: crate::PathRead<State, Output, false>,
+        Output: ?Sized,
+    {
+        path.follow(&self.state).is_some()
+    }
+
+    /// Read the value inside a [`RefCell`] at a given path with a closure.
+    ///
+    /// Returns `None` if the path doesn't resolve or the cell is currently
+    /// borrowed mutably.
+    ///
+    /// Example:
+    /// ```
+    /// use std::cell::RefCell;
+    ///
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     legacy: RefCell<String>,
+    /// }
+    ///
+    /// let context = Context::new(MyState {
+    ///     legacy: RefCell::new("Hello".to_owned()),
+    /// });
+    ///
+    /// let length = context.with_cell(MyState::path().legacy(), |text: &String| text.len());
+    ///
+    /// assert_eq!(length, Some(5));
+    /// ```
+    pub fn with_cell<Path, Value, R, const SAFE: bool>(&self, path: Path, closure: impl FnOnce(&Value) -> R) -> Option<R>
+    where
+        Path: crate::Path<State, RefCell<Value>, SAFE>,
+        Value: 'static,
+    {
+        let cell = path.follow(&self.state)?;
+        let value = cell.try_borrow().ok()?;
+        Some(closure(&value))
+    }
+
+    /// Read the value behind a [`Lock`] with a closure, acquiring the lock
+    /// for the duration of the call.
+    ///
+    /// Returns `None` if the selector doesn't resolve or the lock is
+    /// poisoned.
+    ///
+    /// Only available with the `sync` feature.
+    #[cfg(feature = "sync")]
+    pub fn with_locked<Selector, Value, R, const SAFE: bool>(
+        &self,
+        selector: Selector,
+        closure: impl FnOnce(&Value::Target) -> R,
+    ) -> Option<R>
+    where
+        Selector: crate::Selector<State, Value, SAFE>,
+        Value: Lock,
+    {
+        selector.select(&self.state)?.with_locked(closure)
+    }
+
+    /// Call a closure for every target of a [`Traversal`](crate::Traversal).
+    pub fn for_each<'a, Traversal, Value>(&'a self, traversal: Traversal, mut closure: impl FnMut(&'a Value))
+    where
+        Traversal: crate::Traversal<State, Value>,
+        Value: ?Sized + 'a,
+    {
+        traversal.traverse(&self.state, &mut closure);
+    }
+
+    /// Call a closure for every key and target of an
+    /// [`IndexedTraversal`](crate::IndexedTraversal).
+    pub fn for_each_indexed<'a, Traversal, Key, Value>(&'a self, traversal: Traversal, mut closure: impl FnMut(&'a Key, &'a Value))
+    where
+        Traversal: crate::IndexedTraversal<State, Key, Value>,
+        Key: 'a,
+        Value: ?Sized + 'a,
+    {
+        traversal.traverse_indexed(&self.state, &mut closure);
+    }
+
+    /// Try to get the output of any (safe or unsafe) selector.
+    ///
+    /// Use of this function is discouraged unless `SAFE` is not known in
+    /// the current scope.
+    ///
+    /// Please use [`get`](Self::get) and [`try_get`](Self::try_get)
+    /// otherwise.
+    pub fn try_get_any<Selector, Output, const SAFE: bool>(&self, selector: Selector) -> Option<&'_ Output>
+    where
+        Selector: crate::PathRead<State, Output, SAFE>,
+        Output: ?Sized,
     {
-        self.push_change(Box::new(move |state: &mut State| match path.follow_mut(state) {
-            Some(reference) => closure(reference),
-            None => println!("Failed to update state"),
-        }));
+        selector.follow(&self.state)
     }
 
-    /// Push an item to a [`Vec`].
+    /// Iterate over the items of a vector together with a path to each item.
+    ///
+    /// The paths index into the vector, so they might resolve to a different
+    /// item once the vector changes. Use [`iter_by_id`](Self::iter_by_id) for
+    /// paths that stay with their item.
     ///
     /// Example:
     /// ```
-    /// use rust_state::{Context, RustState, VecItem};
-    ///
-    /// struct TestItem {
-    ///     id: u32,
-    /// }
-    ///
-    /// impl VecItem for TestItem {
-    ///     type Id = u32;
-    ///
-    ///     fn get_id(&self) -> Self::Id {
-    ///         self.id
-    ///     }
-    /// }
+    /// use rust_state::{Context, RustState};
     ///
     /// #[derive(RustState)]
     /// #[state_root]
     /// struct MyState {
-    ///     items: Vec<TestItem>,
+    ///     items: Vec<u32>,
     /// }
     ///
-    /// let mut context = Context::new(MyState { items: Vec::new() });
+    /// let mut context = Context::new(MyState { items: vec![1, 2, 3] });
     /// let items_path = MyState::path().items();
     ///
-    /// context.vec_push(items_path, TestItem { id: 10 });
+    /// for (item, item_path) in context.iter(items_path) {
+    ///     context.update_value(item_path, item * 10);
+    /// }
+    ///
     /// context.apply();
     ///
-    /// assert_eq!(context.get(&items_path).len(), 1);
+    /// assert_eq!(context.get(&items_path), &vec![10, 20, 30]);
     /// ```
-    pub fn vec_push<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    pub fn iter<Path, Value, const SAFE: bool>(&self, path: Path) -> impl Iterator<Item = (&Value, impl crate::Path<State, Value, false>)>
     where
-        Path: crate::Path<State, Vec<Value>, SAFE>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE> + Copy,
         Value: 'static,
     {
-        self.push_change(Box::new(move |state: &mut State| match path.follow_mut(state) {
-            Some(reference) => reference.push(value),
-            None => println!("Failed to update state"),
-        }));
+        path.follow(&self.state)
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .map(move |(index, item)| (item, path.index(index)))
     }
 
-    /// Remove an item from a [`Vec`].
+    /// Iterate over the items of a vector together with a path that looks up
+    /// each item by its [`VecItem::Id`].
     ///
     /// Example:
     /// ```
     /// use rust_state::{Context, RustState, VecItem};
     ///
+    /// #[derive(Clone, Copy)]
     /// struct TestItem {
     ///     id: u32,
+    ///     count: u32,
     /// }
     ///
     /// impl VecItem for TestItem {
@@ -195,34 +4004,44 @@ impl<State> Context<State> {
     ///     items: Vec<TestItem>,
     /// }
     ///
-    /// let mut context = Context::new(MyState { items: vec![TestItem { id: 10 }] });
+    /// let mut context = Context::new(MyState {
+    ///     items: vec![TestItem { id: 10, count: 0 }, TestItem { id: 11, count: 0 }],
+    /// });
     /// let items_path = MyState::path().items();
     ///
+    /// for (item, item_path) in context.iter_by_id(items_path) {
+    ///     context.update_value(item_path, TestItem { count: 1, ..*item });
+    /// }
+    ///
     /// context.vec_remove(items_path, 10);
     /// context.apply();
     ///
-    /// assert_eq!(context.get(&items_path).len(), 0);
+    /// assert_eq!(context.get(&items_path)[0].count, 1);
     /// ```
-    pub fn vec_remove<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    pub fn iter_by_id<Path, Value, const SAFE: bool>(
+        &self,
+        path: Path,
+    ) -> impl Iterator<Item = (&Value, impl crate::Path<State, Value, false>)>
     where
-        Path: crate::Path<State, Vec<Value>, SAFE>,
+        State: 'static,
+        Path: crate::Path<State, Vec<Value>, SAFE> + Copy,
         Value: VecItem + 'static,
     {
-        self.push_change(Box::new(move |state: &mut State| match path.follow_mut(state) {
-            Some(reference) => reference.retain(|item| item.get_id() != id),
-            None => println!("Failed to update state"),
-        }));
+        path.follow(&self.state)
+            .into_iter()
+            .flatten()
+            .map(move |item| (item, path.lookup(item.get_id())))
     }
 
-    /// Insert an item into a [`HashMap`].
+    /// Iterate over the ids of a [`HashMap`] in arbitrary order.
     ///
     /// Example:
     /// ```
     /// use std::collections::HashMap;
-    /// use rust_state::{Context, RustState, MapItem};
+    /// use rust_state::{Context, MapItem, RustState};
     ///
     /// struct TestItem;
-    //
+    ///
     /// impl MapItem for TestItem {
     ///     type Id = u32;
     /// }
@@ -233,37 +4052,35 @@ impl<State> Context<State> {
     ///     items: HashMap<u32, TestItem>,
     /// }
     ///
-    /// let mut context = Context::new(MyState { items: HashMap::new() });
-    /// let items_path = MyState::path().items();
+    /// let context = Context::new(MyState {
+    ///     items: HashMap::from([(10, TestItem), (11, TestItem)]),
+    /// });
     ///
-    /// context.map_insert(items_path, 10, TestItem);
-    /// context.apply();
+    /// let mut ids: Vec<u32> = context.map_keys(MyState::path().items()).collect();
+    /// ids.sort();
     ///
-    /// assert_eq!(context.get(&items_path).len(), 1);
+    /// assert_eq!(ids, vec![10, 11]);
     /// ```
-    pub fn map_insert<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id, value: Value)
+    pub fn map_keys<Path, Value, const SAFE: bool>(&self, path: Path) -> impl Iterator<Item = Value::Id> + '_
     where
         Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
         Value: MapItem + 'static,
     {
-        self.push_change(Box::new(move |state: &mut State| match path.follow_mut(state) {
-            Some(reference) => {
-                reference.insert(id, value);
-            }
-            None => println!("Failed to update state"),
-        }));
+        path.follow(&self.state).into_iter().flat_map(|map| map.keys().copied())
     }
 
-    /// Insert an item with default value into a [`HashMap`].
+    /// Iterate over the values of a [`HashMap`] in arbitrary order, together
+    /// with a path that looks up each value by its id.
     ///
     /// Example:
     /// ```
     /// use std::collections::HashMap;
-    /// use rust_state::{Context, RustState, MapItem};
+    /// use rust_state::{Context, MapItem, RustState};
+    ///
+    /// struct TestItem {
+    ///     count: u32,
+    /// }
     ///
-    /// #[derive(Default)]
-    /// struct TestItem;
-    //
     /// impl MapItem for TestItem {
     ///     type Id = u32;
     /// }
@@ -274,141 +4091,219 @@ impl<State> Context<State> {
     ///     items: HashMap<u32, TestItem>,
     /// }
     ///
-    /// let mut context = Context::new(MyState { items: HashMap::new() });
+    /// let mut context = Context::new(MyState {
+    ///     items: HashMap::from([(10, TestItem { count: 1 }), (11, TestItem { count: 2 })]),
+    /// });
     /// let items_path = MyState::path().items();
     ///
-    /// context.map_insert_default(items_path, 10);
+    /// for (item, item_path) in context.map_values(items_path) {
+    ///     context.update_value(item_path, TestItem { count: item.count * 10 });
+    /// }
+    ///
     /// context.apply();
     ///
-    /// assert_eq!(context.get(&items_path).len(), 1);
+    /// assert_eq!(context.get(&items_path)[&11].count, 20);
     /// ```
-    pub fn map_insert_default<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    pub fn map_values<Path, Value, const SAFE: bool>(
+        &self,
+        path: Path,
+    ) -> impl Iterator<Item = (&Value, impl crate::Path<State, Value, false>)>
     where
-        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
-        Value: MapItem + Default + 'static,
+        State: 'static,
+        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE> + Copy,
+        Value: MapItem + 'static,
     {
-        self.push_change(Box::new(move |state: &mut State| match path.follow_mut(state) {
-            Some(reference) => {
-                reference.entry(id).or_default();
-            }
-            None => println!("Failed to update state"),
-        }));
+        path.follow(&self.state)
+            .into_iter()
+            .flatten()
+            .map(move |(id, value)| (value, path.lookup(*id)))
     }
 
-    /// Remove an item from a [`HashMap`].
+    /// Iterate over the keys of a [`BTreeMap`] in ascending order.
     ///
     /// Example:
     /// ```
-    /// use std::collections::HashMap;
-    /// use rust_state::{Context, RustState, MapItem};
-    ///
-    /// struct TestItem;
-    //
-    /// impl MapItem for TestItem {
-    ///     type Id = u32;
-    /// }
+    /// use std::collections::BTreeMap;
+    /// use rust_state::{Context, RustState};
     ///
     /// #[derive(RustState)]
     /// #[state_root]
     /// struct MyState {
-    ///     items: HashMap<u32, TestItem>,
+    ///     items: BTreeMap<u32, &'static str>,
     /// }
     ///
-    /// let mut context = Context::new(MyState { items: HashMap::from([(10,
-    /// TestItem)]) }); let items_path = MyState::path().items();
+    /// let context = Context::new(MyState {
+    ///     items: BTreeMap::from([(20, "second"), (10, "first")]),
+    /// });
     ///
-    /// context.map_remove(items_path, 10);
-    /// context.apply();
+    /// let keys: Vec<u32> = context.btree_keys(MyState::path().items()).collect();
     ///
-    /// assert_eq!(context.get(&items_path).len(), 0);
+    /// assert_eq!(keys, vec![10, 20]);
     /// ```
-    pub fn map_remove<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    pub fn btree_keys<Path, Key, Value, const SAFE: bool>(&self, path: Path) -> impl Iterator<Item = Key> + '_
     where
-        Path: crate::Path<State, HashMap<Value::Id, Value>, SAFE>,
-        Value: MapItem + 'static,
+        Path: crate::Path<State, BTreeMap<Key, Value>, SAFE>,
+        Key: Ord + Hash + Copy + 'static,
+        Value: 'static,
     {
-        self.push_change(Box::new(move |state: &mut State| match path.follow_mut(state) {
-            Some(reference) => {
-                reference.remove(&id);
-            }
-            None => println!("Failed to update state"),
-        }));
+        path.follow(&self.state).into_iter().flat_map(|map| map.keys().copied())
     }
 
-    /// Apply any pending changes.
+    /// Iterate over the values of a [`BTreeMap`] in ascending order of their
+    /// keys, together with a path that looks up each value by its key.
     ///
     /// Example:
     /// ```
+    /// use std::collections::BTreeMap;
     /// use rust_state::{Context, RustState};
     ///
     /// #[derive(RustState)]
     /// #[state_root]
     /// struct MyState {
-    ///     value: &'static str,
+    ///     items: BTreeMap<u32, &'static str>,
     /// }
     ///
-    /// let mut context = Context::new(MyState { value: "Before" });
-    /// let value_path = MyState::path().value();
+    /// let context = Context::new(MyState {
+    ///     items: BTreeMap::from([(20, "second"), (10, "first")]),
+    /// });
     ///
-    /// context.update_value(value_path, "After");
+    /// let (first, first_path) = context.btree_values(MyState::path().items()).next().unwrap();
     ///
-    /// assert_eq!(context.get(&value_path), &"Before");
+    /// assert_eq!(*first, "first");
+    /// assert_eq!(context.try_get(&first_path), Some(&"first"));
+    /// ```
+    pub fn btree_values<Path, Key, Value, const SAFE: bool>(
+        &self,
+        path: Path,
+    ) -> impl Iterator<Item = (&Value, impl crate::Path<State, Value, false>)>
+    where
+        State: 'static,
+        Path: crate::Path<State, BTreeMap<Key, Value>, SAFE> + Copy,
+        Key: Ord + Hash + Copy + 'static,
+        Value: 'static,
+    {
+        path.follow(&self.state)
+            .into_iter()
+            .flatten()
+            .map(move |(key, value)| (value, path.lookup(*key)))
+    }
+
+    /// Compute the output of a safe [`Compute`](crate::Compute).
+    pub fn compute<Compute, Output>(&self, compute: &Compute) -> Output
+    where
+        Compute: crate::Compute<State, Output>,
+    {
+        compute.compute(&self.state).unwrap()
+    }
+
+    /// Get the current version of the state.
+    ///
+    /// The version changes every time the state is mutated, either by applying
+    /// changes or through mutable access. It is used by
+    /// [`Memoized`](crate::Memoized) to detect when its output is outdated.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { value: 0 });
+    /// let version = context.version();
     ///
     /// context.apply();
+    /// assert_eq!(context.version(), version);
     ///
-    /// assert_eq!(context.get(&value_path), &"After");
+    /// context.update_value(MyState::path().value(), 1);
+    /// context.apply();
+    /// assert_ne!(context.version(), version);
     /// ```
-    pub fn apply(&mut self) {
-        UnsafeCell::get_mut(&mut self.state_changes)
-            .drain(..)
-            .for_each(|apply| apply(&mut self.state));
+    pub fn version(&self) -> u64 {
+        self.version
     }
 
-    /// Get the output of a safe selector.
-    ///
-    /// # Panics
-    ///
-    /// This function will panic if the safe selector was unable to read the
-    /// value. This might happen if
-    /// [`ManuallyAssertExt`](crate::ManuallyAssertExt) is used incorrectly
-    /// or the [`Selector`](crate::Selector) trait is implemented incorrectly.
-    pub fn get<'a, Selector, Output>(&'a self, selector: &'a Selector) -> &'a Output
+    /// Get the [`PathRegistry`] of this context.
+    pub fn path_registry(&self) -> &PathRegistry<State> {
+        &self.path_registry
+    }
+
+    /// Get the [`PathRegistry`] of this context mutably, to register or
+    /// unregister paths.
+    pub fn path_registry_mut(&mut self) -> &mut PathRegistry<State> {
+        &mut self.path_registry
+    }
+
+    /// Get the output of a safe [`Memoized`](crate::Memoized) compute,
+    /// recomputing it only if the state changed since the last read.
+    pub fn get_memoized<'a, Compute, Output>(&self, memoized: &'a Memoized<State, Compute, Output, true>) -> Ref<'a, Output>
     where
-        Selector: crate::Selector<State, Output>,
-        Output: ?Sized,
+        Compute: crate::Compute<State, Output>,
     {
-        selector.select(&self.state).unwrap()
+        memoized.output_for_version(&self.state, self.version).unwrap()
     }
 
-    /// Try to get the output of an unsafe selector.
+    /// Try to get the output of an unsafe [`Memoized`](crate::Memoized)
+    /// compute, recomputing it only if the state changed since the last read.
+    pub fn try_get_memoized<'a, Compute, Output>(&self, memoized: &'a Memoized<State, Compute, Output, false>) -> Option<Ref<'a, Output>>
+    where
+        Compute: crate::Compute<State, Output, false>,
+    {
+        memoized.output_for_version(&self.state, self.version)
+    }
+
+    /// Try to compute the output of an unsafe [`Compute`](crate::Compute).
     ///
-    /// This is deliberately only implement for unsafe selectors and not for
-    /// all selectors to encourage using the safe [`get`](Self::get)
-    /// if possible.
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, LenExt, OptionExt, RustState};
     ///
-    /// For a version of this function that accepts any selector see
-    /// [`try_get_any`](Self::try_get_any).
-    pub fn try_get<'a, Selector, Output>(&'a self, selector: &'a Selector) -> Option<&'a Output>
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: Option<Vec<u32>>,
+    /// }
+    ///
+    /// let context = Context::new(MyState { items: None });
+    /// let len = MyState::path().items().unwrapped().len();
+    ///
+    /// assert_eq!(context.try_compute(&len), None);
+    /// ```
+    pub fn try_compute<Compute, Output>(&self, compute: &Compute) -> Option<Output>
     where
-        Selector: crate::Selector<State, Output, false>,
-        Output: ?Sized,
+        Compute: crate::Compute<State, Output, false>,
     {
-        selector.select(&self.state)
+        compute.compute(&self.state)
     }
 
-    /// Try to get the output of any (safe or unsafe) selector.
+    /// Try to get the items of a vector in the given range. Returns `None` if
+    /// the path does not resolve or the range is out of bounds.
     ///
-    /// Use of this function is discouraged unless `SAFE` is not known in
-    /// the current scope.
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
     ///
-    /// Please use [`get`](Self::get) and [`try_get`](Self::try_get)
-    /// otherwise.
-    pub fn try_get_any<Selector, Output, const SAFE: bool>(&self, selector: Selector) -> Option<&'_ Output>
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     items: Vec<u32>,
+    /// }
+    ///
+    /// let context = Context::new(MyState { items: vec![1, 2, 3, 4] });
+    /// let items_path = MyState::path().items();
+    ///
+    /// assert_eq!(context.try_get_range(items_path, 1..3), Some(&[2, 3][..]));
+    /// assert_eq!(context.try_get_range(items_path, 3..5), None);
+    /// ```
+    pub fn try_get_range<Path, Item, const SAFE: bool>(&self, path: Path, range: Range<usize>) -> Option<&[Item]>
     where
-        Selector: crate::Path<State, Output, SAFE>,
-        Output: ?Sized,
+        Path: crate::Path<State, Vec<Item>, SAFE>,
+        Item: 'static,
     {
-        selector.follow(&self.state)
+        path.follow(&self.state)?.get(range)
     }
 
     /// Follow a safe path.
@@ -421,7 +4316,7 @@ impl<State> Context<State> {
     /// or the [`Path`](crate::Path) trait is implemented incorrectly.
     pub fn follow<Path, Output>(&self, path: Path) -> &Output
     where
-        Path: crate::Path<State, Output>,
+        Path: crate::PathRead<State, Output>,
         Output: ?Sized,
     {
         path.follow(&self.state).unwrap()
@@ -437,7 +4332,7 @@ impl<State> Context<State> {
     /// [`try_follow_any`](Self::try_follow_any).
     pub fn try_follow<Path, Output>(&self, path: Path) -> Option<&Output>
     where
-        Path: crate::Path<State, Output, false>,
+        Path: crate::PathRead<State, Output, false>,
         Output: ?Sized,
     {
         path.follow(&self.state)
@@ -452,7 +4347,7 @@ impl<State> Context<State> {
     /// otherwise.
     pub fn try_follow_any<Path, Output, const SAFE: bool>(&self, path: Path) -> Option<&Output>
     where
-        Path: crate::Path<State, Output, SAFE>,
+        Path: crate::PathRead<State, Output, SAFE>,
         Output: ?Sized,
     {
         path.follow(&self.state)
@@ -471,7 +4366,7 @@ impl<State> Context<State> {
         Path: crate::Path<State, Output>,
         Output: ?Sized,
     {
-        path.follow_mut(&mut self.state).unwrap()
+        path.follow_mut(self.state_mut()).unwrap()
     }
 
     /// Try to follow an unsafe path.
@@ -487,7 +4382,7 @@ impl<State> Context<State> {
         Path: crate::Path<State, Output, false>,
         Output: ?Sized,
     {
-        path.follow_mut(&mut self.state)
+        path.follow_mut(self.state_mut())
     }
 
     /// Try to follow any (safe or unsafe) path.
@@ -502,6 +4397,190 @@ impl<State> Context<State> {
         Path: crate::Path<State, Output, SAFE>,
         Output: ?Sized,
     {
-        path.follow_mut(&mut self.state)
+        path.follow_mut(self.state_mut())
+    }
+
+    /// Get a mutable reference to the output of any (safe or unsafe) path.
+    ///
+    /// This bypasses the queue, so it can only be used while holding a mutable
+    /// reference to the context.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     values: Vec<u32>,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { values: vec![1, 2] });
+    ///
+    /// if let Some(values) = context.get_mut(MyState::path().values()) {
+    ///     values.push(3);
+    /// }
+    ///
+    /// assert_eq!(context.get(&MyState::path().values()), &[1, 2, 3]);
+    /// ```
+    pub fn get_mut<Path, Output, const SAFE: bool>(&mut self, path: Path) -> Option<&mut Output>
+    where
+        Path: crate::Path<State, Output, SAFE>,
+        Output: ?Sized,
+    {
+        path.follow_mut(self.state_mut())
+    }
+
+    /// Update the value for a given path immediately, bypassing the queue.
+    ///
+    /// Pending changes are not applied and will be applied on top of this
+    /// update.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct MyState {
+    ///     value: u32,
+    /// }
+    ///
+    /// let mut context = Context::new(MyState { value: 5 });
+    /// let value_path = MyState::path().value();
+    ///
+    /// context.update_now(value_path, 10);
+    ///
+    /// assert_eq!(context.get(&value_path), &10);
+    /// ```
+    pub fn update_now<Path, Value, const SAFE: bool>(&mut self, path: Path, value: Value)
+    where
+        Path: crate::Path<State, Value, SAFE>,
+    {
+        if !path.write(self.state_mut(), value) {
+            println!("Failed to update state");
+        }
+    }
+}
+
+/// Deduplicate, sort and apply changes to a state until the budget is used up.
+/// Returns the report and all changes that were not applied.
+#[allow(clippy::too_many_arguments)]
+fn apply_changes<State: 'static>(
+    state: &mut State,
+    history: &mut History<State>,
+    validators: &mut [Box<dyn Validator<State>>],
+    callbacks: &mut Callbacks,
+    state_changes: Vec<QueuedChange<State>>,
+    apply_order: ApplyOrder,
+    budget: &mut Budget,
+    preview: bool,
+) -> (ApplyReport, Vec<QueuedChange<State>>) {
+    let state_changes = deduplicate_changes(state_changes);
+    let state_changes = sort_changes(state_changes, apply_order);
+    let mut report = ApplyReport::default();
+    let mut state_changes = state_changes.into_iter().peekable();
+
+    // Accept values that were changed without validation, so they aren't
+    // compared to outdated values.
+    validators.iter_mut().for_each(|validator| validator.accept(state));
+
+    while !budget.is_exhausted() {
+        // The scratch history of a preview doesn't contain the commands of the
+        // real history, so undo and redo can only be applied once it's committed.
+        let Some(queued_change) = state_changes
+            .next_if(|queued_change| !preview || !matches!(queued_change.state_change, QueuedStateChange::Undo | QueuedStateChange::Redo))
+        else {
+            break;
+        };
+
+        let mut change_callbacks = Callbacks::default();
+        let (result, history_change) = match queued_change.state_change.apply(state, history, &mut change_callbacks) {
+            Ok(history_change) => (Ok(()), history_change),
+            Err(error) => (Err(error), None),
+        };
+
+        let validation = validators
+            .iter_mut()
+            .map(|validator| validator.validate(state))
+            .fold(Ok(()), Result::and);
+
+        // A rejected change must not leave any trace, so it's neither recorded in the
+        // history nor are its callbacks called.
+        match validation {
+            Ok(()) => {
+                validators.iter_mut().for_each(|validator| validator.accept(state));
+                history_change.into_iter().for_each(|history_change| history.commit(history_change));
+                callbacks.append(change_callbacks);
+            }
+            Err(_) => {
+                validators.iter_mut().for_each(|validator| validator.restore(state));
+                history_change
+                    .into_iter()
+                    .for_each(|history_change| history.discard(history_change));
+            }
+        }
+
+        report.record_change(result.and(validation), queued_change.records);
+        budget.consume();
+    }
+
+    (report, state_changes.collect())
+}
+
+/// Only keep the last change for every [`ChangeKey`].
+fn deduplicate_changes<State>(state_changes: Vec<QueuedChange<State>>) -> Vec<QueuedChange<State>> {
+    let last_keyed: HashMap<_, _> = state_changes
+        .iter()
+        .enumerate()
+        .filter_map(|(index, queued_change)| Some((queued_change.key?, index)))
+        .collect();
+
+    if last_keyed.is_empty() {
+        return state_changes;
     }
+
+    state_changes
+        .into_iter()
+        .enumerate()
+        .filter(|(index, queued_change)| queued_change.key.is_none_or(|key| last_keyed[&key] == *index))
+        .map(|(_, queued_change)| queued_change)
+        .collect()
+}
+
+/// Sort changes by their [`Priority`] and, depending on the [`ApplyOrder`], by
+/// their [`SourceKey`].
+fn sort_changes<State>(state_changes: Vec<QueuedChange<State>>, apply_order: ApplyOrder) -> Vec<QueuedChange<State>> {
+    let mut source_priorities = HashMap::new();
+    let mut group_order = HashMap::new();
+
+    let mut sortable: Vec<_> = state_changes
+        .into_iter()
+        .map(|queued_change| {
+            // A change can't have a higher priority than earlier changes from the same
+            // source, so changes from the same source are never reordered.
+            let priority = match queued_change.source {
+                Some(source) => {
+                    let source_priority = source_priorities.entry(source).or_insert(queued_change.priority);
+                    *source_priority = queued_change.priority.max(*source_priority);
+                    *source_priority
+                }
+                None => queued_change.priority,
+            };
+
+            let group = match apply_order {
+                ApplyOrder::Insertion => 0,
+                ApplyOrder::GroupedBySource => {
+                    let next_group = group_order.len();
+                    *group_order.entry(queued_change.source).or_insert(next_group)
+                }
+            };
+
+            ((priority, group), queued_change)
+        })
+        .collect();
+
+    // Stable sort, so the order of changes is only changed where necessary.
+    sortable.sort_by_key(|(sort_key, _)| *sort_key);
+    sortable.into_iter().map(|(_, queued_change)| queued_change).collect()
 }
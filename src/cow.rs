@@ -0,0 +1,131 @@
+//! Module providing an extension trait to dereference [`Cow`] paths in the
+//! state.
+//!
+//! Reading the path borrows the value, while following it mutably promotes
+//! the value to an owned one first, just like [`Cow::to_mut`].
+//!
+//! Example:
+//! ```
+//! use std::borrow::Cow;
+//! use rust_state::{Context, CowExt, RustState};
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! struct Theme {
+//!     accent: u32,
+//! }
+//!
+//! static DEFAULT_THEME: Theme = Theme { accent: 0xff0000 };
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     theme: Cow<'static, Theme>,
+//! }
+//!
+//! let mut context = Context::new(State {
+//!     theme: Cow::Borrowed(&DEFAULT_THEME),
+//! });
+//!
+//! let accent_path = State::path().theme().cow_deref();
+//!
+//! context.update_value_with(accent_path, |theme| theme.accent = 0x00ff00);
+//! context.apply();
+//!
+//! assert_eq!(context.get(&accent_path), &Theme { accent: 0x00ff00 });
+//! assert!(matches!(context.get(&State::path().theme()), Cow::Owned(_)));
+//! ```
+
+use std::borrow::Cow;
+use std::marker::PhantomData;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// A path that dereferences a [`Cow<T>`] in the state into a path of `T`.
+///
+/// This type is not accessible outside this module. Instead, [`CowExt`] is
+/// used to construct it and receive an `impl Path<State, T>`.
+struct CowPath<State, InnerPath, Value, const SAFE: bool> {
+    cow_path: InnerPath,
+    _marker: PhantomData<(State, Value)>,
+}
+
+impl<State, InnerPath, Value, const SAFE: bool> Clone for CowPath<State, InnerPath, Value, SAFE>
+where
+    InnerPath: Path<State, Cow<'static, Value>, SAFE>,
+    Value: Clone + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            cow_path: self.cow_path.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, InnerPath, Value, const SAFE: bool> Copy for CowPath<State, InnerPath, Value, SAFE>
+where
+    InnerPath: Path<State, Cow<'static, Value>, SAFE> + Copy,
+    Value: Clone + 'static,
+{
+}
+
+impl<State, InnerPath, Value, const SAFE: bool> Selector<State, Value, SAFE> for CowPath<State, InnerPath, Value, SAFE>
+where
+    State: 'static,
+    InnerPath: Path<State, Cow<'static, Value>, SAFE>,
+    Value: Clone + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Value> {
+        self.follow(state)
+    }
+}
+
+impl<State, InnerPath, Value, const SAFE: bool> PathRead<State, Value, SAFE> for CowPath<State, InnerPath, Value, SAFE>
+where
+    State: 'static,
+    InnerPath: Path<State, Cow<'static, Value>, SAFE>,
+    Value: Clone + 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Value> {
+        self.cow_path.follow(state).map(|cow| cow.as_ref())
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.cow_path.key()?))
+    }
+}
+
+impl<State, InnerPath, Value, const SAFE: bool> PathWrite<State, Value, SAFE> for CowPath<State, InnerPath, Value, SAFE>
+where
+    State: 'static,
+    InnerPath: Path<State, Cow<'static, Value>, SAFE>,
+    Value: Clone + 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Value> {
+        self.cow_path.follow_mut(state).map(Cow::to_mut)
+    }
+}
+
+pub trait CowExt<State, Value, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Cow<'static, Value>, SAFE> + Copy,
+    Value: Clone + 'static,
+{
+    /// Dereference the [`Cow`] in this path. Following the path mutably turns
+    /// a borrowed value into an owned one.
+    fn cow_deref(self) -> impl Path<State, Value, SAFE> + Copy {
+        CowPath {
+            cow_path: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Value, const SAFE: bool> CowExt<State, Value, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Cow<'static, Value>, SAFE> + Copy,
+    Value: Clone + 'static,
+{
+}
@@ -0,0 +1,130 @@
+//! Module providing an extension trait to dereference smart pointer paths in
+//! the state.
+//!
+//! This works for any type implementing [`DerefMut`], including [`Box`] and
+//! smart pointers from other crates.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, DerefExt, RustState};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     name: Box<str>,
+//!     value: Box<u32>,
+//! }
+//!
+//! let mut context = Context::new(State {
+//!     name: "Boxed".into(),
+//!     value: Box::new(1),
+//! });
+//!
+//! let name_path = State::path().name().dereferenced();
+//! let value_path = State::path().value().dereferenced();
+//!
+//! context.update_value(value_path, 2);
+//! context.apply();
+//!
+//! assert_eq!(context.get(&name_path), "Boxed");
+//! assert_eq!(context.get(&value_path), &2);
+//! ```
+
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// A path that dereferences a type implementing [`DerefMut`] in the state.
+///
+/// This type is not accessible outside this module. Instead, [`DerefExt`] is
+/// used to construct it and receive an `impl Path<State, T>`.
+struct DerefPath<State, PointerPath, Pointer, Target: ?Sized, const SAFE: bool> {
+    pointer_path: PointerPath,
+    _marker: PhantomData<(State, Pointer, Target)>,
+}
+
+impl<State, PointerPath, Pointer, Target: ?Sized, const SAFE: bool> Clone for DerefPath<State, PointerPath, Pointer, Target, SAFE>
+where
+    PointerPath: Path<State, Pointer, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            pointer_path: self.pointer_path.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, PointerPath, Pointer, Target: ?Sized, const SAFE: bool> Copy for DerefPath<State, PointerPath, Pointer, Target, SAFE> where
+    PointerPath: Path<State, Pointer, SAFE> + Copy
+{
+}
+
+impl<State, PointerPath, Pointer, Target, const SAFE: bool> Selector<State, Target, SAFE>
+    for DerefPath<State, PointerPath, Pointer, Target, SAFE>
+where
+    State: 'static,
+    PointerPath: Path<State, Pointer, SAFE>,
+    Pointer: DerefMut<Target = Target> + 'static,
+    Target: ?Sized + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Target> {
+        self.follow(state)
+    }
+}
+
+impl<State, PointerPath, Pointer, Target, const SAFE: bool> PathRead<State, Target, SAFE>
+    for DerefPath<State, PointerPath, Pointer, Target, SAFE>
+where
+    State: 'static,
+    PointerPath: Path<State, Pointer, SAFE>,
+    Pointer: DerefMut<Target = Target> + 'static,
+    Target: ?Sized + 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Target> {
+        self.pointer_path.follow(state).map(Deref::deref)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.pointer_path.key()?))
+    }
+}
+
+impl<State, PointerPath, Pointer, Target, const SAFE: bool> PathWrite<State, Target, SAFE>
+    for DerefPath<State, PointerPath, Pointer, Target, SAFE>
+where
+    State: 'static,
+    PointerPath: Path<State, Pointer, SAFE>,
+    Pointer: DerefMut<Target = Target> + 'static,
+    Target: ?Sized + 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Target> {
+        self.pointer_path.follow_mut(state).map(DerefMut::deref_mut)
+    }
+}
+
+pub trait DerefExt<State, Pointer, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Pointer, SAFE> + Copy,
+    Pointer: DerefMut + 'static,
+    Pointer::Target: 'static,
+{
+    /// Dereference the smart pointer in this path.
+    fn dereferenced(self) -> impl Path<State, Pointer::Target, SAFE> + Copy {
+        DerefPath {
+            pointer_path: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Pointer, const SAFE: bool> DerefExt<State, Pointer, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Pointer, SAFE> + Copy,
+    Pointer: DerefMut + 'static,
+    Pointer::Target: 'static,
+{
+}
@@ -2,7 +2,8 @@
 //! paths in the state.
 //!
 //! This allows dynamically typed paths (`Box<dyn Any>`) to be downcast into a
-//! concrete type.
+//! concrete type. Boxed custom trait objects can be downcast as well if the
+//! trait has [`AsAny`] as a supertrait.
 //!
 //! Example:
 //! ```
@@ -26,40 +27,114 @@
 //!
 //! let path = State::path().dynamic().downcast::<Inner>();
 //!
-//! assert_eq!(context.try_get(&path), Some(Inner { value: 99 }));
+//! assert_eq!(context.try_get(&path), Some(&Inner { value: 99 }));
+//! ```
+//!
+//! Downcasting a custom trait object:
+//! ```
+//! use rust_state::{AsAny, Context, DowncastExt, RustState};
+//!
+//! trait Widget: AsAny {
+//!     fn name(&self) -> &str;
+//! }
+//!
+//! struct Button {
+//!     clicks: u32,
+//! }
+//!
+//! impl Widget for Button {
+//!     fn name(&self) -> &str {
+//!         "button"
+//!     }
+//! }
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     widget: Box<dyn Widget>,
+//! }
+//!
+//! let mut context = Context::new(State {
+//!     widget: Box::new(Button { clicks: 0 }),
+//! });
+//!
+//! let button_path = State::path().widget().downcast::<Button>();
+//!
+//! context.update_value_with(button_path, |button| button.clicks += 1);
+//! context.apply();
+//!
+//! assert_eq!(context.get(&State::path().widget()).name(), "button");
+//! assert_eq!(context.try_get(&button_path).map(|button| button.clicks), Some(1));
 //! ```
 
 use std::any::Any;
 use std::marker::PhantomData;
 
-use crate::{Path, Selector};
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// Conversion to [`Any`], so custom trait objects can be downcast.
+///
+/// This is implemented for every `'static` type, so it only has to be added
+/// as a supertrait of the custom trait.
+pub trait AsAny: Any {
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+impl AsAny for dyn Any {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
 
-/// A path that downcasts a [`Box<dyn Any>`] in the state to a specific concrete
-/// type.
+/// A path that downcasts a boxed trait object in the state to a specific
+/// concrete type.
 ///
 /// This type is not accessible outside this module. Instead,
 /// [`DowncastExt`] is used to construct it and receive an `impl Path<State,
 /// T>`.
-struct DowncastPath<State, AnyPath, To, const SAFE: bool> {
+struct DowncastPath<State, AnyPath, Dyn: ?Sized, To, const SAFE: bool> {
     path: AnyPath,
-    _marker: PhantomData<(State, To)>,
+    _marker: PhantomData<(State, To, Box<Dyn>)>,
 }
 
-impl<State, AnyPath, To, const SAFE: bool> Clone for DowncastPath<State, AnyPath, To, SAFE>
+impl<State, AnyPath, Dyn: ?Sized, To, const SAFE: bool> Clone for DowncastPath<State, AnyPath, Dyn, To, SAFE>
 where
-    AnyPath: Path<State, Box<dyn Any>, SAFE>,
+    AnyPath: Path<State, Box<Dyn>, SAFE>,
 {
     fn clone(&self) -> Self {
-        *self
+        Self {
+            path: self.path.clone(),
+            _marker: PhantomData,
+        }
     }
 }
 
-impl<State, AnyPath, To, const SAFE: bool> Copy for DowncastPath<State, AnyPath, To, SAFE> where AnyPath: Path<State, Box<dyn Any>, SAFE> {}
+impl<State, AnyPath, Dyn: ?Sized, To, const SAFE: bool> Copy for DowncastPath<State, AnyPath, Dyn, To, SAFE> where
+    AnyPath: Path<State, Box<Dyn>, SAFE> + Copy
+{
+}
 
-impl<State, AnyPath, To, const SAFE: bool> Selector<State, To, false> for DowncastPath<State, AnyPath, To, SAFE>
+impl<State, AnyPath, Dyn, To, const SAFE: bool> Selector<State, To, false> for DowncastPath<State, AnyPath, Dyn, To, SAFE>
 where
     State: 'static,
-    AnyPath: Path<State, Box<dyn Any>, SAFE>,
+    AnyPath: Path<State, Box<Dyn>, SAFE>,
+    Dyn: AsAny + ?Sized,
     To: 'static,
 {
     fn select<'a>(&'a self, state: &'a State) -> Option<&'a To> {
@@ -67,27 +142,44 @@ where
     }
 }
 
-impl<State, AnyPath, To, const SAFE: bool> Path<State, To, false> for DowncastPath<State, AnyPath, To, SAFE>
+impl<State, AnyPath, Dyn, To, const SAFE: bool> PathRead<State, To, false> for DowncastPath<State, AnyPath, Dyn, To, SAFE>
 where
     State: 'static,
-    AnyPath: Path<State, Box<dyn Any>, SAFE>,
+    AnyPath: Path<State, Box<Dyn>, SAFE>,
+    Dyn: AsAny + ?Sized,
     To: 'static,
 {
     fn follow<'a>(&self, state: &'a State) -> Option<&'a To> {
-        self.path.follow(state).and_then(|any| any.downcast_ref::<To>())
+        // Dereference the box explicitly, otherwise `Box<Dyn>` itself is converted.
+        self.path.follow(state).and_then(|boxed| (**boxed).as_any().downcast_ref::<To>())
     }
 
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.path.key()?))
+    }
+}
+
+impl<State, AnyPath, Dyn, To, const SAFE: bool> PathWrite<State, To, false> for DowncastPath<State, AnyPath, Dyn, To, SAFE>
+where
+    State: 'static,
+    AnyPath: Path<State, Box<Dyn>, SAFE>,
+    Dyn: AsAny + ?Sized,
+    To: 'static,
+{
     fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut To> {
-        self.path.follow_mut(state).and_then(|any| any.downcast_mut::<To>())
+        self.path
+            .follow_mut(state)
+            .and_then(|boxed| (**boxed).as_any_mut().downcast_mut::<To>())
     }
 }
 
-pub trait DowncastExt<State, AnyPath, const SAFE: bool>
+pub trait DowncastExt<State, Dyn, const SAFE: bool>
 where
     State: 'static,
-    Self: Path<State, Box<dyn Any>, SAFE>,
+    Self: Path<State, Box<Dyn>, SAFE> + Copy,
+    Dyn: AsAny + ?Sized,
 {
-    fn downcast<To: 'static>(self) -> impl Path<State, To, false> {
+    fn downcast<To: 'static>(self) -> impl Path<State, To, false> + Copy {
         DowncastPath {
             path: self,
             _marker: PhantomData,
@@ -95,9 +187,10 @@ where
     }
 }
 
-impl<State, T, const SAFE: bool> DowncastExt<State, T, SAFE> for T
+impl<State, T, Dyn, const SAFE: bool> DowncastExt<State, Dyn, SAFE> for T
 where
     State: 'static,
-    T: Path<State, Box<dyn Any>, SAFE>,
+    T: Path<State, Box<Dyn>, SAFE> + Copy,
+    Dyn: AsAny + ?Sized,
 {
 }
@@ -0,0 +1,414 @@
+//! Module providing [`DynamicPath`], a path parsed from a string at runtime.
+//!
+//! Paths are written as field names separated by dots, with indices in
+//! brackets, for example `"settings.audio.volume"` or `"items[3].name"`. They
+//! are followed through [`Reflect`], so every type along the path has to
+//! implement it. Since the path is only known at runtime, a `DynamicPath` is
+//! always unsafe.
+//!
+//! Paths can also be converted to and from [RFC 6901](https://www.rfc-editor.org/rfc/rfc6901)
+//! JSON Pointers like `"/items/3/name"`. JSON Pointers don't distinguish
+//! between fields and indices, so a numeric field name that is not found is
+//! used as an index instead. With the `serde` feature, paths are serialized as
+//! JSON Pointers.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, DynamicPath, RustState};
+//!
+//! #[derive(RustState)]
+//! #[state(reflect)]
+//! struct Item {
+//!     name: String,
+//! }
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! #[state(reflect)]
+//! struct State {
+//!     items: Vec<Item>,
+//! }
+//!
+//! let mut context = Context::new(State {
+//!     items: vec![Item { name: "Sword".to_owned() }],
+//! });
+//!
+//! let name_path = DynamicPath::<State>::parse("items[0].name").unwrap().typed::<String>();
+//!
+//! assert_eq!(context.try_get(&name_path).map(String::as_str), Some("Sword"));
+//!
+//! context.update_value(name_path, "Shield".to_owned());
+//! context.apply();
+//!
+//! assert_eq!(context.get(&State::path().items()).first().map(|item| item.name.as_str()), Some("Shield"));
+//!
+//! // Paths that don't exist or have a different type don't resolve.
+//! assert!(context.try_get(&DynamicPath::<State>::parse("items[1].name").unwrap()).is_none());
+//! assert!(context.try_get(&name_path.typed::<u32>()).is_none());
+//! ```
+
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use crate::{AutoImplSelector, FromReflect, PathKey, PathRead, PathWrite, Reflect, Selector};
+
+/// A single step of a [`DynamicPath`].
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum Segment {
+    Field(Box<str>),
+    Index(usize),
+}
+
+/// Error returned when a string is not a valid path.
+///
+/// Example:
+/// ```
+/// use rust_state::{DynamicPath, ParsePathError};
+///
+/// struct State;
+///
+/// assert_eq!(DynamicPath::<State>::parse("").unwrap_err(), ParsePathError::Empty);
+/// assert_eq!(DynamicPath::<State>::parse("items[").unwrap_err(), ParsePathError::UnexpectedEnd);
+/// assert_eq!(
+///     DynamicPath::<State>::parse("items[x]").unwrap_err(),
+///     ParsePathError::InvalidIndex { position: 6 }
+/// );
+/// assert_eq!(
+///     DynamicPath::<State>::parse("items..name").unwrap_err(),
+///     ParsePathError::UnexpectedCharacter { position: 6, character: '.' }
+/// );
+/// assert_eq!(DynamicPath::<State>::parse("items[3].name").unwrap().to_string(), "items[3].name");
+/// assert_eq!(
+///     DynamicPath::<State>::from_json_pointer("/a~2").unwrap_err(),
+///     ParsePathError::InvalidEscape { position: 2 }
+/// );
+/// assert_eq!(DynamicPath::<State>::from_json_pointer("/a~1b/c~0").unwrap().to_json_pointer(), "/a~1b/c~0");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParsePathError {
+    /// The path is empty.
+    Empty,
+    /// A character that is not valid at this position.
+    UnexpectedCharacter { position: usize, character: char },
+    /// The path ends in the middle of a segment.
+    UnexpectedEnd,
+    /// An index that is not a valid number.
+    InvalidIndex { position: usize },
+    /// A `~` in a JSON Pointer that is not followed by `0` or `1`.
+    InvalidEscape { position: usize },
+}
+
+impl fmt::Display for ParsePathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Path is empty"),
+            Self::UnexpectedCharacter { position, character } => {
+                write!(f, "Unexpected character '{character}' at position {position}")
+            }
+            Self::UnexpectedEnd => write!(f, "Unexpected end of path"),
+            Self::InvalidIndex { position } => write!(f, "Invalid index at position {position}"),
+            Self::InvalidEscape { position } => write!(f, "Invalid escape at position {position}"),
+        }
+    }
+}
+
+impl Error for ParsePathError {}
+
+fn is_name_character(character: char) -> bool {
+    character.is_alphanumeric() || character == '_'
+}
+
+fn parse_segments(path: &str) -> Result<Vec<Segment>, ParsePathError> {
+    if path.is_empty() {
+        return Err(ParsePathError::Empty);
+    }
+
+    let mut segments = Vec::new();
+    let mut characters = path.char_indices().peekable();
+    // A field name is expected at the start and after every dot.
+    let mut expect_field = !path.starts_with('[');
+
+    loop {
+        if expect_field {
+            let start = characters.peek().map_or(path.len(), |(position, _)| *position);
+
+            while characters.next_if(|(_, character)| is_name_character(*character)).is_some() {}
+
+            let end = characters.peek().map_or(path.len(), |(position, _)| *position);
+
+            if start == end {
+                return match characters.next() {
+                    Some((position, character)) => Err(ParsePathError::UnexpectedCharacter { position, character }),
+                    None => Err(ParsePathError::UnexpectedEnd),
+                };
+            }
+
+            segments.push(Segment::Field(path[start..end].into()));
+            expect_field = false;
+        }
+
+        match characters.next() {
+            None => return Ok(segments),
+            Some((_, '.')) => expect_field = true,
+            Some((position, '[')) => {
+                let start = position + 1;
+
+                while characters.next_if(|(_, character)| *character != ']').is_some() {}
+
+                let Some((end, _)) = characters.next() else {
+                    return Err(ParsePathError::UnexpectedEnd);
+                };
+
+                let index = path[start..end]
+                    .parse()
+                    .map_err(|_| ParsePathError::InvalidIndex { position: start })?;
+
+                segments.push(Segment::Index(index));
+            }
+            Some((position, character)) => return Err(ParsePathError::UnexpectedCharacter { position, character }),
+        }
+    }
+}
+
+fn parse_json_pointer(pointer: &str) -> Result<Vec<Segment>, ParsePathError> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let Some(tokens) = pointer.strip_prefix('/') else {
+        let character = pointer.chars().next().unwrap();
+        return Err(ParsePathError::UnexpectedCharacter { position: 0, character });
+    };
+
+    let mut segments = Vec::new();
+    let mut position = 1;
+
+    for token in tokens.split('/') {
+        let mut name = String::with_capacity(token.len());
+        let mut characters = token.char_indices();
+
+        while let Some((offset, character)) = characters.next() {
+            if character != '~' {
+                name.push(character);
+                continue;
+            }
+
+            match characters.next() {
+                Some((_, '0')) => name.push('~'),
+                Some((_, '1')) => name.push('/'),
+                _ => {
+                    return Err(ParsePathError::InvalidEscape {
+                        position: position + offset,
+                    });
+                }
+            }
+        }
+
+        segments.push(Segment::Field(name.into()));
+        position += token.len() + 1;
+    }
+
+    Ok(segments)
+}
+
+/// Look up a field, falling back to an index if the name is numeric.
+fn field<'a>(value: &'a dyn Reflect, name: &str) -> Option<&'a dyn Reflect> {
+    value.field(name).or_else(|| value.index(name.parse().ok()?))
+}
+
+fn field_mut<'a>(value: &'a mut dyn Reflect, name: &str) -> Option<&'a mut dyn Reflect> {
+    // Returning the first mutable borrow conditionally is rejected by the borrow
+    // checker, so check if the field exists before borrowing mutably.
+    if value.field(name).is_some() {
+        value.field_mut(name)
+    } else {
+        value.index_mut(name.parse().ok()?)
+    }
+}
+
+/// A type-erased, unsafe path parsed from a string.
+///
+/// [`DynamicPath::parse`] creates a path to `dyn Reflect`, which can be
+/// narrowed to a concrete type with [`DynamicPath::typed`].
+///
+/// Since dynamic paths are [`Copy`], the parsed segments
+/// are moved to a `'static` allocation that is never freed. Parse paths once
+/// and store them, rather than parsing a path every frame.
+pub struct DynamicPath<State, To: ?Sized = dyn Reflect> {
+    segments: &'static [Segment],
+    _marker: PhantomData<fn(&State) -> &To>,
+}
+
+impl<State, To: ?Sized> DynamicPath<State, To> {
+    /// Parse a path like `"settings.audio.volume"` or `"items[3].name"`.
+    pub fn parse(path: &str) -> Result<Self, ParsePathError> {
+        Ok(Self {
+            segments: Box::leak(parse_segments(path)?.into_boxed_slice()),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Parse an RFC 6901 JSON Pointer like `"/items/3/name"`. The empty
+    /// pointer resolves to the state itself.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, DynamicPath, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// #[state(reflect)]
+    /// struct State {
+    ///     scores: Vec<u32>,
+    /// }
+    ///
+    /// let context = Context::new(State { scores: vec![10, 20] });
+    ///
+    /// let score_path = DynamicPath::<State>::from_json_pointer("/scores/1").unwrap().typed::<u32>();
+    ///
+    /// assert_eq!(context.try_get(&score_path), Some(&20));
+    /// assert_eq!(score_path.to_json_pointer(), "/scores/1");
+    ///
+    /// let index_path = DynamicPath::<State>::parse("scores[1]").unwrap();
+    ///
+    /// assert_eq!(index_path.to_json_pointer(), "/scores/1");
+    /// ```
+    pub fn from_json_pointer(pointer: &str) -> Result<Self, ParsePathError> {
+        Ok(Self {
+            segments: Box::leak(parse_json_pointer(pointer)?.into_boxed_slice()),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Convert the path to an RFC 6901 JSON Pointer.
+    pub fn to_json_pointer(&self) -> String {
+        let mut pointer = String::new();
+
+        for segment in self.segments {
+            pointer.push('/');
+
+            match segment {
+                Segment::Field(name) => pointer.push_str(&name.replace('~', "~0").replace('/', "~1")),
+                Segment::Index(index) => pointer.push_str(&index.to_string()),
+            }
+        }
+
+        pointer
+    }
+
+    /// Change the type the path resolves to. The path doesn't resolve if the
+    /// value has a different type.
+    pub fn typed<Target: ?Sized>(self) -> DynamicPath<State, Target> {
+        DynamicPath {
+            segments: self.segments,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, To: ?Sized> FromStr for DynamicPath<State, To> {
+    type Err = ParsePathError;
+
+    fn from_str(path: &str) -> Result<Self, Self::Err> {
+        Self::parse(path)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<State, To: ?Sized> serde::Serialize for DynamicPath<State, To> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_json_pointer())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, State, To: ?Sized> serde::Deserialize<'de> for DynamicPath<State, To> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pointer = String::deserialize(deserializer)?;
+        Self::from_json_pointer(&pointer).map_err(serde::de::Error::custom)
+    }
+}
+
+impl<State, To: ?Sized> !AutoImplSelector for DynamicPath<State, To> {}
+
+impl<State, To: ?Sized> Clone for DynamicPath<State, To> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<State, To: ?Sized> Copy for DynamicPath<State, To> {}
+
+impl<State, To: ?Sized> fmt::Display for DynamicPath<State, To> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (position, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Field(name) if position == 0 => write!(f, "{name}")?,
+                Segment::Field(name) => write!(f, ".{name}")?,
+                Segment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<State, To: ?Sized> fmt::Debug for DynamicPath<State, To> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DynamicPath").field(&self.to_string()).finish()
+    }
+}
+
+impl<State, To> Selector<State, To, false> for DynamicPath<State, To>
+where
+    State: Reflect,
+    To: FromReflect + ?Sized + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a To> {
+        self.follow(state)
+    }
+}
+
+impl<State, To> PathRead<State, To, false> for DynamicPath<State, To>
+where
+    State: Reflect,
+    To: FromReflect + ?Sized + 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a To> {
+        let mut value: &dyn Reflect = state;
+
+        for segment in self.segments {
+            value = match segment {
+                Segment::Field(name) => field(value, name)?,
+                Segment::Index(index) => value.index(*index)?,
+            };
+        }
+
+        To::from_reflect(value)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.segments))
+    }
+}
+
+impl<State, To> PathWrite<State, To, false> for DynamicPath<State, To>
+where
+    State: Reflect,
+    To: FromReflect + ?Sized + 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut To> {
+        let mut value: &mut dyn Reflect = state;
+
+        for segment in self.segments {
+            value = match segment {
+                Segment::Field(name) => field_mut(value, name)?,
+                Segment::Index(index) => value.index_mut(*index)?,
+            };
+        }
+
+        To::from_reflect_mut(value)
+    }
+}
@@ -0,0 +1,75 @@
+//! Module providing an extension trait to check if an unsafe path resolves.
+//!
+//! The check is a safe boolean selector, so it can be passed to anything that
+//! reads a `bool`. To check if an [`Option`] is [`Some`], see
+//! [`OptionExt::is_some`](crate::OptionExt::is_some).
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, ExistsExt, RustState, VecIndexExt};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     items: Vec<u32>,
+//! }
+//!
+//! let mut context = Context::new(State { items: vec![10] });
+//!
+//! let has_second = State::path().items().index(1).exists();
+//!
+//! assert!(!*context.get(&has_second));
+//!
+//! context.vec_push(State::path().items(), 20);
+//! context.apply();
+//!
+//! assert!(*context.get(&has_second));
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{PathRead, Selector};
+
+/// A safe selector that resolves to `true` if an unsafe path resolves.
+///
+/// This type is not accessible outside this module, instead [`ExistsExt`]
+/// can be used to construct it and receive an `impl Selector<State, bool>`.
+struct Exists<State, InnerPath, To: ?Sized> {
+    path: InnerPath,
+    _marker: PhantomData<(State, To)>,
+}
+
+impl<State, InnerPath, To> Selector<State, bool> for Exists<State, InnerPath, To>
+where
+    State: 'static,
+    InnerPath: PathRead<State, To, false>,
+    To: ?Sized + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a bool> {
+        let exists = self.path.follow(state).is_some();
+        Some(if exists { &true } else { &false })
+    }
+}
+
+pub trait ExistsExt<State, To>
+where
+    State: 'static,
+    Self: PathRead<State, To, false>,
+    To: ?Sized + 'static,
+{
+    /// Safe selector that resolves to `true` if this path currently resolves.
+    fn exists(self) -> impl Selector<State, bool> {
+        Exists {
+            path: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, To> ExistsExt<State, To> for T
+where
+    State: 'static,
+    T: PathRead<State, To, false>,
+    To: ?Sized + 'static,
+{
+}
@@ -0,0 +1,173 @@
+//! Module providing [`Extensions`], a type map to hold values that are not
+//! known to the state at compile time.
+//!
+//! Every [`Context`](crate::Context) has its own extensions, see
+//! [`Context::extensions`](crate::Context::extensions). They can also be
+//! stored in the state. Values are accessed using [`ExtensionsExt`].
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector, StateMarker};
+
+/// A map holding at most one value of every type.
+///
+/// Storing extensions in the state:
+/// ```
+/// use rust_state::{Context, Extensions, ExtensionsExt, RustState};
+///
+/// struct PluginState {
+///     enabled: bool,
+/// }
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct State {
+///     extensions: Extensions,
+/// }
+///
+/// let mut context = Context::new(State {
+///     extensions: Extensions::default(),
+/// });
+///
+/// let extensions_path = State::path().extensions();
+/// let plugin_path = extensions_path.extension::<PluginState>();
+///
+/// assert!(context.try_get(&plugin_path).is_none());
+///
+/// context.insert_extension_at(extensions_path, PluginState { enabled: false });
+/// context.update_value(plugin_path, PluginState { enabled: true });
+/// context.apply();
+///
+/// assert!(context.try_get(&plugin_path).unwrap().enabled);
+/// ```
+#[derive(Default)]
+pub struct Extensions {
+    values: HashMap<TypeId, Box<dyn Any>>,
+}
+
+// Allows extensions to be the root of the context returned by
+// `Context::extensions`.
+impl StateMarker for Extensions {}
+
+impl Extensions {
+    /// Insert a value, returning the previous value of the same type.
+    pub fn insert<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.values
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .map(|previous| *previous.downcast().unwrap())
+    }
+
+    /// Remove the value of type `T`.
+    pub fn remove<T: 'static>(&mut self) -> Option<T> {
+        self.values.remove(&TypeId::of::<T>()).map(|value| *value.downcast().unwrap())
+    }
+
+    /// Get the value of type `T`.
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values.get(&TypeId::of::<T>()).and_then(|value| value.downcast_ref())
+    }
+
+    /// Get the value of type `T` mutably.
+    pub fn get_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.values.get_mut(&TypeId::of::<T>()).and_then(|value| value.downcast_mut())
+    }
+
+    /// Check if there is a value of type `T`.
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+}
+
+/// A path to the value of type `Extension` in [`Extensions`].
+///
+/// This type is not accessible outside this module. Instead,
+/// [`ExtensionsExt`] is used to construct it and receive an `impl Path<State,
+/// T>`.
+struct ExtensionPath<State, ExtensionsPath, Extension, const SAFE: bool> {
+    extensions_path: ExtensionsPath,
+    _marker: PhantomData<(State, Extension)>,
+}
+
+impl<State, ExtensionsPath, Extension, const SAFE: bool> Clone for ExtensionPath<State, ExtensionsPath, Extension, SAFE>
+where
+    ExtensionsPath: Path<State, Extensions, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            extensions_path: self.extensions_path.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, ExtensionsPath, Extension, const SAFE: bool> Copy for ExtensionPath<State, ExtensionsPath, Extension, SAFE> where
+    ExtensionsPath: Path<State, Extensions, SAFE> + Copy
+{
+}
+
+impl<State, ExtensionsPath, Extension, const SAFE: bool> Selector<State, Extension, false>
+    for ExtensionPath<State, ExtensionsPath, Extension, SAFE>
+where
+    State: 'static,
+    ExtensionsPath: Path<State, Extensions, SAFE>,
+    Extension: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Extension> {
+        self.follow(state)
+    }
+}
+
+impl<State, ExtensionsPath, Extension, const SAFE: bool> PathRead<State, Extension, false>
+    for ExtensionPath<State, ExtensionsPath, Extension, SAFE>
+where
+    State: 'static,
+    ExtensionsPath: Path<State, Extensions, SAFE>,
+    Extension: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Extension> {
+        self.extensions_path.follow(state)?.get()
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.extensions_path.key()?))
+    }
+}
+
+impl<State, ExtensionsPath, Extension, const SAFE: bool> PathWrite<State, Extension, false>
+    for ExtensionPath<State, ExtensionsPath, Extension, SAFE>
+where
+    State: 'static,
+    ExtensionsPath: Path<State, Extensions, SAFE>,
+    Extension: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Extension> {
+        self.extensions_path.follow_mut(state)?.get_mut()
+    }
+}
+
+/// Extension trait providing `.extension::<T>()` for [`Extensions`] paths.
+pub trait ExtensionsExt<State, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Extensions, SAFE> + Copy,
+{
+    /// Get a path to the value of type `Extension`.
+    ///
+    /// This path is *not* safe. It returns `None` at runtime if there is no
+    /// value of type `Extension`.
+    fn extension<Extension: 'static>(self) -> impl Path<State, Extension, false> + Copy {
+        ExtensionPath {
+            extensions_path: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, const SAFE: bool> ExtensionsExt<State, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Extensions, SAFE> + Copy,
+{
+}
@@ -0,0 +1,123 @@
+//! Module providing a trait and an extension trait to read single flags of a
+//! bit field in the state.
+//!
+//! [`Flags`] is implemented for all unsigned integers and can be implemented
+//! for custom bit field types.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, FlagsExt, RustState};
+//!
+//! const CAN_READ: u8 = 0b01;
+//! const CAN_WRITE: u8 = 0b10;
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     permissions: u8,
+//! }
+//!
+//! let mut context = Context::new(State { permissions: CAN_READ });
+//! let permissions_path = State::path().permissions();
+//!
+//! let can_read = permissions_path.flag(CAN_READ);
+//! let can_write = permissions_path.flag(CAN_WRITE);
+//!
+//! assert!(*context.get(&can_read));
+//! assert!(!*context.get(&can_write));
+//!
+//! context.flag_set(permissions_path, CAN_READ, false);
+//! context.flag_toggle(permissions_path, CAN_WRITE);
+//! context.apply();
+//!
+//! assert!(!*context.get(&can_read));
+//! assert!(*context.get(&can_write));
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{Path, Selector};
+
+/// A bit field of flags.
+pub trait Flags: Copy + 'static {
+    /// Check if all bits of `flag` are set.
+    fn contains_flag(&self, flag: Self) -> bool;
+
+    /// Set or clear all bits of `flag`.
+    fn set_flag(&mut self, flag: Self, value: bool);
+
+    /// Toggle all bits of `flag`.
+    fn toggle_flag(&mut self, flag: Self);
+}
+
+macro_rules! implement_flags {
+    ($($integer:ty),*) => {
+        $(
+            impl Flags for $integer {
+                fn contains_flag(&self, flag: Self) -> bool {
+                    *self & flag == flag
+                }
+
+                fn set_flag(&mut self, flag: Self, value: bool) {
+                    if value {
+                        *self |= flag;
+                    } else {
+                        *self &= !flag;
+                    }
+                }
+
+                fn toggle_flag(&mut self, flag: Self) {
+                    *self ^= flag;
+                }
+            }
+        )*
+    };
+}
+
+implement_flags!(u8, u16, u32, u64, u128, usize);
+
+/// A selector that checks if a flag is set.
+///
+/// This type is not accessible outside this module, instead [`FlagsExt`] can
+/// be used to construct it and receive a `impl Selector<State, bool>`.
+struct FlagSelector<State, FlagsPath, Value, const SAFE: bool> {
+    flags_path: FlagsPath,
+    flag: Value,
+    _marker: PhantomData<State>,
+}
+
+impl<State, FlagsPath, Value, const SAFE: bool> Selector<State, bool, SAFE> for FlagSelector<State, FlagsPath, Value, SAFE>
+where
+    State: 'static,
+    FlagsPath: Path<State, Value, SAFE>,
+    Value: Flags,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a bool> {
+        let contains = self.flags_path.follow(state)?.contains_flag(self.flag);
+        Some(if contains { &true } else { &false })
+    }
+}
+
+pub trait FlagsExt<State, Value, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Value, SAFE>,
+    Value: Flags,
+{
+    /// Selector that resolves to `true` if all bits of `flag` are set.
+    fn flag(self, flag: Value) -> impl Selector<State, bool, SAFE> {
+        FlagSelector {
+            flags_path: self,
+            flag,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Value, const SAFE: bool> FlagsExt<State, Value, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Value, SAFE>,
+    Value: Flags,
+{
+}
@@ -0,0 +1,120 @@
+//! Module providing [`FnPath`], a path backed by two functions.
+
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::{AutoImplSelector, PathKey, PathRead, PathWrite, Selector};
+
+/// A path that projects from `State` to `To` with a pair of functions.
+///
+/// This is useful for projections that are too small to warrant a derive or
+/// an adapter. Both function pointers and closures can be used, including
+/// closures that capture values, as long as they are [`Copy`]. Combined with
+/// [`PathComposeExt::then`](crate::PathComposeExt::then), an `FnPath` can also
+/// be attached to another path.
+///
+/// [`Path`](crate::Path) is a trait, so the constructor lives on this type as
+/// [`FnPath::from_fns`].
+///
+/// Example:
+/// ```
+/// use rust_state::{Context, FnPath, RustState};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct MyState {
+///     points: Vec<(f32, f32)>,
+/// }
+///
+/// let mut context = Context::new(MyState { points: vec![(1.0, 2.0), (3.0, 4.0)] });
+///
+/// let index = 1;
+/// let x_path = FnPath::from_fns(
+///     move |state: &MyState| &state.points[index].0,
+///     move |state: &mut MyState| &mut state.points[index].0,
+/// );
+///
+/// context.update_value(x_path, 5.0);
+/// context.apply();
+///
+/// assert_eq!(context.get(&x_path), &5.0);
+/// ```
+pub struct FnPath<State, To: ?Sized, Get = fn(&State) -> &To, GetMut = fn(&mut State) -> &mut To> {
+    get: Get,
+    get_mut: GetMut,
+    _marker: PhantomData<(State, To)>,
+}
+
+impl<State, To, Get, GetMut> FnPath<State, To, Get, GetMut>
+where
+    To: ?Sized,
+    Get: Fn(&State) -> &To + Copy + 'static,
+    GetMut: Fn(&mut State) -> &mut To + Copy + 'static,
+{
+    /// Create a path from a getter and a mutable getter.
+    pub fn from_fns(get: Get, get_mut: GetMut) -> Self {
+        Self {
+            get,
+            get_mut,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, To: ?Sized, Get, GetMut> !AutoImplSelector for FnPath<State, To, Get, GetMut> {}
+
+impl<State, To: ?Sized, Get: Copy, GetMut: Copy> Clone for FnPath<State, To, Get, GetMut> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<State, To: ?Sized, Get: Copy, GetMut: Copy> Copy for FnPath<State, To, Get, GetMut> {}
+
+impl<State, To: ?Sized, Get, GetMut> fmt::Debug for FnPath<State, To, Get, GetMut> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FnPath").field("to", &std::any::type_name::<To>()).finish()
+    }
+}
+
+impl<State, To, Get, GetMut> Selector<State, To> for FnPath<State, To, Get, GetMut>
+where
+    State: 'static,
+    To: ?Sized + 'static,
+    Get: Fn(&State) -> &To + Copy + 'static,
+    GetMut: Fn(&mut State) -> &mut To + Copy + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a To> {
+        self.follow(state)
+    }
+}
+
+impl<State, To, Get, GetMut> PathRead<State, To> for FnPath<State, To, Get, GetMut>
+where
+    State: 'static,
+    To: ?Sized + 'static,
+    Get: Fn(&State) -> &To + Copy + 'static,
+    GetMut: Fn(&mut State) -> &mut To + Copy + 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a To> {
+        Some((self.get)(state))
+    }
+
+    /// Only closures that don't capture anything and function items can be
+    /// identified, see [`PathKey::with_fn`].
+    fn key(&self) -> Option<PathKey> {
+        PathKey::new::<Self>().with_fn::<Get>()?.with_fn::<GetMut>()
+    }
+}
+
+impl<State, To, Get, GetMut> PathWrite<State, To> for FnPath<State, To, Get, GetMut>
+where
+    State: 'static,
+    To: ?Sized + 'static,
+    Get: Fn(&State) -> &To + Copy + 'static,
+    GetMut: Fn(&mut State) -> &mut To + Copy + 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut To> {
+        Some((self.get_mut)(state))
+    }
+}
@@ -0,0 +1,169 @@
+//! Module providing generic paths used by the code generated by
+//! [`RustState`](crate::RustState).
+//!
+//! Types and fields marked with `#[state(no_selector)]` use these paths instead
+//! of generating their own path types. This way no [`Selector`] (and no
+//! negative `AutoImplSelector`) implementations are generated in the crate
+//! using the derive, which avoids coherence conflicts with blanket
+//! implementations in that crate.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, RustState};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! #[state(no_selector)]
+//! struct State {
+//!     inner: Inner,
+//! }
+//!
+//! #[derive(RustState)]
+//! struct Inner {
+//!     #[state(no_selector)]
+//!     value: u32,
+//! }
+//!
+//! let context = Context::new(State { inner: Inner { value: 5 } });
+//!
+//! assert_eq!(context.get(&State::path().inner().value()), &5);
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// A path to the root of the state.
+#[doc(hidden)]
+pub struct RootPath<State> {
+    _marker: PhantomData<State>,
+}
+
+impl<State> RootPath<State> {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self { _marker: PhantomData }
+    }
+}
+
+impl<State> Clone for RootPath<State> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<State> Copy for RootPath<State> {}
+
+impl<State> Selector<State, State> for RootPath<State>
+where
+    State: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a State> {
+        Some(state)
+    }
+}
+
+impl<State> PathRead<State, State> for RootPath<State>
+where
+    State: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a State> {
+        Some(state)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>())
+    }
+}
+
+impl<State> PathWrite<State, State> for RootPath<State>
+where
+    State: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut State> {
+        Some(state)
+    }
+}
+
+/// A path to a field, using function pointers to project from the parent to
+/// the field. The name of the field identifies the path in its key.
+#[doc(hidden)]
+pub struct FieldPath<State, ParentPath, Parent, Field: ?Sized, const SAFE: bool> {
+    parent_path: ParentPath,
+    name: &'static str,
+    get: fn(&Parent) -> &Field,
+    get_mut: fn(&mut Parent) -> &mut Field,
+    _marker: PhantomData<State>,
+}
+
+impl<State, ParentPath, Parent, Field: ?Sized, const SAFE: bool> FieldPath<State, ParentPath, Parent, Field, SAFE> {
+    pub fn new(parent_path: ParentPath, name: &'static str, get: fn(&Parent) -> &Field, get_mut: fn(&mut Parent) -> &mut Field) -> Self {
+        Self {
+            parent_path,
+            name,
+            get,
+            get_mut,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, ParentPath, Parent, Field: ?Sized, const SAFE: bool> Clone for FieldPath<State, ParentPath, Parent, Field, SAFE>
+where
+    ParentPath: Path<State, Parent, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            parent_path: self.parent_path.clone(),
+            name: self.name,
+            get: self.get,
+            get_mut: self.get_mut,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, ParentPath, Parent, Field: ?Sized, const SAFE: bool> Copy for FieldPath<State, ParentPath, Parent, Field, SAFE> where
+    ParentPath: Path<State, Parent, SAFE> + Copy
+{
+}
+
+impl<State, ParentPath, Parent, Field, const SAFE: bool> Selector<State, Field, SAFE> for FieldPath<State, ParentPath, Parent, Field, SAFE>
+where
+    State: 'static,
+    ParentPath: Path<State, Parent, SAFE>,
+    Parent: 'static,
+    Field: ?Sized + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Field> {
+        self.follow(state)
+    }
+}
+
+impl<State, ParentPath, Parent, Field, const SAFE: bool> PathRead<State, Field, SAFE> for FieldPath<State, ParentPath, Parent, Field, SAFE>
+where
+    State: 'static,
+    ParentPath: Path<State, Parent, SAFE>,
+    Parent: 'static,
+    Field: ?Sized + 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Field> {
+        self.parent_path.follow(state).map(self.get)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.parent_path.key()?).with(self.name))
+    }
+}
+
+impl<State, ParentPath, Parent, Field, const SAFE: bool> PathWrite<State, Field, SAFE> for FieldPath<State, ParentPath, Parent, Field, SAFE>
+where
+    State: 'static,
+    ParentPath: Path<State, Parent, SAFE>,
+    Parent: 'static,
+    Field: ?Sized + 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Field> {
+        self.parent_path.follow_mut(state).map(self.get_mut)
+    }
+}
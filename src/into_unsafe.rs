@@ -0,0 +1,124 @@
+//! Module providing an extension trait to turn any path into an unsafe path.
+//!
+//! `SAFE` is part of the type of a path, so safe and unsafe paths can't be
+//! stored in the same place. Converting all of them to unsafe paths removes
+//! the need to be generic over `SAFE`.
+//!
+//! Example:
+//! ```
+//! use rust_state::{BoxedPath, Context, IntoUnsafeExt, OptionExt, RustState};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     volume: u32,
+//!     override_volume: Option<u32>,
+//! }
+//!
+//! let context = Context::new(State {
+//!     volume: 50,
+//!     override_volume: None,
+//! });
+//!
+//! let paths: Vec<BoxedPath<State, u32, false>> = vec![
+//!     BoxedPath::new(State::path().volume().into_unsafe()),
+//!     BoxedPath::new(State::path().override_volume().unwrapped()),
+//! ];
+//!
+//! assert_eq!(context.try_get(&paths[0]), Some(&50));
+//! assert_eq!(context.try_get(&paths[1]), None);
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+struct IntoUnsafe<State, InnerPath, To: ?Sized, const SAFE: bool> {
+    path: InnerPath,
+    _marker: PhantomData<(State, To)>,
+}
+
+impl<State, InnerPath, To: ?Sized, const SAFE: bool> Clone for IntoUnsafe<State, InnerPath, To, SAFE>
+where
+    InnerPath: Path<State, To, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, InnerPath, To: ?Sized, const SAFE: bool> Copy for IntoUnsafe<State, InnerPath, To, SAFE> where
+    InnerPath: Path<State, To, SAFE> + Copy
+{
+}
+
+impl<State, InnerPath, To, const SAFE: bool> Selector<State, To, false> for IntoUnsafe<State, InnerPath, To, SAFE>
+where
+    State: 'static,
+    InnerPath: Path<State, To, SAFE>,
+    To: ?Sized + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a To> {
+        self.path.select(state)
+    }
+}
+
+impl<State, InnerPath, To, const SAFE: bool> PathRead<State, To, false> for IntoUnsafe<State, InnerPath, To, SAFE>
+where
+    State: 'static,
+    InnerPath: Path<State, To, SAFE>,
+    To: ?Sized + 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a To> {
+        self.path.follow(state)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.path.key()?))
+    }
+}
+
+impl<State, InnerPath, To, const SAFE: bool> PathWrite<State, To, false> for IntoUnsafe<State, InnerPath, To, SAFE>
+where
+    State: 'static,
+    InnerPath: Path<State, To, SAFE>,
+    To: ?Sized + 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut To> {
+        self.path.follow_mut(state)
+    }
+
+    fn write(&self, state: &mut State, value: To) -> bool
+    where
+        To: Sized,
+    {
+        self.path.write(state, value)
+    }
+}
+
+pub trait IntoUnsafeExt<State, To, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, To, SAFE> + Copy,
+    To: ?Sized + 'static,
+{
+    /// Treat this path as unsafe, regardless of whether it is safe or not.
+    fn into_unsafe(self) -> impl Path<State, To, false> + Copy {
+        IntoUnsafe {
+            path: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+// Blanket implementation.
+impl<State, To, T, const SAFE: bool> IntoUnsafeExt<State, To, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, To, SAFE> + Copy,
+    To: ?Sized + 'static,
+{
+}
@@ -0,0 +1,149 @@
+//! Module providing an extension trait to get the length of a collection in
+//! the state.
+//!
+//! Example:
+//! ```
+//! use std::collections::HashMap;
+//! use rust_state::{Context, LenExt, RustState};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     items: Vec<u32>,
+//!     names: HashMap<u32, String>,
+//! }
+//!
+//! let context = Context::new(State {
+//!     items: vec![1, 2, 3],
+//!     names: HashMap::new(),
+//! });
+//!
+//! let items_len = State::path().items().len();
+//! let names_empty = State::path().names().is_empty();
+//!
+//! assert_eq!(context.compute(&items_len), 3);
+//! assert!(*context.get(&names_empty));
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::marker::PhantomData;
+
+use crate::{Compute, Path, Selector};
+
+/// A collection with a length.
+pub trait Length {
+    fn length(&self) -> usize;
+}
+
+impl<T> Length for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for VecDeque<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, const N: usize> Length for [T; N] {
+    fn length(&self) -> usize {
+        N
+    }
+}
+
+impl<K, V, S> Length for HashMap<K, V, S> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T, S> Length for HashSet<T, S> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> Length for BTreeMap<K, V> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> Length for BTreeSet<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Length for String {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+/// A computed selector for the length of a collection.
+///
+/// This type is not accessible outside this module, instead [`LenExt`] can be
+/// used to construct it and receive a `impl Compute<State, usize>` or `impl
+/// Selector<State, bool>`.
+struct Len<State, CollectionPath, Collection, const SAFE: bool> {
+    collection_path: CollectionPath,
+    _marker: PhantomData<(State, Collection)>,
+}
+
+impl<State, CollectionPath, Collection, const SAFE: bool> Compute<State, usize, SAFE> for Len<State, CollectionPath, Collection, SAFE>
+where
+    State: 'static,
+    CollectionPath: Path<State, Collection, SAFE>,
+    Collection: Length + 'static,
+{
+    fn compute(&self, state: &State) -> Option<usize> {
+        self.collection_path.follow(state).map(Length::length)
+    }
+}
+
+impl<State, CollectionPath, Collection, const SAFE: bool> Selector<State, bool, SAFE> for Len<State, CollectionPath, Collection, SAFE>
+where
+    State: 'static,
+    CollectionPath: Path<State, Collection, SAFE>,
+    Collection: Length + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a bool> {
+        let is_empty = self.collection_path.follow(state)?.length() == 0;
+        Some(if is_empty { &true } else { &false })
+    }
+}
+
+pub trait LenExt<State, Collection, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Collection, SAFE>,
+    Collection: Length + 'static,
+{
+    /// Computed selector for the number of items in the collection.
+    fn len(self) -> impl Compute<State, usize, SAFE> {
+        Len {
+            collection_path: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Selector that resolves to `true` if the collection has no items.
+    #[allow(clippy::wrong_self_convention)]
+    fn is_empty(self) -> impl Selector<State, bool, SAFE> {
+        Len {
+            collection_path: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Collection, const SAFE: bool> LenExt<State, Collection, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Collection, SAFE>,
+    Collection: Length + 'static,
+{
+}
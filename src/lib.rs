@@ -1,26 +1,201 @@
 #![feature(auto_traits)]
 #![feature(negative_impls)]
+#![doc(test(attr(feature(negative_impls))))]
 
 // Reexport self as `rust_state` so that the derive macro works in this crate.
 extern crate self as rust_state;
 
 mod array;
 mod as_ref;
+mod batch;
+mod boxed;
+mod btree_map;
+mod builder;
+mod cache;
+mod change;
+mod change_set;
+mod command;
+mod compare;
+mod compose;
+mod composite;
+mod compute;
 mod context;
+mod cow;
+mod deref;
 mod downcast;
+mod dynamic_path;
+mod exists;
+mod extensions;
+mod flags;
+mod fn_path;
+mod generated;
+mod into_unsafe;
+mod len;
+#[cfg(feature = "sync")]
+mod lock;
 mod manual;
 mod map;
+mod memo;
+#[cfg(feature = "metrics")]
+mod metrics;
 mod option;
+mod or;
+mod or_default;
 mod path;
+mod path_key;
+mod persist;
+mod project;
+mod read_only;
+mod reflect;
+mod registry;
+mod report;
+mod result;
+mod scope;
+#[cfg(feature = "serde")]
+mod session;
+mod set;
+mod shared;
+mod slice;
+#[cfg(feature = "slotmap")]
+mod slot_map;
+mod string;
+mod traversal;
+mod tuple;
+mod validator;
+mod variant;
 mod vec;
+mod write_adapter;
+mod zip;
 
 pub use array::ArrayLookupExt;
 pub use as_ref::AsRefExt;
-pub use context::{Context, StateMarker};
-pub use downcast::DowncastExt;
+pub use batch::{Batch, ChangeBatch};
+pub use boxed::BoxedPath;
+pub use btree_map::BTreeMapLookupExt;
+pub use builder::ContextBuilder;
+pub use cache::{Cached, CachedExt, Version, Versioned, VersionedExt};
+pub use change_set::{Change, ChangeKind, ChangeSet};
+pub use command::{Command, SetValue};
+pub use compare::CompareExt;
+pub use compose::{ComposedPath, PathComposeExt};
+pub use composite::{CompositeContext, CompositeRoot, FirstRoot, SecondRoot};
+pub use compute::{Compute, SelectorComputeExt};
+pub use context::{ApplyOrder, ChangeKey, Context, Delay, Priority, SourceKey, StateMarker};
+pub use cow::CowExt;
+pub use deref::DerefExt;
+pub use downcast::{AsAny, DowncastExt};
+pub use dynamic_path::{DynamicPath, ParsePathError};
+pub use exists::ExistsExt;
+pub use extensions::{Extensions, ExtensionsExt};
+pub use flags::{Flags, FlagsExt};
+pub use fn_path::FnPath;
+#[doc(hidden)]
+pub use generated::{FieldPath, RootPath};
+pub use into_unsafe::IntoUnsafeExt;
+pub use len::{LenExt, Length};
+#[cfg(feature = "sync")]
+pub use lock::Lock;
+/// Derive paths for every field of a struct.
+///
+/// Fields can refer to the type itself through `Self`:
+/// ```
+/// use rust_state::{Context, RustState};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct Node {
+///     children: Vec<Self>,
+/// }
+///
+/// let context = Context::new(Node { children: Vec::new() });
+///
+/// assert!(context.get(&Node::path().children()).is_empty());
+/// ```
+///
+/// Misuse is reported at the offending tokens instead of causing errors in
+/// the generated code. Unions can't be derived:
+/// ```compile_fail
+/// use rust_state::RustState;
+///
+/// #[derive(RustState)]
+/// union Bits {
+///     int: u32,
+///     float: f32,
+/// }
+/// ```
+///
+/// Neither can enums:
+/// ```compile_fail
+/// use rust_state::RustState;
+///
+/// #[derive(RustState)]
+/// enum Shape {
+///     Circle(f32),
+///     Square(f32),
+/// }
+/// ```
+///
+/// Types with lifetime parameters can't be derived, since paths need to be
+/// `'static`:
+/// ```compile_fail
+/// use rust_state::RustState;
+///
+/// #[derive(RustState)]
+/// struct Borrowed<'a> {
+///     name: &'a str,
+/// }
+/// ```
+///
+/// Some names are used by the generated code and can't be used for the type
+/// or its generic parameters:
+/// ```compile_fail
+/// use rust_state::RustState;
+///
+/// #[derive(RustState)]
+/// struct Wrapper<P> {
+///     value: P,
+/// }
+/// ```
+///
+/// `state_root` doesn't take any arguments:
+/// ```compile_fail
+/// use rust_state::RustState;
+///
+/// #[derive(RustState)]
+/// #[state_root(always)]
+/// struct State {
+///     value: u32,
+/// }
+/// ```
 pub use macros::RustState;
 pub use manual::ManuallyAssertExt;
-pub use map::{MapItem, MapLookupExt};
-pub use option::OptionExt;
-pub use path::{AutoImplSelector, Path, Selector};
-pub use vec::{VecIndexExt, VecItem, VecLookupExt};
+pub use map::{MapItem, MapLookupExt, MissingId};
+pub use memo::{MemoizeExt, Memoized};
+#[cfg(feature = "metrics")]
+pub use metrics::Metrics;
+pub use option::{OptionExt, OptionFlattenExt};
+pub use or::{OrPath, PathOrExt};
+pub use or_default::OrDefaultExt;
+pub use path::{AutoImplSelector, Path, PathRead, PathWrite, Selector, UnresolvedPath};
+pub use path_key::PathKey;
+pub use project::ProjectExt;
+pub use read_only::ReadOnlyContext;
+pub use reflect::{FromReflect, Reflect};
+pub use registry::PathRegistry;
+pub use report::ApplyReport;
+pub use result::ResultExt;
+pub use scope::ScopedContext;
+#[cfg(feature = "serde")]
+pub use session::Session;
+pub use set::{BTreeSetExt, SetContainsExt};
+pub use shared::{Shared, SharedExt};
+pub use slice::{AsSlice, AsSliceExt, SliceIndexExt};
+#[cfg(feature = "slotmap")]
+pub use slot_map::SlotMapLookupExt;
+pub use string::StringExt;
+pub use traversal::{IndexedTraversable, IndexedTraversal, Traversable, Traversal, TraversalExt};
+pub use tuple::{TupleElement, TupleExt};
+pub use variant::VariantExt;
+pub use vec::{DuplicateId, ReusedId, VecIndexExt, VecItem, VecLookupExt, VecRangeExt};
+pub use write_adapter::WriteAdapterExt;
+pub use zip::{SelectAll, Zip, ZipExt};
@@ -0,0 +1,73 @@
+//! Module providing [`Lock`], a common interface for [`Mutex`] and [`RwLock`]
+//! values in the state.
+//!
+//! Locked values can be read with
+//! [`Context::with_locked`](crate::Context::with_locked) and updated with
+//! [`Context::update_locked`](crate::Context::update_locked). Changes are
+//! applied with mutable access to the state, so updating never has to acquire
+//! the lock.
+//!
+//! Only available with the `sync` feature.
+//!
+//! Example:
+//! ```
+//! use std::sync::{Arc, Mutex};
+//! use rust_state::{Context, RustState, SharedExt};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     shared: Arc<Mutex<u32>>,
+//! }
+//!
+//! let shared = Arc::new(Mutex::new(1));
+//! let context = Context::new(State { shared: shared.clone() });
+//!
+//! // Another thread updates the value.
+//! std::thread::spawn(move || *shared.lock().unwrap() = 2).join().unwrap();
+//!
+//! let value = context.with_locked(State::path().shared().shared_deref(), |value: &u32| *value);
+//!
+//! assert_eq!(value, Some(2));
+//! ```
+
+use std::sync::{Mutex, RwLock};
+
+/// A value behind a lock.
+pub trait Lock {
+    type Target;
+
+    /// Acquire the lock and call the closure with the value. Returns `None`
+    /// if the lock is poisoned.
+    fn with_locked<R>(&self, closure: impl FnOnce(&Self::Target) -> R) -> Option<R>;
+
+    /// Get mutable access to the value without locking. Returns `None` if the
+    /// lock is poisoned.
+    fn locked_mut(&mut self) -> Option<&mut Self::Target>;
+}
+
+impl<T> Lock for Mutex<T> {
+    type Target = T;
+
+    fn with_locked<R>(&self, closure: impl FnOnce(&T) -> R) -> Option<R> {
+        let value = self.lock().ok()?;
+        Some(closure(&value))
+    }
+
+    fn locked_mut(&mut self) -> Option<&mut T> {
+        self.get_mut().ok()
+    }
+}
+
+impl<T> Lock for RwLock<T> {
+    type Target = T;
+
+    fn with_locked<R>(&self, closure: impl FnOnce(&T) -> R) -> Option<R> {
+        let value = self.read().ok()?;
+        Some(closure(&value))
+    }
+
+    fn locked_mut(&mut self) -> Option<&mut T> {
+        self.get_mut().ok()
+    }
+}
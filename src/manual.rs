@@ -38,7 +38,7 @@
 
 use std::marker::PhantomData;
 
-use crate::{Path, Selector};
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
 
 struct ManuallyAsserted<State, AssertedPath, To> {
     path: AssertedPath,
@@ -50,11 +50,14 @@ where
     AssertedPath: Path<State, To, false>,
 {
     fn clone(&self) -> Self {
-        *self
+        Self {
+            path: self.path.clone(),
+            _marker: PhantomData,
+        }
     }
 }
 
-impl<State, AssertedPath, To> Copy for ManuallyAsserted<State, AssertedPath, To> where AssertedPath: Path<State, To, false> {}
+impl<State, AssertedPath, To> Copy for ManuallyAsserted<State, AssertedPath, To> where AssertedPath: Path<State, To, false> + Copy {}
 
 impl<State, AssertedPath, To> Selector<State, To> for ManuallyAsserted<State, AssertedPath, To>
 where
@@ -67,7 +70,7 @@ where
     }
 }
 
-impl<State, AssertedPath, To> Path<State, To> for ManuallyAsserted<State, AssertedPath, To>
+impl<State, AssertedPath, To> PathRead<State, To> for ManuallyAsserted<State, AssertedPath, To>
 where
     State: 'static,
     AssertedPath: Path<State, To, false>,
@@ -77,6 +80,17 @@ where
         self.path.follow(state)
     }
 
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.path.key()?))
+    }
+}
+
+impl<State, AssertedPath, To> PathWrite<State, To> for ManuallyAsserted<State, AssertedPath, To>
+where
+    State: 'static,
+    AssertedPath: Path<State, To, false>,
+    To: 'static,
+{
     fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut To> {
         self.path.follow_mut(state)
     }
@@ -85,10 +99,10 @@ where
 pub trait ManuallyAssertExt<State, To>
 where
     State: 'static,
-    Self: Path<State, To, false>,
+    Self: Path<State, To, false> + Copy,
     To: 'static,
 {
-    fn manually_asserted(self) -> impl Path<State, To> {
+    fn manually_asserted(self) -> impl Path<State, To> + Copy {
         ManuallyAsserted {
             path: self,
             _marker: PhantomData,
@@ -100,7 +114,7 @@ where
 impl<State, To, T> ManuallyAssertExt<State, To> for T
 where
     State: 'static,
-    T: Path<State, To, false>,
+    T: Path<State, To, false> + Copy,
     To: 'static,
 {
 }
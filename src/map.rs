@@ -25,9 +25,15 @@
 //! let item_path = State::path().items().lookup(10);
 //!
 //! assert_eq!(context.try_get(&item_path), Some(&TestItem));
+//!
+//! let contains_path = State::path().items().contains(10);
+//!
+//! assert!(*context.get(&contains_path));
 //! ```
 
 use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
 use std::hash::Hash;
 use std::marker::PhantomData;
 
@@ -37,6 +43,30 @@ pub trait MapItem {
     type Id: Eq + PartialEq + Hash + Copy;
 }
 
+/// Error returned when there is no item with a given [`MapItem::Id`].
+///
+/// See [`Context::map_update`](crate::Context::map_update).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MissingId {
+    type_name: &'static str,
+}
+
+impl MissingId {
+    pub(crate) fn new<Item>() -> Self {
+        Self {
+            type_name: std::any::type_name::<Item>(),
+        }
+    }
+}
+
+impl fmt::Display for MissingId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Missing id in map of {}", self.type_name)
+    }
+}
+
+impl Error for MissingId {}
+
 struct MapLookup<State, Path, Item, const SAFE: bool>
 where
     Item: MapItem,
@@ -52,13 +82,17 @@ where
     Item: MapItem,
 {
     fn clone(&self) -> Self {
-        *self
+        Self {
+            path: self.path.clone(),
+            id: self.id,
+            _marker: PhantomData,
+        }
     }
 }
 
 impl<State, Path, Item, const SAFE: bool> Copy for MapLookup<State, Path, Item, SAFE>
 where
-    Path: crate::Path<State, HashMap<Item::Id, Item>, SAFE>,
+    Path: crate::Path<State, HashMap<Item::Id, Item>, SAFE> + Copy,
     Item: MapItem,
 {
 }
@@ -74,7 +108,7 @@ where
     }
 }
 
-impl<State, Path, Item, const SAFE: bool> crate::Path<State, Item, false> for MapLookup<State, Path, Item, SAFE>
+impl<State, Path, Item, const SAFE: bool> crate::PathRead<State, Item, false> for MapLookup<State, Path, Item, SAFE>
 where
     State: 'static,
     Path: crate::Path<State, HashMap<Item::Id, Item>, SAFE>,
@@ -84,30 +118,189 @@ where
         self.path.follow(state)?.get(&self.id)
     }
 
+    fn key(&self) -> Option<crate::PathKey> {
+        Some(crate::PathKey::new::<Self>().with(self.path.key()?).with(self.id))
+    }
+}
+
+impl<State, Path, Item, const SAFE: bool> crate::PathWrite<State, Item, false> for MapLookup<State, Path, Item, SAFE>
+where
+    State: 'static,
+    Path: crate::Path<State, HashMap<Item::Id, Item>, SAFE>,
+    Item: MapItem + 'static,
+{
     fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Item> {
         self.path.follow_mut(state)?.get_mut(&self.id)
     }
 }
 
+/// A path to an entry of a map that inserts a default value when followed
+/// mutably.
+///
+/// This type is not accessible outside this module, instead [`MapLookupExt`]
+/// can be used to construct it and receive a `impl Path<State, Item>`.
+struct MapEntryOrDefault<State, Path, Item, const SAFE: bool>
+where
+    Item: MapItem,
+{
+    path: Path,
+    id: Item::Id,
+    _marker: PhantomData<State>,
+}
+
+impl<State, Path, Item, const SAFE: bool> Clone for MapEntryOrDefault<State, Path, Item, SAFE>
+where
+    Path: crate::Path<State, HashMap<Item::Id, Item>, SAFE>,
+    Item: MapItem,
+{
+    fn clone(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            id: self.id,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, Path, Item, const SAFE: bool> Copy for MapEntryOrDefault<State, Path, Item, SAFE>
+where
+    Path: crate::Path<State, HashMap<Item::Id, Item>, SAFE> + Copy,
+    Item: MapItem,
+{
+}
+
+impl<State, Path, Item, const SAFE: bool> Selector<State, Item, false> for MapEntryOrDefault<State, Path, Item, SAFE>
+where
+    State: 'static,
+    Path: crate::Path<State, HashMap<Item::Id, Item>, SAFE>,
+    Item: MapItem + Default + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Item> {
+        self.path.follow(state)?.get(&self.id)
+    }
+}
+
+impl<State, Path, Item, const SAFE: bool> crate::PathRead<State, Item, false> for MapEntryOrDefault<State, Path, Item, SAFE>
+where
+    State: 'static,
+    Path: crate::Path<State, HashMap<Item::Id, Item>, SAFE>,
+    Item: MapItem + Default + 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Item> {
+        self.path.follow(state)?.get(&self.id)
+    }
+
+    fn key(&self) -> Option<crate::PathKey> {
+        Some(crate::PathKey::new::<Self>().with(self.path.key()?).with(self.id))
+    }
+}
+
+impl<State, Path, Item, const SAFE: bool> crate::PathWrite<State, Item, false> for MapEntryOrDefault<State, Path, Item, SAFE>
+where
+    State: 'static,
+    Path: crate::Path<State, HashMap<Item::Id, Item>, SAFE>,
+    Item: MapItem + Default + 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Item> {
+        Some(self.path.follow_mut(state)?.entry(self.id).or_default())
+    }
+}
+
+struct MapContains<State, Path, Item, const SAFE: bool>
+where
+    Item: MapItem,
+{
+    path: Path,
+    id: Item::Id,
+    _marker: PhantomData<State>,
+}
+
+impl<State, Path, Item, const SAFE: bool> Selector<State, bool, SAFE> for MapContains<State, Path, Item, SAFE>
+where
+    State: 'static,
+    Path: crate::Path<State, HashMap<Item::Id, Item>, SAFE>,
+    Item: MapItem + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a bool> {
+        let contains = self.path.follow(state)?.contains_key(&self.id);
+        Some(if contains { &true } else { &false })
+    }
+}
+
 pub trait MapLookupExt<State, T, Item, const SAFE: bool>
 where
     State: 'static,
-    Self: Path<State, HashMap<Item::Id, Item>, SAFE>,
+    Self: Path<State, HashMap<Item::Id, Item>, SAFE> + Copy,
     Item: MapItem + 'static,
 {
-    fn lookup(self, id: Item::Id) -> impl Path<State, Item, false> {
+    fn lookup(self, id: Item::Id) -> impl Path<State, Item, false> + Copy {
         MapLookup {
             path: self,
             id,
             _marker: PhantomData,
         }
     }
+
+    /// Path to the item with the given id. Following the path mutably inserts
+    /// a default item if there is none, so writes through the path always
+    /// succeed while reads still miss.
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::HashMap;
+    /// use rust_state::{Context, MapItem, MapLookupExt, RustState};
+    ///
+    /// #[derive(Default)]
+    /// struct Counter {
+    ///     count: u32,
+    /// }
+    ///
+    /// impl MapItem for Counter {
+    ///     type Id = u32;
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct State {
+    ///     counters: HashMap<u32, Counter>,
+    /// }
+    ///
+    /// let mut context = Context::new(State { counters: HashMap::new() });
+    /// let counter_path = State::path().counters().entry_or_default(10);
+    ///
+    /// assert!(context.try_get(&counter_path).is_none());
+    ///
+    /// context.update_value_with(counter_path, |counter| counter.count += 1);
+    /// context.apply();
+    ///
+    /// assert_eq!(context.try_get(&counter_path).map(|counter| counter.count), Some(1));
+    /// ```
+    fn entry_or_default(self, id: Item::Id) -> impl Path<State, Item, false> + Copy
+    where
+        Item: Default,
+    {
+        MapEntryOrDefault {
+            path: self,
+            id,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Selector that resolves to `true` if the map contains an item with the
+    /// given id.
+    fn contains(self, id: Item::Id) -> impl Selector<State, bool, SAFE> {
+        MapContains {
+            path: self,
+            id,
+            _marker: PhantomData,
+        }
+    }
 }
 
 impl<State, T, Item, const SAFE: bool> MapLookupExt<State, T, Item, SAFE> for T
 where
     State: 'static,
-    T: Path<State, HashMap<Item::Id, Item>, SAFE>,
+    T: Path<State, HashMap<Item::Id, Item>, SAFE> + Copy,
     Item: MapItem + 'static,
 {
 }
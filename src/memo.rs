@@ -0,0 +1,96 @@
+//! Module providing [`Memoized`], a [`Compute`] that is only recomputed when
+//! the state changes.
+//!
+//! Every [`Context`](crate::Context) has a [`version`](crate::Context::version)
+//! that changes whenever the state is mutated. A memoized compute remembers
+//! the version its output was computed for and returns the stored output until
+//! the version changes.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Compute, Context, MemoizeExt, RustState};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     scores: Vec<u32>,
+//! }
+//!
+//! struct HighScores;
+//!
+//! impl Compute<State, Vec<u32>> for HighScores {
+//!     fn compute(&self, state: &State) -> Option<Vec<u32>> {
+//!         let mut scores: Vec<u32> = state.scores.iter().copied().filter(|score| *score >= 10).collect();
+//!         scores.sort_unstable();
+//!         Some(scores)
+//!     }
+//! }
+//!
+//! let mut context = Context::new(State { scores: vec![20, 5, 10] });
+//! let high_scores = HighScores.memoized();
+//!
+//! assert_eq!(*context.get_memoized(&high_scores), vec![10, 20]);
+//!
+//! context.vec_push(State::path().scores(), 15);
+//! context.apply();
+//!
+//! assert_eq!(*context.get_memoized(&high_scores), vec![10, 15, 20]);
+//! ```
+
+use std::cell::{Ref, RefCell};
+use std::marker::PhantomData;
+
+use crate::Compute;
+
+/// A [`Compute`] with its last output.
+///
+/// [`MemoizeExt::memoized`] is used to construct it. The output is keyed by
+/// the version of the context only, so a `Memoized` should only ever be read
+/// from a single context.
+pub struct Memoized<State, Inner, Output, const SAFE: bool> {
+    compute: Inner,
+    output: RefCell<Option<(u64, Option<Output>)>>,
+    _marker: PhantomData<State>,
+}
+
+impl<State, Inner, Output, const SAFE: bool> Memoized<State, Inner, Output, SAFE>
+where
+    Inner: Compute<State, Output, SAFE>,
+{
+    /// Get the output for the given version of the state, only computing it
+    /// if the version changed since the last call.
+    pub(crate) fn output_for_version(&self, state: &State, version: u64) -> Option<Ref<'_, Output>> {
+        let is_current = matches!(&*self.output.borrow(), Some((output_version, _)) if *output_version == version);
+
+        if !is_current {
+            let output = self.compute.compute(state);
+            *self.output.borrow_mut() = Some((version, output));
+        }
+
+        Ref::filter_map(self.output.borrow(), |output| {
+            output.as_ref().and_then(|(_, output)| output.as_ref())
+        })
+        .ok()
+    }
+
+    /// Drop the stored output, so it is recomputed on the next read.
+    pub fn invalidate(&self) {
+        self.output.borrow_mut().take();
+    }
+}
+
+pub trait MemoizeExt<State, Output, const SAFE: bool>
+where
+    Self: Compute<State, Output, SAFE> + Sized,
+{
+    /// Only recompute the output if the state changed since the last read.
+    fn memoized(self) -> Memoized<State, Self, Output, SAFE> {
+        Memoized {
+            compute: self,
+            output: RefCell::new(None),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Output, const SAFE: bool> MemoizeExt<State, Output, SAFE> for T where T: Compute<State, Output, SAFE> {}
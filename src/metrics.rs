@@ -0,0 +1,82 @@
+//! Module providing [`Metrics`] about the changes processed by a
+//! [`Context`](crate::Context).
+//!
+//! Only available with the `metrics` feature.
+
+use std::time::Duration;
+
+use crate::ApplyReport;
+
+/// Counters for the changes processed by a [`Context`](crate::Context).
+///
+/// See [`Context::metrics`](crate::Context::metrics).
+///
+/// Example:
+/// ```
+/// use rust_state::{Context, RustState};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct MyState {
+///     value: u32,
+/// }
+///
+/// let mut context = Context::new(MyState { value: 5 });
+///
+/// context.update_value(MyState::path().value(), 10);
+/// context.update_value(MyState::path().value(), 20);
+/// context.apply();
+///
+/// let metrics = context.metrics();
+///
+/// assert_eq!(metrics.queued_changes(), 2);
+/// assert_eq!(metrics.applied_changes(), 2);
+/// assert_eq!(metrics.apply_calls(), 1);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Metrics {
+    queued_changes: u64,
+    applied_changes: u64,
+    failed_changes: u64,
+    apply_calls: u64,
+    apply_time: Duration,
+}
+
+impl Metrics {
+    pub(crate) fn record_queued(&mut self) {
+        self.queued_changes += 1;
+    }
+
+    pub(crate) fn record_apply(&mut self, report: &ApplyReport, apply_time: Duration) {
+        self.applied_changes += report.applied_changes() as u64;
+        self.failed_changes += report.errors().len() as u64;
+        self.apply_calls += 1;
+        self.apply_time += apply_time;
+    }
+
+    /// Get the number of queued changes, including delayed changes.
+    pub fn queued_changes(&self) -> u64 {
+        self.queued_changes
+    }
+
+    /// Get the number of applied changes, including failed ones.
+    pub fn applied_changes(&self) -> u64 {
+        self.applied_changes
+    }
+
+    /// Get the number of applied changes that returned an error.
+    pub fn failed_changes(&self) -> u64 {
+        self.failed_changes
+    }
+
+    /// Get the number of calls to [`Context::apply`](crate::Context::apply)
+    /// and its variants.
+    pub fn apply_calls(&self) -> u64 {
+        self.apply_calls
+    }
+
+    /// Get the total time spent applying changes, including hooks.
+    pub fn apply_time(&self) -> Duration {
+        self.apply_time
+    }
+}
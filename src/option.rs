@@ -30,7 +30,7 @@
 
 use std::marker::PhantomData;
 
-use crate::{Path, Selector};
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
 
 /// A path that unwraps an [`Option<T>`] in the state tree into a path of `T`.
 ///
@@ -46,12 +46,15 @@ where
     OptionPath: Path<State, Option<Unwrapped>, SAFE>,
 {
     fn clone(&self) -> Self {
-        *self
+        Self {
+            option_path: self.option_path.clone(),
+            _marker: PhantomData,
+        }
     }
 }
 
 impl<State, OptionPath, Unwrapped, const SAFE: bool> Copy for OptionUnwrapped<State, OptionPath, Unwrapped, SAFE> where
-    OptionPath: Path<State, Option<Unwrapped>, SAFE>
+    OptionPath: Path<State, Option<Unwrapped>, SAFE> + Copy
 {
 }
 
@@ -67,7 +70,8 @@ where
     }
 }
 
-impl<State, OptionPath, Unwrapped, const SAFE: bool> Path<State, Unwrapped, false> for OptionUnwrapped<State, OptionPath, Unwrapped, SAFE>
+impl<State, OptionPath, Unwrapped, const SAFE: bool> PathRead<State, Unwrapped, false>
+    for OptionUnwrapped<State, OptionPath, Unwrapped, SAFE>
 where
     State: 'static,
     OptionPath: Path<State, Option<Unwrapped>, SAFE>,
@@ -77,11 +81,113 @@ where
         self.option_path.follow(state).and_then(Option::as_ref)
     }
 
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.option_path.key()?))
+    }
+}
+
+impl<State, OptionPath, Unwrapped, const SAFE: bool> PathWrite<State, Unwrapped, false>
+    for OptionUnwrapped<State, OptionPath, Unwrapped, SAFE>
+where
+    State: 'static,
+    OptionPath: Path<State, Option<Unwrapped>, SAFE>,
+    Unwrapped: 'static,
+{
     fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Unwrapped> {
         self.option_path.follow_mut(state).and_then(Option::as_mut)
     }
 }
 
+/// A path that unwraps a nested [`Option<Option<T>>`] in the state tree into
+/// a path of `T`.
+///
+/// This type is not accessible outside this module. Instead,
+/// [`OptionFlattenExt`] is used to construct it and receive an `impl
+/// Path<State, T>`.
+struct OptionFlattened<State, OptionPath, Unwrapped, const SAFE: bool> {
+    option_path: OptionPath,
+    _marker: PhantomData<(State, Unwrapped)>,
+}
+
+impl<State, OptionPath, Unwrapped, const SAFE: bool> Clone for OptionFlattened<State, OptionPath, Unwrapped, SAFE>
+where
+    OptionPath: Path<State, Option<Option<Unwrapped>>, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            option_path: self.option_path.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, OptionPath, Unwrapped, const SAFE: bool> Copy for OptionFlattened<State, OptionPath, Unwrapped, SAFE> where
+    OptionPath: Path<State, Option<Option<Unwrapped>>, SAFE> + Copy
+{
+}
+
+impl<State, OptionPath, Unwrapped, const SAFE: bool> Selector<State, Unwrapped, false>
+    for OptionFlattened<State, OptionPath, Unwrapped, SAFE>
+where
+    State: 'static,
+    OptionPath: Path<State, Option<Option<Unwrapped>>, SAFE>,
+    Unwrapped: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Unwrapped> {
+        self.follow(state)
+    }
+}
+
+impl<State, OptionPath, Unwrapped, const SAFE: bool> PathRead<State, Unwrapped, false>
+    for OptionFlattened<State, OptionPath, Unwrapped, SAFE>
+where
+    State: 'static,
+    OptionPath: Path<State, Option<Option<Unwrapped>>, SAFE>,
+    Unwrapped: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Unwrapped> {
+        self.option_path.follow(state)?.as_ref()?.as_ref()
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.option_path.key()?))
+    }
+}
+
+impl<State, OptionPath, Unwrapped, const SAFE: bool> PathWrite<State, Unwrapped, false>
+    for OptionFlattened<State, OptionPath, Unwrapped, SAFE>
+where
+    State: 'static,
+    OptionPath: Path<State, Option<Option<Unwrapped>>, SAFE>,
+    Unwrapped: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Unwrapped> {
+        self.option_path.follow_mut(state)?.as_mut()?.as_mut()
+    }
+}
+
+/// A selector that resolves to `true` if an [`Option<T>`] in the state tree
+/// is [`Some`].
+///
+/// This type is not accessible outside this module. Instead, [`OptionExt`]
+/// is used to construct it and receive an `impl Selector<State, bool>`.
+struct OptionIsSome<State, OptionPath, Unwrapped, const SAFE: bool> {
+    option_path: OptionPath,
+    _marker: PhantomData<(State, Unwrapped)>,
+}
+
+impl<State, OptionPath, Unwrapped, const SAFE: bool> Selector<State, bool, SAFE> for OptionIsSome<State, OptionPath, Unwrapped, SAFE>
+where
+    State: 'static,
+    OptionPath: Path<State, Option<Unwrapped>, SAFE>,
+    Unwrapped: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a bool> {
+        let is_some = self.option_path.follow(state)?.is_some();
+        Some(if is_some { &true } else { &false })
+    }
+}
+
 /// Extension trait providing `.unwrapped()` for [`Option<T>`] paths.
 ///
 /// Converts a `Path<State, Option<T>>` into a `Path<State, T>`, returning
@@ -89,7 +195,7 @@ where
 pub trait OptionExt<State, T, Unwrapped, const SAFE: bool>
 where
     State: 'static,
-    Self: Path<State, Option<Unwrapped>, SAFE>,
+    Self: Path<State, Option<Unwrapped>, SAFE> + Copy,
     Unwrapped: 'static,
 {
     /// Unwrap the [`Option`] in this path, converting a `Path<State,
@@ -97,18 +203,97 @@ where
     ///
     /// This path is *not* safe. It may return `None` at runtime if the
     /// underlying [`Option`] is `None`.
-    fn unwrapped(self) -> impl Path<State, Unwrapped, false> {
+    fn unwrapped(self) -> impl Path<State, Unwrapped, false> + Copy {
         OptionUnwrapped {
             option_path: self,
             _marker: PhantomData,
         }
     }
+
+    /// Selector that resolves to `true` if the [`Option`] is [`Some`]. The
+    /// selector is safe if this path is safe.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, OptionExt, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct State {
+    ///     selected: Option<u32>,
+    /// }
+    ///
+    /// let mut context = Context::new(State { selected: None });
+    /// let has_selection = State::path().selected().is_some();
+    ///
+    /// assert!(!*context.get(&has_selection));
+    ///
+    /// context.update_value(State::path().selected(), Some(3));
+    /// context.apply();
+    ///
+    /// assert!(*context.get(&has_selection));
+    /// ```
+    fn is_some(self) -> impl Selector<State, bool, SAFE> {
+        OptionIsSome {
+            option_path: self,
+            _marker: PhantomData,
+        }
+    }
 }
 
 impl<State, T, Unwrapped, const SAFE: bool> OptionExt<State, T, Unwrapped, SAFE> for T
 where
     State: 'static,
-    T: Path<State, Option<Unwrapped>, SAFE>,
+    T: Path<State, Option<Unwrapped>, SAFE> + Copy,
+    Unwrapped: 'static,
+{
+}
+
+/// Extension trait providing `.flattened()` for [`Option<Option<T>>`] paths.
+pub trait OptionFlattenExt<State, Unwrapped, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Option<Option<Unwrapped>>, SAFE> + Copy,
+    Unwrapped: 'static,
+{
+    /// Unwrap both layers of the nested [`Option`] in this path, converting a
+    /// `Path<State, Option<Option<T>>>` into a `Path<State, T>`.
+    ///
+    /// This path is *not* safe. It may return `None` at runtime if either
+    /// layer is `None`.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, OptionFlattenExt, RustState};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct State {
+    ///     override_color: Option<Option<u32>>,
+    /// }
+    ///
+    /// let mut context = Context::new(State { override_color: Some(None) });
+    /// let color_path = State::path().override_color().flattened();
+    ///
+    /// assert_eq!(context.try_get(&color_path), None);
+    ///
+    /// context.update_value(State::path().override_color(), Some(Some(0xff)));
+    /// context.apply();
+    ///
+    /// assert_eq!(context.try_get(&color_path), Some(&0xff));
+    /// ```
+    fn flattened(self) -> impl Path<State, Unwrapped, false> + Copy {
+        OptionFlattened {
+            option_path: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Unwrapped, const SAFE: bool> OptionFlattenExt<State, Unwrapped, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Option<Option<Unwrapped>>, SAFE> + Copy,
     Unwrapped: 'static,
 {
 }
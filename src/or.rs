@@ -0,0 +1,151 @@
+//! Module providing an extension trait to fall back to a second path if the
+//! first one doesn't resolve.
+//!
+//! The combined path is safe if either of the paths is safe.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, OptionExt, PathOrExt, RustState};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     user_volume: Option<u32>,
+//!     default_volume: u32,
+//! }
+//!
+//! let mut context = Context::new(State {
+//!     user_volume: None,
+//!     default_volume: 50,
+//! });
+//!
+//! let volume_path = State::path().user_volume().unwrapped().or(State::path().default_volume());
+//!
+//! assert_eq!(context.get(&volume_path), &50);
+//!
+//! context.update_value(State::path().user_volume(), Some(80));
+//! context.apply();
+//!
+//! assert_eq!(context.get(&volume_path), &80);
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// A path that resolves to the target of the first path, or the target of
+/// the second path if the first one doesn't resolve.
+///
+/// [`PathOrExt::or`] is used to construct it. It implements `Path<State, To,
+/// true>` if either path is safe and `Path<State, To, false>` otherwise.
+pub struct OrPath<State, First, Second, To: ?Sized, const FIRST_SAFE: bool, const SECOND_SAFE: bool> {
+    first: First,
+    second: Second,
+    _marker: PhantomData<(State, To)>,
+}
+
+impl<State, First, Second, To: ?Sized, const FIRST_SAFE: bool, const SECOND_SAFE: bool> Clone
+    for OrPath<State, First, Second, To, FIRST_SAFE, SECOND_SAFE>
+where
+    First: Path<State, To, FIRST_SAFE>,
+    Second: Path<State, To, SECOND_SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            first: self.first.clone(),
+            second: self.second.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, First, Second, To: ?Sized, const FIRST_SAFE: bool, const SECOND_SAFE: bool> Copy
+    for OrPath<State, First, Second, To, FIRST_SAFE, SECOND_SAFE>
+where
+    First: Path<State, To, FIRST_SAFE> + Copy,
+    Second: Path<State, To, SECOND_SAFE> + Copy,
+{
+}
+
+macro_rules! implement_or_path {
+    ($first_safe:literal, $second_safe:literal => $safe:literal) => {
+        impl<State, First, Second, To> Selector<State, To, $safe> for OrPath<State, First, Second, To, $first_safe, $second_safe>
+        where
+            State: 'static,
+            First: Path<State, To, $first_safe>,
+            Second: Path<State, To, $second_safe>,
+            To: ?Sized + 'static,
+        {
+            fn select<'a>(&'a self, state: &'a State) -> Option<&'a To> {
+                self.follow(state)
+            }
+        }
+
+        impl<State, First, Second, To> PathRead<State, To, $safe> for OrPath<State, First, Second, To, $first_safe, $second_safe>
+        where
+            State: 'static,
+            First: Path<State, To, $first_safe>,
+            Second: Path<State, To, $second_safe>,
+            To: ?Sized + 'static,
+        {
+            fn follow<'a>(&self, state: &'a State) -> Option<&'a To> {
+                self.first.follow(state).or_else(|| self.second.follow(state))
+            }
+
+            fn key(&self) -> Option<PathKey> {
+                Some(PathKey::new::<Self>().with(self.first.key()?).with(self.second.key()?))
+            }
+        }
+
+        impl<State, First, Second, To> PathWrite<State, To, $safe> for OrPath<State, First, Second, To, $first_safe, $second_safe>
+        where
+            State: 'static,
+            First: Path<State, To, $first_safe>,
+            Second: Path<State, To, $second_safe>,
+            To: ?Sized + 'static,
+        {
+            fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut To> {
+                // Returning the first mutable borrow conditionally is rejected by the borrow
+                // checker, so check if the first path resolves before borrowing mutably.
+                if self.first.follow(state).is_some() {
+                    self.first.follow_mut(state)
+                } else {
+                    self.second.follow_mut(state)
+                }
+            }
+        }
+    };
+}
+
+implement_or_path!(true, true => true);
+implement_or_path!(true, false => true);
+implement_or_path!(false, true => true);
+implement_or_path!(false, false => false);
+
+pub trait PathOrExt<State, To, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, To, SAFE>,
+    To: ?Sized + 'static,
+{
+    /// Fall back to another path to the same target if this path doesn't
+    /// resolve.
+    fn or<Second, const SECOND_SAFE: bool>(self, second: Second) -> OrPath<State, Self, Second, To, SAFE, SECOND_SAFE>
+    where
+        Second: Path<State, To, SECOND_SAFE>,
+    {
+        OrPath {
+            first: self,
+            second,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, To, const SAFE: bool> PathOrExt<State, To, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, To, SAFE>,
+    To: ?Sized + 'static,
+{
+}
@@ -0,0 +1,103 @@
+//! Module providing an extension trait to turn an unsafe selector into a safe
+//! one by falling back to a default value.
+//!
+//! This is an alternative to
+//! [`manually_asserted`](crate::ManuallyAssertExt::manually_asserted) for
+//! lookups where a miss is expected and a default is fine.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, OptionExt, OrDefaultExt, ProjectExt, RustState};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     volume: Option<u32>,
+//!     nickname: Option<String>,
+//! }
+//!
+//! let mut context = Context::new(State {
+//!     volume: None,
+//!     nickname: None,
+//! });
+//!
+//! let volume = State::path().volume().unwrapped().or_default();
+//! let nickname = State::path().nickname().unwrapped().project(String::as_str).or_value("Unknown");
+//!
+//! // The selectors are safe, so `get` can be used.
+//! assert_eq!(context.get(&volume), &0);
+//! assert_eq!(context.get(&nickname), "Unknown");
+//!
+//! context.update_value(State::path().volume(), Some(80));
+//! context.update_value(State::path().nickname(), Some("Alice".to_owned()));
+//! context.apply();
+//!
+//! assert_eq!(context.get(&volume), &80);
+//! assert_eq!(context.get(&nickname), "Alice");
+//! ```
+
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+
+use crate::Selector;
+
+/// A safe selector that resolves to a fallback if the inner selector doesn't
+/// resolve.
+///
+/// This type is not accessible outside this module, instead [`OrDefaultExt`]
+/// can be used to construct it and receive an `impl Selector<State, To>`.
+struct OrFallback<State, Inner, To: ?Sized, Fallback, const SAFE: bool> {
+    selector: Inner,
+    fallback: Fallback,
+    _marker: PhantomData<(State, To)>,
+}
+
+impl<State, Inner, To, Fallback, const SAFE: bool> Selector<State, To> for OrFallback<State, Inner, To, Fallback, SAFE>
+where
+    State: 'static,
+    Inner: Selector<State, To, SAFE>,
+    To: ?Sized + 'static,
+    Fallback: Borrow<To> + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a To> {
+        Some(self.selector.select(state).unwrap_or_else(|| self.fallback.borrow()))
+    }
+}
+
+pub trait OrDefaultExt<State, To, const SAFE: bool>
+where
+    State: 'static,
+    Self: Selector<State, To, SAFE> + Sized,
+    To: ?Sized + 'static,
+{
+    /// Safe selector that resolves to the default value if this selector
+    /// doesn't resolve.
+    fn or_default(self) -> impl Selector<State, To>
+    where
+        To: Default,
+    {
+        OrFallback::<_, _, To, _, SAFE> {
+            selector: self,
+            fallback: To::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Safe selector that resolves to `value` if this selector doesn't
+    /// resolve.
+    fn or_value(self, value: &'static To) -> impl Selector<State, To> {
+        OrFallback::<_, _, To, _, SAFE> {
+            selector: self,
+            fallback: value,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, To, const SAFE: bool> OrDefaultExt<State, To, SAFE> for T
+where
+    State: 'static,
+    T: Selector<State, To, SAFE>,
+    To: ?Sized + 'static,
+{
+}
@@ -1,6 +1,11 @@
 //! Module providing the base mechanism for indexing state, namely [`Path`] and
 //! [`Selector`].
 
+use std::error::Error;
+use std::fmt;
+
+use crate::PathKey;
+
 /// A `Selector` can be used to get an item from the state or itself.
 ///
 /// `Selector<State, T>` is implemented for `T`, so any value is also a
@@ -64,14 +69,16 @@ where
 /// A `Path` can be followed to get a mutable or immutable reference to
 /// arbitrary data from the state.
 ///
-/// Paths are forced to be [`Copy`] so they are easier to pass around and
-/// duplicate.
+/// Paths are forced to be [`Clone`] so they are easier to pass around and
+/// duplicate. Almost all paths are [`Copy`] as well, the exception being a
+/// [`BoxedPath`](crate::BoxedPath).
 ///
 /// Additionally, every path is forced to implement [`Selector`] to improve the
 /// ergonomics of the [`Context`].
 ///
-/// The [`Path`] trait is automatically implemented when deriving
-/// [`RustState`](crate::RustState).
+/// `Path` is the combination of [`PathRead`] and [`PathWrite`] and is
+/// implemented for every type implementing both. Both traits are
+/// automatically implemented when deriving [`RustState`](crate::RustState).
 ///
 /// Example:
 ///```
@@ -110,10 +117,95 @@ where
 ///
 /// let path = GlobalState::path().generic();
 /// ```
-pub trait Path<State, To: ?Sized, const SAFE: bool = true>: Selector<State, To, SAFE> + Copy {
+pub trait Path<State, To: ?Sized, const SAFE: bool = true>: PathWrite<State, To, SAFE> {}
+
+impl<State, To: ?Sized, T, const SAFE: bool> Path<State, To, SAFE> for T where T: PathWrite<State, To, SAFE> {}
+
+/// The read half of a [`Path`].
+///
+/// Projections that can't hand out mutable references, like the target of an
+/// [`Arc`](std::sync::Arc), only implement `PathRead`. They can still be read
+/// with the [`Context`](crate::Context) but can't be updated.
+///
+/// Example:
+/// ```
+/// use std::sync::Arc;
+/// use rust_state::{Context, PathRead, RustState, SharedExt};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct GlobalState {
+///     shared: Arc<u32>,
+/// }
+///
+/// fn takes_read_path(_: impl PathRead<GlobalState, u32>) {}
+///
+/// let context = Context::new(GlobalState { shared: Arc::new(10) });
+/// let shared_path = GlobalState::path().shared().shared_deref();
+///
+/// takes_read_path(shared_path);
+///
+/// assert_eq!(context.follow(shared_path), &10);
+/// ```
+pub trait PathRead<State, To: ?Sized, const SAFE: bool = true>: Selector<State, To, SAFE> + Clone {
     /// Follow the path and try to return a reference to its target.
     fn follow<'a>(&self, state: &'a State) -> Option<&'a To>;
 
+    /// Get a [`PathKey`] identifying the location this path points to.
+    ///
+    /// The key has to include all runtime data of the path, like ids or the
+    /// keys of inner paths. Paths that can't be identified, for example
+    /// because they call a function pointer, return [`None`] and can't be
+    /// used with key-based APIs.
+    fn key(&self) -> Option<PathKey>;
+}
+
+/// The write half of a [`Path`].
+pub trait PathWrite<State, To: ?Sized, const SAFE: bool = true>: PathRead<State, To, SAFE> {
     /// Follow the path and try to return a mutable reference to its target.
     fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut To>;
+
+    /// Follow the path and replace its target with a new value. Returns
+    /// `false` if the path doesn't resolve.
+    ///
+    /// Paths that transform written values, like the ones created by
+    /// [`with_write_adapter`](crate::WriteAdapterExt::with_write_adapter),
+    /// override this.
+    fn write(&self, state: &mut State, value: To) -> bool
+    where
+        To: Sized,
+    {
+        match self.follow_mut(state) {
+            Some(reference) => {
+                *reference = value;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Error returned when a path doesn't resolve while applying a fallible
+/// change.
+///
+/// See [`Context::update_value_try`](crate::Context::update_value_try).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnresolvedPath {
+    type_name: &'static str,
 }
+
+impl UnresolvedPath {
+    pub(crate) fn new<To: ?Sized>() -> Self {
+        Self {
+            type_name: std::any::type_name::<To>(),
+        }
+    }
+}
+
+impl fmt::Display for UnresolvedPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Path to {} did not resolve", self.type_name)
+    }
+}
+
+impl Error for UnresolvedPath {}
@@ -0,0 +1,101 @@
+//! Module providing [`PathKey`], a hashable identity for paths.
+
+use std::any::TypeId;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// A key identifying the location a path points to.
+///
+/// Two paths have the same key if they have the same type and were
+/// constructed with the same runtime data, like the ids passed to
+/// [`lookup`](crate::VecLookupExt::lookup). This allows using locations in the
+/// state as keys for caches, subscription tables and the like.
+///
+/// Runtime data is hashed, so keys only identify paths within the same
+/// process. Functions and closures are identified by their type, see
+/// [`with_fn`](Self::with_fn). Paths that can't be identified, like paths
+/// using function pointers or closures that capture values, have no key.
+///
+/// Example:
+/// ```
+/// use rust_state::{PathRead, RustState, VecItem, VecLookupExt};
+///
+/// struct Item {
+///     id: u32,
+///     name: String,
+/// }
+///
+/// impl VecItem for Item {
+///     type Id = u32;
+///
+///     fn get_id(&self) -> Self::Id {
+///         self.id
+///     }
+/// }
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct State {
+///     items: Vec<Item>,
+///     selected: Vec<Item>,
+/// }
+///
+/// let first = State::path().items().lookup(1);
+///
+/// assert!(first.key().is_some());
+/// assert_eq!(first.key(), State::path().items().lookup(1).key());
+/// assert_ne!(first.key(), State::path().items().lookup(2).key());
+/// assert_ne!(first.key(), State::path().selected().lookup(1).key());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PathKey {
+    type_id: TypeId,
+    data: u64,
+}
+
+impl PathKey {
+    /// Create the key for a path type without any runtime data.
+    pub fn new<P: ?Sized + 'static>() -> Self {
+        Self {
+            type_id: TypeId::of::<P>(),
+            data: 0,
+        }
+    }
+
+    /// Add runtime data, for example an id or the key of an inner path.
+    pub fn with(self, data: impl Hash) -> Self {
+        let mut hasher = DefaultHasher::new();
+        self.data.hash(&mut hasher);
+        data.hash(&mut hasher);
+
+        Self {
+            type_id: self.type_id,
+            data: hasher.finish(),
+        }
+    }
+
+    /// Add a function or closure of type `F`.
+    ///
+    /// Function items and closures that don't capture anything are zero-sized
+    /// and fully identified by their type. Function pointers and capturing
+    /// closures carry data that can't be hashed, so [`None`] is returned for
+    /// them.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::PathKey;
+    ///
+    /// fn key_of<F: 'static>(_: F) -> Option<PathKey> {
+    ///     PathKey::new::<()>().with_fn::<F>()
+    /// }
+    ///
+    /// let offset = 1;
+    ///
+    /// assert!(key_of(|value: u32| value + 1).is_some());
+    /// assert!(key_of(move |value: u32| value + offset).is_none());
+    /// assert!(key_of(u32::count_ones as fn(u32) -> u32).is_none());
+    /// ```
+    pub fn with_fn<F: 'static>(self) -> Option<Self> {
+        (std::mem::size_of::<F>() == 0).then(|| self.with(TypeId::of::<F>()))
+    }
+}
@@ -0,0 +1,45 @@
+//! Module providing the autosave hook of the [`Context`](crate::Context). See
+//! [`Context::on_apply_persist`](crate::Context::on_apply_persist).
+
+use std::time::{Duration, Instant};
+
+/// Calls a persist function once changes settled.
+pub(crate) struct Autosave<State> {
+    persist: Box<dyn FnMut(&State)>,
+    debounce: Duration,
+    last_change: Option<Instant>,
+}
+
+impl<State> Autosave<State> {
+    pub(crate) fn new(debounce: Duration, persist: impl FnMut(&State) + 'static) -> Self {
+        Self {
+            persist: Box::new(persist),
+            debounce,
+            last_change: None,
+        }
+    }
+
+    /// Called after every apply. Persists the state if there are unsaved
+    /// changes and no changes were made for the debounce duration.
+    pub(crate) fn after_apply(&mut self, state: &State, has_changes: bool) {
+        let now = Instant::now();
+
+        if has_changes {
+            self.last_change = Some(now);
+        }
+
+        if self
+            .last_change
+            .is_some_and(|last_change| now.duration_since(last_change) >= self.debounce)
+        {
+            self.flush(state);
+        }
+    }
+
+    /// Persists the state if there are unsaved changes.
+    pub(crate) fn flush(&mut self, state: &State) {
+        if self.last_change.take().is_some() {
+            (self.persist)(state);
+        }
+    }
+}
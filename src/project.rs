@@ -0,0 +1,118 @@
+//! Module providing an extension trait to project the target of a path with a
+//! function.
+//!
+//! This covers small read-only projections, like borrowing a [`String`] as a
+//! [`str`], without writing an adapter.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, ProjectExt, RustState};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     name: String,
+//!     scores: Vec<u32>,
+//! }
+//!
+//! let context = Context::new(State {
+//!     name: "Alice".to_owned(),
+//!     scores: vec![10, 20],
+//! });
+//!
+//! let name_path = State::path().name().project(String::as_str);
+//! let scores_path = State::path().scores().project(|scores: &Vec<u32>| scores.as_slice());
+//!
+//! assert_eq!(context.get(&name_path), "Alice");
+//! assert_eq!(context.get(&scores_path), &[10, 20]);
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{PathKey, PathRead, Selector};
+
+/// A read-only path that projects the target of another path.
+///
+/// This type is not accessible outside this module, instead [`ProjectExt`]
+/// can be used to construct it and receive an `impl PathRead<State, To>`.
+struct Projection<State, InnerPath, From: ?Sized, To: ?Sized, const SAFE: bool> {
+    path: InnerPath,
+    project: fn(&From) -> &To,
+    _marker: PhantomData<State>,
+}
+
+impl<State, InnerPath, From: ?Sized, To: ?Sized, const SAFE: bool> Clone for Projection<State, InnerPath, From, To, SAFE>
+where
+    InnerPath: PathRead<State, From, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            project: self.project,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, InnerPath, From: ?Sized, To: ?Sized, const SAFE: bool> Copy for Projection<State, InnerPath, From, To, SAFE> where
+    InnerPath: PathRead<State, From, SAFE> + Copy
+{
+}
+
+impl<State, InnerPath, From, To, const SAFE: bool> Selector<State, To, SAFE> for Projection<State, InnerPath, From, To, SAFE>
+where
+    State: 'static,
+    InnerPath: PathRead<State, From, SAFE>,
+    From: ?Sized + 'static,
+    To: ?Sized + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a To> {
+        self.follow(state)
+    }
+}
+
+impl<State, InnerPath, From, To, const SAFE: bool> PathRead<State, To, SAFE> for Projection<State, InnerPath, From, To, SAFE>
+where
+    State: 'static,
+    InnerPath: PathRead<State, From, SAFE>,
+    From: ?Sized + 'static,
+    To: ?Sized + 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a To> {
+        self.path.follow(state).map(self.project)
+    }
+
+    /// The projection is a function pointer, which can't be identified, so
+    /// the path has no key.
+    fn key(&self) -> Option<PathKey> {
+        None
+    }
+}
+
+pub trait ProjectExt<State, From, const SAFE: bool>
+where
+    State: 'static,
+    Self: PathRead<State, From, SAFE> + Copy,
+    From: ?Sized + 'static,
+{
+    /// Project the target of this path with a function. The resulting path is
+    /// read-only.
+    fn project<To>(self, project: fn(&From) -> &To) -> impl PathRead<State, To, SAFE> + Copy
+    where
+        To: ?Sized + 'static,
+    {
+        Projection {
+            path: self,
+            project,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, From, const SAFE: bool> ProjectExt<State, From, SAFE> for T
+where
+    State: 'static,
+    T: PathRead<State, From, SAFE> + Copy,
+    From: ?Sized + 'static,
+{
+}
@@ -0,0 +1,167 @@
+//! Module providing the [`ReadOnlyContext`], a read-only view of the
+//! [`Context`](crate::Context).
+
+use std::borrow::Cow;
+
+/// A read-only view of the [`Context`](crate::Context).
+///
+/// Created using [`Context::freeze`](crate::Context::freeze). The view can
+/// only read the state, so code that receives it can't queue any changes.
+///
+/// Example:
+/// ```
+/// use rust_state::{Context, ReadOnlyContext, RustState};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct MyState {
+///     name: String,
+/// }
+///
+/// fn render(context: ReadOnlyContext<MyState>) -> String {
+///     format!("Hello, {}!", context.get(&MyState::path().name()))
+/// }
+///
+/// let context = Context::new(MyState { name: "World".to_owned() });
+///
+/// assert_eq!(render(context.freeze()), "Hello, World!");
+/// ```
+///
+/// Read-only paths, like the ones created by
+/// [`shared_deref`](crate::SharedExt::shared_deref), can be followed as well.
+///
+/// ```
+/// use std::rc::Rc;
+/// use rust_state::{Context, RustState, SharedExt};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct MyState {
+///     name: Rc<String>,
+/// }
+///
+/// let context = Context::new(MyState {
+///     name: Rc::new("World".to_owned()),
+/// });
+/// let view = context.freeze();
+///
+/// assert_eq!(view.follow(MyState::path().name().shared_deref()), "World");
+/// assert_eq!(view.try_get_any(MyState::path().name().shared_deref()).map(String::as_str), Some("World"));
+/// ```
+///
+/// Trying to queue a change through the view will fail to compile.
+///
+/// ```compile_fail
+/// use rust_state::{Context, ReadOnlyContext, RustState};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct MyState {
+///     name: String,
+/// }
+///
+/// fn render(context: ReadOnlyContext<MyState>) {
+///     context.update_value(MyState::path().name(), "Changed".to_owned());
+/// }
+/// ```
+pub struct ReadOnlyContext<'a, State> {
+    state: &'a State,
+}
+
+impl<State> Clone for ReadOnlyContext<'_, State> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<State> Copy for ReadOnlyContext<'_, State> {}
+
+impl<'a, State> ReadOnlyContext<'a, State> {
+    pub(crate) fn new(state: &'a State) -> Self {
+        Self { state }
+    }
+
+    /// See [`Context::get`](crate::Context::get).
+    pub fn get<'b, Selector, Output>(&'b self, selector: &'b Selector) -> &'b Output
+    where
+        Selector: crate::Selector<State, Output>,
+        Output: ?Sized,
+    {
+        selector.select(self.state).unwrap()
+    }
+
+    /// See [`Context::try_get`](crate::Context::try_get).
+    pub fn try_get<'b, Selector, Output>(&'b self, selector: &'b Selector) -> Option<&'b Output>
+    where
+        Selector: crate::Selector<State, Output, false>,
+        Output: ?Sized,
+    {
+        selector.select(self.state)
+    }
+
+    /// See [`Context::get_or`](crate::Context::get_or).
+    pub fn get_or<'b, Selector, Output>(&'b self, selector: &'b Selector, fallback: &'b Output) -> &'b Output
+    where
+        Selector: crate::Selector<State, Output, false>,
+        Output: ?Sized,
+    {
+        selector.select(self.state).unwrap_or(fallback)
+    }
+
+    /// See [`Context::get_or_default`](crate::Context::get_or_default).
+    pub fn get_or_default<'b, Selector, Output>(&'b self, selector: &'b Selector) -> Cow<'b, Output>
+    where
+        Selector: crate::Selector<State, Output, false>,
+        Output: Clone + Default,
+    {
+        match selector.select(self.state) {
+            Some(output) => Cow::Borrowed(output),
+            None => Cow::Owned(Output::default()),
+        }
+    }
+
+    /// See [`Context::exists`](crate::Context::exists).
+    pub fn exists<Path, Output>(&self, path: Path) -> bool
+    where
+        Path: crate::PathRead<State, Output, false>,
+        Output: ?Sized,
+    {
+        path.follow(self.state).is_some()
+    }
+
+    /// See [`Context::try_get_any`](crate::Context::try_get_any).
+    pub fn try_get_any<Selector, Output, const SAFE: bool>(&self, selector: Selector) -> Option<&'a Output>
+    where
+        Selector: crate::PathRead<State, Output, SAFE>,
+        Output: ?Sized,
+    {
+        selector.follow(self.state)
+    }
+
+    /// See [`Context::follow`](crate::Context::follow).
+    pub fn follow<Path, Output>(&self, path: Path) -> &'a Output
+    where
+        Path: crate::PathRead<State, Output>,
+        Output: ?Sized,
+    {
+        path.follow(self.state).unwrap()
+    }
+
+    /// See [`Context::try_follow`](crate::Context::try_follow).
+    pub fn try_follow<Path, Output>(&self, path: Path) -> Option<&'a Output>
+    where
+        Path: crate::PathRead<State, Output, false>,
+        Output: ?Sized,
+    {
+        path.follow(self.state)
+    }
+
+    /// See [`Context::try_follow_any`](crate::Context::try_follow_any).
+    pub fn try_follow_any<Path, Output, const SAFE: bool>(&self, path: Path) -> Option<&'a Output>
+    where
+        Path: crate::PathRead<State, Output, SAFE>,
+        Output: ?Sized,
+    {
+        path.follow(self.state)
+    }
+}
@@ -0,0 +1,135 @@
+//! Module providing [`Reflect`], runtime access to the fields of a value by
+//! name.
+//!
+//! Structs can implement `Reflect` with `#[derive(RustState)]` and
+//! `#[state(reflect)]`. It is implemented for primitives, [`String`], [`Vec`],
+//! arrays and maps with [`String`] keys. [`DynamicPath`](crate::DynamicPath)
+//! uses it to follow paths parsed at runtime.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Reflect, RustState};
+//!
+//! #[derive(RustState)]
+//! #[state(reflect)]
+//! struct Audio {
+//!     volume: u32,
+//! }
+//!
+//! let audio = Audio { volume: 80 };
+//! let volume = audio.field("volume").unwrap();
+//!
+//! assert_eq!(volume.as_any().downcast_ref::<u32>(), Some(&80));
+//! assert!(audio.field("pitch").is_none());
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasher;
+
+use crate::AsAny;
+
+/// A value whose fields and items can be looked up at runtime.
+///
+/// All methods return [`None`] by default, so leaf types only need an empty
+/// implementation.
+pub trait Reflect: AsAny {
+    /// Get a field by name. Fields of tuple structs are named by their index.
+    fn field(&self, _name: &str) -> Option<&dyn Reflect> {
+        None
+    }
+
+    /// Get a field by name mutably.
+    fn field_mut(&mut self, _name: &str) -> Option<&mut dyn Reflect> {
+        None
+    }
+
+    /// Get an item by index.
+    fn index(&self, _index: usize) -> Option<&dyn Reflect> {
+        None
+    }
+
+    /// Get an item by index mutably.
+    fn index_mut(&mut self, _index: usize) -> Option<&mut dyn Reflect> {
+        None
+    }
+}
+
+/// Conversion from a reflected value to a concrete type.
+///
+/// This is implemented for every type implementing [`Reflect`] by
+/// downcasting, and for `dyn Reflect` itself.
+pub trait FromReflect {
+    fn from_reflect(value: &dyn Reflect) -> Option<&Self>;
+
+    fn from_reflect_mut(value: &mut dyn Reflect) -> Option<&mut Self>;
+}
+
+impl FromReflect for dyn Reflect {
+    fn from_reflect(value: &dyn Reflect) -> Option<&Self> {
+        Some(value)
+    }
+
+    fn from_reflect_mut(value: &mut dyn Reflect) -> Option<&mut Self> {
+        Some(value)
+    }
+}
+
+impl<T: Reflect> FromReflect for T {
+    fn from_reflect(value: &dyn Reflect) -> Option<&Self> {
+        value.as_any().downcast_ref()
+    }
+
+    fn from_reflect_mut(value: &mut dyn Reflect) -> Option<&mut Self> {
+        value.as_any_mut().downcast_mut()
+    }
+}
+
+macro_rules! implement_leaf {
+    ($($value:ty),*) => {
+        $(impl Reflect for $value {})*
+    };
+}
+
+implement_leaf!(
+    bool, char, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, String
+);
+
+impl<T: Reflect> Reflect for Vec<T> {
+    fn index(&self, index: usize) -> Option<&dyn Reflect> {
+        self.get(index).map(|item| item as &dyn Reflect)
+    }
+
+    fn index_mut(&mut self, index: usize) -> Option<&mut dyn Reflect> {
+        self.get_mut(index).map(|item| item as &mut dyn Reflect)
+    }
+}
+
+impl<T: Reflect, const N: usize> Reflect for [T; N] {
+    fn index(&self, index: usize) -> Option<&dyn Reflect> {
+        self.get(index).map(|item| item as &dyn Reflect)
+    }
+
+    fn index_mut(&mut self, index: usize) -> Option<&mut dyn Reflect> {
+        self.get_mut(index).map(|item| item as &mut dyn Reflect)
+    }
+}
+
+impl<V: Reflect, S: BuildHasher + 'static> Reflect for HashMap<String, V, S> {
+    fn field(&self, name: &str) -> Option<&dyn Reflect> {
+        self.get(name).map(|value| value as &dyn Reflect)
+    }
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+        self.get_mut(name).map(|value| value as &mut dyn Reflect)
+    }
+}
+
+impl<V: Reflect> Reflect for BTreeMap<String, V> {
+    fn field(&self, name: &str) -> Option<&dyn Reflect> {
+        self.get(name).map(|value| value as &dyn Reflect)
+    }
+
+    fn field_mut(&mut self, name: &str) -> Option<&mut dyn Reflect> {
+        self.get_mut(name).map(|value| value as &mut dyn Reflect)
+    }
+}
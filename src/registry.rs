@@ -0,0 +1,167 @@
+//! Module providing [`PathRegistry`], a place to register commonly used paths
+//! once and look them up from anywhere.
+//!
+//! Every [`Context`](crate::Context) has a registry, accessible with
+//! [`Context::path_registry`](crate::Context::path_registry), so paths don't
+//! have to be passed through every layer that needs them.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use crate::{BoxedPath, Path, PathKey};
+
+/// Paths registered by name and by [`PathKey`].
+///
+/// Registered paths are stored as [`BoxedPath`]s under their name. Paths with
+/// a [`key`](crate::PathRead::key) are also indexed by it, so they can be
+/// looked up with the key of an equal path. If multiple names are registered
+/// with paths of the same key, the last registered path is returned. Looking up
+/// a path with a different target type or safety than it was registered with
+/// returns [`None`].
+///
+/// Example:
+/// ```
+/// use rust_state::{BoxedPath, Context, PathRead, RustState};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct State {
+///     volume: u32,
+/// }
+///
+/// let mut context = Context::new(State { volume: 50 });
+///
+/// let volume_path = State::path().volume();
+/// context.path_registry_mut().register("volume", volume_path);
+///
+/// // Somewhere else, without access to `volume_path`.
+/// let registry = context.path_registry();
+/// let by_name: BoxedPath<State, u32> = registry.get("volume").unwrap();
+/// let by_key: BoxedPath<State, u32> = registry.get_by_key(volume_path.key().unwrap()).unwrap();
+///
+/// assert_eq!(context.get(&by_name), &50);
+/// assert_eq!(context.get(&by_key), &50);
+///
+/// // The target type has to match.
+/// assert!(context.path_registry().get::<String, true>("volume").is_none());
+/// ```
+///
+/// Paths are stored by name, so paths of the same type are kept apart even if
+/// their keys don't cover all of their runtime data:
+/// ```
+/// use rust_state::{BoxedPath, Context, Path, RustState, VecIndexExt};
+///
+/// #[derive(RustState)]
+/// #[state_root]
+/// struct State {
+///     users: Vec<String>,
+/// }
+///
+/// fn by_name(name: &'static str) -> impl Path<State, String, false> {
+///     State::path().users().find_by(move |user: &String| user == name)
+/// }
+///
+/// let mut context = Context::new(State {
+///     users: vec!["alice".to_owned(), "bob".to_owned()],
+/// });
+///
+/// context.path_registry_mut().register("alice", by_name("alice"));
+/// context.path_registry_mut().register("bob", by_name("bob"));
+///
+/// let bob: BoxedPath<State, String, false> = context.path_registry().get("bob").unwrap();
+///
+/// assert_eq!(context.try_get(&bob).map(String::as_str), Some("bob"));
+/// ```
+pub struct PathRegistry<State> {
+    names: HashMap<String, Registered>,
+    keys: HashMap<PathKey, String>,
+    _marker: PhantomData<State>,
+}
+
+/// A path registered under a name.
+struct Registered {
+    path: Box<dyn Any>,
+    key: Option<PathKey>,
+}
+
+impl<State> Default for PathRegistry<State> {
+    fn default() -> Self {
+        Self {
+            names: HashMap::new(),
+            keys: HashMap::new(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State: 'static> PathRegistry<State> {
+    /// Register a path under a name, replacing any path previously registered
+    /// under the same name.
+    pub fn register<To, const SAFE: bool>(
+        &mut self,
+        name: impl Into<String>,
+        path: impl Path<State, To, SAFE>,
+    ) -> BoxedPath<State, To, SAFE>
+    where
+        To: ?Sized + 'static,
+    {
+        let name = name.into();
+        let key = path.key();
+        let boxed = BoxedPath::new(path);
+
+        self.unregister(&name);
+
+        if let Some(key) = key {
+            self.keys.insert(key, name.clone());
+        }
+
+        self.names.insert(name, Registered {
+            path: Box::new(boxed.clone()),
+            key,
+        });
+
+        boxed
+    }
+
+    /// Remove the path registered under a name. Returns `false` if there is
+    /// no path with that name.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        let Some(registered) = self.names.remove(name) else {
+            return false;
+        };
+
+        // Point the key to another path with the same key, if there is one.
+        if let Some(key) = registered.key {
+            if self.keys.get(&key).is_some_and(|registered_name| registered_name == name) {
+                match self.names.iter().find(|(_, other)| other.key == Some(key)) {
+                    Some((other_name, _)) => self.keys.insert(key, other_name.clone()),
+                    None => self.keys.remove(&key),
+                };
+            }
+        }
+
+        true
+    }
+
+    /// Get the path registered under a name.
+    pub fn get<To, const SAFE: bool>(&self, name: &str) -> Option<BoxedPath<State, To, SAFE>>
+    where
+        To: ?Sized + 'static,
+    {
+        self.names.get(name)?.path.downcast_ref().cloned()
+    }
+
+    /// Get a registered path by its [`PathKey`].
+    pub fn get_by_key<To, const SAFE: bool>(&self, key: PathKey) -> Option<BoxedPath<State, To, SAFE>>
+    where
+        To: ?Sized + 'static,
+    {
+        self.get(self.keys.get(&key)?)
+    }
+
+    /// Check if a path is registered under a name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.names.contains_key(name)
+    }
+}
@@ -0,0 +1,87 @@
+//! Module providing the [`ApplyReport`], which summarizes a call to
+//! [`Context::apply`](crate::Context::apply).
+
+use std::error::Error;
+
+use crate::change_set::{Change, ChangeSet};
+
+/// Summary of a call to [`Context::apply`](crate::Context::apply).
+///
+/// Errors returned by fallible changes, like the ones queued with
+/// [`Context::update_value_try`](crate::Context::update_value_try), are
+/// collected here in the order they occurred.
+#[derive(Debug, Default)]
+pub struct ApplyReport {
+    applied_changes: usize,
+    remaining_changes: usize,
+    errors: Vec<Box<dyn Error>>,
+    change_set: ChangeSet,
+}
+
+impl ApplyReport {
+    pub(crate) fn record_change(&mut self, result: Result<(), Box<dyn Error>>, changes: Vec<Change>) {
+        self.applied_changes += 1;
+
+        match result {
+            Ok(()) => changes.into_iter().for_each(|change| self.change_set.push(change)),
+            Err(error) => self.errors.push(error),
+        }
+    }
+
+    /// Report an error that doesn't belong to a single change.
+    pub(crate) fn push_error(&mut self, error: Box<dyn Error>) {
+        self.errors.push(error);
+    }
+
+    pub(crate) fn merge(&mut self, other: ApplyReport) {
+        self.applied_changes += other.applied_changes;
+        self.errors.extend(other.errors);
+        self.change_set.extend(other.change_set);
+    }
+
+    pub(crate) fn set_remaining_changes(&mut self, remaining_changes: usize) {
+        self.remaining_changes = remaining_changes;
+    }
+
+    /// Get the number of changes that were applied, including the ones that
+    /// returned an error.
+    pub fn applied_changes(&self) -> usize {
+        self.applied_changes
+    }
+
+    /// Check if any changes were applied.
+    pub fn has_changes(&self) -> bool {
+        self.applied_changes > 0
+    }
+
+    /// Get the number of changes that are still pending after the call, for
+    /// example because the budget of
+    /// [`Context::apply_budgeted`](crate::Context::apply_budgeted) was used
+    /// up or changes kept queueing new ones for more than the maximum number
+    /// of apply rounds.
+    pub fn remaining_changes(&self) -> usize {
+        self.remaining_changes
+    }
+
+    /// Get all errors returned by fallible changes.
+    pub fn errors(&self) -> &[Box<dyn Error>] {
+        &self.errors
+    }
+
+    /// Check if any fallible change returned an error.
+    pub fn has_errors(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// Get the [`ChangeSet`] describing the applied changes. Empty unless
+    /// enabled with
+    /// [`Context::set_record_changes`](crate::Context::set_record_changes).
+    pub fn change_set(&self) -> &ChangeSet {
+        &self.change_set
+    }
+
+    /// Take all errors returned by fallible changes.
+    pub fn into_errors(self) -> Vec<Box<dyn Error>> {
+        self.errors
+    }
+}
@@ -0,0 +1,197 @@
+//! Module providing an extension trait to unwrap a [`Result`] path in the
+//! state.
+//!
+//! This allows treating `Result<T, E>` fields as if they are either `T` or
+//! `E` in a path. If the value is the other variant, the path returns `None`.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, ResultExt, RustState};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     loaded: Result<u32, String>,
+//! }
+//!
+//! let mut context = Context::new(State {
+//!     loaded: Err("File not found".to_owned()),
+//! });
+//!
+//! let value_path = State::path().loaded().ok_path();
+//! let error_path = State::path().loaded().err_path();
+//!
+//! assert_eq!(context.try_get(&value_path), None);
+//! assert_eq!(context.try_get(&error_path).map(String::as_str), Some("File not found"));
+//!
+//! context.update_value(State::path().loaded(), Ok(10));
+//! context.apply();
+//!
+//! assert_eq!(context.try_get(&value_path), Some(&10));
+//! assert_eq!(context.try_get(&error_path), None);
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// A path that unwraps the [`Ok`] variant of a [`Result`] in the state.
+///
+/// This type is not accessible outside this module. Instead, [`ResultExt`] is
+/// used to construct it and receive an `impl Path<State, T>`.
+struct ResultOk<State, ResultPath, Value, Error, const SAFE: bool> {
+    result_path: ResultPath,
+    _marker: PhantomData<(State, Value, Error)>,
+}
+
+/// A path that unwraps the [`Err`] variant of a [`Result`] in the state.
+///
+/// This type is not accessible outside this module. Instead, [`ResultExt`] is
+/// used to construct it and receive an `impl Path<State, E>`.
+struct ResultErr<State, ResultPath, Value, Error, const SAFE: bool> {
+    result_path: ResultPath,
+    _marker: PhantomData<(State, Value, Error)>,
+}
+
+macro_rules! implement_copy {
+    ($name:ident) => {
+        impl<State, ResultPath, Value, Error, const SAFE: bool> Clone for $name<State, ResultPath, Value, Error, SAFE>
+        where
+            ResultPath: Path<State, Result<Value, Error>, SAFE>,
+        {
+            fn clone(&self) -> Self {
+                Self {
+                    result_path: self.result_path.clone(),
+                    _marker: PhantomData,
+                }
+            }
+        }
+
+        impl<State, ResultPath, Value, Error, const SAFE: bool> Copy for $name<State, ResultPath, Value, Error, SAFE> where
+            ResultPath: Path<State, Result<Value, Error>, SAFE> + Copy
+        {
+        }
+    };
+}
+
+implement_copy!(ResultOk);
+implement_copy!(ResultErr);
+
+impl<State, ResultPath, Value, Error, const SAFE: bool> Selector<State, Value, false> for ResultOk<State, ResultPath, Value, Error, SAFE>
+where
+    State: 'static,
+    ResultPath: Path<State, Result<Value, Error>, SAFE>,
+    Value: 'static,
+    Error: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Value> {
+        self.follow(state)
+    }
+}
+
+impl<State, ResultPath, Value, Error, const SAFE: bool> PathRead<State, Value, false> for ResultOk<State, ResultPath, Value, Error, SAFE>
+where
+    State: 'static,
+    ResultPath: Path<State, Result<Value, Error>, SAFE>,
+    Value: 'static,
+    Error: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Value> {
+        self.result_path.follow(state)?.as_ref().ok()
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.result_path.key()?))
+    }
+}
+
+impl<State, ResultPath, Value, Error, const SAFE: bool> PathWrite<State, Value, false> for ResultOk<State, ResultPath, Value, Error, SAFE>
+where
+    State: 'static,
+    ResultPath: Path<State, Result<Value, Error>, SAFE>,
+    Value: 'static,
+    Error: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Value> {
+        self.result_path.follow_mut(state)?.as_mut().ok()
+    }
+}
+
+impl<State, ResultPath, Value, Error, const SAFE: bool> Selector<State, Error, false> for ResultErr<State, ResultPath, Value, Error, SAFE>
+where
+    State: 'static,
+    ResultPath: Path<State, Result<Value, Error>, SAFE>,
+    Value: 'static,
+    Error: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Error> {
+        self.follow(state)
+    }
+}
+
+impl<State, ResultPath, Value, Error, const SAFE: bool> PathRead<State, Error, false> for ResultErr<State, ResultPath, Value, Error, SAFE>
+where
+    State: 'static,
+    ResultPath: Path<State, Result<Value, Error>, SAFE>,
+    Value: 'static,
+    Error: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Error> {
+        self.result_path.follow(state)?.as_ref().err()
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.result_path.key()?))
+    }
+}
+
+impl<State, ResultPath, Value, Error, const SAFE: bool> PathWrite<State, Error, false> for ResultErr<State, ResultPath, Value, Error, SAFE>
+where
+    State: 'static,
+    ResultPath: Path<State, Result<Value, Error>, SAFE>,
+    Value: 'static,
+    Error: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Error> {
+        self.result_path.follow_mut(state)?.as_mut().err()
+    }
+}
+
+pub trait ResultExt<State, Value, Error, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Result<Value, Error>, SAFE> + Copy,
+    Value: 'static,
+    Error: 'static,
+{
+    /// Unwrap the [`Ok`] variant of the [`Result`] in this path.
+    ///
+    /// This path is *not* safe. It returns `None` at runtime if the
+    /// underlying [`Result`] is an [`Err`].
+    fn ok_path(self) -> impl Path<State, Value, false> + Copy {
+        ResultOk {
+            result_path: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Unwrap the [`Err`] variant of the [`Result`] in this path.
+    ///
+    /// This path is *not* safe. It returns `None` at runtime if the
+    /// underlying [`Result`] is [`Ok`].
+    fn err_path(self) -> impl Path<State, Error, false> + Copy {
+        ResultErr {
+            result_path: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Value, Error, const SAFE: bool> ResultExt<State, Value, Error, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Result<Value, Error>, SAFE> + Copy,
+    Value: 'static,
+    Error: 'static,
+{
+}
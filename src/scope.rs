@@ -0,0 +1,634 @@
+//! Module providing the [`ScopedContext`], a view of the [`Context`] rooted at
+//! a sub-path of the state.
+//!
+//! A scoped context can only read and change the part of the state it is
+//! rooted at, which makes it possible to hand a subsystem access to its own
+//! state without exposing the whole state tree.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, RustState, ScopedContext};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     audio: AudioState,
+//! }
+//!
+//! #[derive(RustState)]
+//! struct AudioState {
+//!     volume: f32,
+//!     muted: bool,
+//! }
+//!
+//! fn mute(audio: &ScopedContext<AudioState>) {
+//!     if *audio.get(&audio.path().volume()) > 0.0 {
+//!         audio.update_value(audio.path().muted(), true);
+//!     }
+//! }
+//!
+//! let mut context = Context::new(State {
+//!     audio: AudioState { volume: 0.5, muted: false },
+//! });
+//!
+//! mute(&context.scope(State::path().audio()));
+//! context.apply();
+//!
+//! assert_eq!(context.get(&State::path().audio().muted()), &true);
+//! ```
+
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::hash::Hash;
+use std::ops::Range;
+
+#[cfg(feature = "slotmap")]
+use slotmap::{Key, SlotMap};
+
+use crate::change::{self, Callbacks, ChangeResult, PathChange, RecordedChange};
+use crate::generated::RootPath;
+#[cfg(feature = "sync")]
+use crate::lock::Lock;
+use crate::{Cached, Extensions, Flags, MapItem, VecItem, Versioned};
+
+/// A view of the [`Context`](crate::Context) rooted at a sub-path of the
+/// state.
+///
+/// Created using [`Context::scope`](crate::Context::scope) or
+/// [`Context::try_scope`](crate::Context::try_scope). All paths used with a
+/// scoped context start at `Sub`, use [`path`](Self::path) to get the root
+/// path of the scope. Changes are queued on the underlying context.
+pub struct ScopedContext<'a, Sub> {
+    sub: &'a Sub,
+    queue: Box<dyn Fn(RecordedChange<Sub>) + 'a>,
+}
+
+impl<'a, Sub> ScopedContext<'a, Sub>
+where
+    Sub: 'static,
+{
+    /// Create a new scoped context.
+    ///
+    /// `sub` is resolved once, since the state can't change while the context
+    /// is borrowed.
+    pub(crate) fn new(sub: &'a Sub, queue: impl Fn(RecordedChange<Sub>) + 'a) -> Self {
+        Self {
+            sub,
+            queue: Box::new(queue),
+        }
+    }
+
+    fn queue<F>(&self, change: PathChange<F>)
+    where
+        F: FnOnce(&mut Sub, &mut Callbacks) -> ChangeResult + 'static,
+    {
+        (self.queue)(change.boxed());
+    }
+
+    /// Get the root path of the scope.
+    pub fn path(&self) -> impl crate::Path<Sub, Sub> + Copy {
+        RootPath::new()
+    }
+
+    /// Create a new scoped context rooted at a sub-path of this scope.
+    pub fn scope<Path, Inner>(&self, path: Path) -> ScopedContext<'_, Inner>
+    where
+        Path: crate::Path<Sub, Inner>,
+        Inner: 'static,
+    {
+        self.try_scope_any(path).unwrap()
+    }
+
+    /// Try to create a new scoped context rooted at an unsafe sub-path of this
+    /// scope. Returns `None` if the path doesn't resolve.
+    pub fn try_scope<Path, Inner>(&self, path: Path) -> Option<ScopedContext<'_, Inner>>
+    where
+        Path: crate::Path<Sub, Inner, false>,
+        Inner: 'static,
+    {
+        self.try_scope_any(path)
+    }
+
+    fn try_scope_any<Path, Inner, const SAFE: bool>(&self, path: Path) -> Option<ScopedContext<'_, Inner>>
+    where
+        Path: crate::Path<Sub, Inner, SAFE>,
+        Inner: 'static,
+    {
+        let inner = path.follow(self.sub)?;
+
+        Some(ScopedContext::new(inner, move |change: RecordedChange<Inner>| {
+            (self.queue)(change.scoped(path.clone()));
+        }))
+    }
+
+    /// See [`Context::get`](crate::Context::get).
+    pub fn get<'b, Selector, Output>(&'b self, selector: &'b Selector) -> &'b Output
+    where
+        Selector: crate::Selector<Sub, Output>,
+        Output: ?Sized,
+    {
+        selector.select(self.sub).unwrap()
+    }
+
+    /// See [`Context::try_get`](crate::Context::try_get).
+    pub fn try_get<'b, Selector, Output>(&'b self, selector: &'b Selector) -> Option<&'b Output>
+    where
+        Selector: crate::Selector<Sub, Output, false>,
+        Output: ?Sized,
+    {
+        selector.select(self.sub)
+    }
+
+    /// See [`Context::follow`](crate::Context::follow).
+    pub fn follow<Path, Output>(&self, path: Path) -> &Output
+    where
+        Path: crate::Path<Sub, Output>,
+        Output: ?Sized,
+    {
+        path.follow(self.sub).unwrap()
+    }
+
+    /// See [`Context::try_follow`](crate::Context::try_follow).
+    pub fn try_follow<Path, Output>(&self, path: Path) -> Option<&Output>
+    where
+        Path: crate::Path<Sub, Output, false>,
+        Output: ?Sized,
+    {
+        path.follow(self.sub)
+    }
+
+    /// See [`Context::update_value`](crate::Context::update_value).
+    pub fn update_value<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<Sub, Value, SAFE>,
+        Value: 'static,
+    {
+        self.queue(change::update_value(path, value));
+    }
+
+    /// See [`Context::update_value_with`](crate::Context::update_value_with).
+    pub fn update_value_with<Path, Value, F, const SAFE: bool>(&self, path: Path, closure: F)
+    where
+        Path: crate::Path<Sub, Value, SAFE>,
+        Value: 'static,
+        F: Fn(&mut Value) + 'static,
+    {
+        self.queue(change::update_value_with(path, closure));
+    }
+
+    /// See [`Context::update_value_try`](crate::Context::update_value_try).
+    pub fn update_value_try<Path, Value, F, E, const SAFE: bool>(&self, path: Path, closure: F)
+    where
+        Path: crate::Path<Sub, Value, SAFE>,
+        Value: 'static,
+        F: FnOnce(&mut Value) -> Result<(), E> + 'static,
+        E: Error + 'static,
+    {
+        self.queue(change::update_value_try(path, closure));
+    }
+
+    /// Update the entire scoped state with a closure. See
+    /// [`Context::update_root`](crate::Context::update_root).
+    pub fn update_root<F>(&self, closure: F)
+    where
+        F: FnOnce(&mut Sub) + 'static,
+    {
+        self.queue(change::update_root(closure));
+    }
+
+    /// See [`Context::update_cell`](crate::Context::update_cell).
+    pub fn update_cell<Path, Value, F, const SAFE: bool>(&self, path: Path, closure: F)
+    where
+        Path: crate::Path<Sub, RefCell<Value>, SAFE>,
+        Value: 'static,
+        F: FnOnce(&mut Value) + 'static,
+    {
+        self.queue(change::update_cell(path, closure));
+    }
+
+    /// See [`Context::update_locked`](crate::Context::update_locked).
+    #[cfg(feature = "sync")]
+    pub fn update_locked<Path, Value, F, const SAFE: bool>(&self, path: Path, closure: F)
+    where
+        Path: crate::Path<Sub, Value, SAFE>,
+        Value: Lock + 'static,
+        F: FnOnce(&mut Value::Target) + 'static,
+    {
+        self.queue(change::update_locked(path, closure));
+    }
+
+    /// See [`Context::update_each`](crate::Context::update_each).
+    pub fn update_each<Traversal, Value, F>(&self, traversal: Traversal, closure: F)
+    where
+        Traversal: crate::Traversal<Sub, Value>,
+        Value: ?Sized + 'static,
+        F: FnMut(&mut Value) + 'static,
+    {
+        self.queue(change::update_each(traversal, closure));
+    }
+
+    /// See [`Context::update_each_indexed`](crate::Context::update_each_indexed).
+    pub fn update_each_indexed<Traversal, Key, Value, F>(&self, traversal: Traversal, closure: F)
+    where
+        Traversal: crate::IndexedTraversal<Sub, Key, Value>,
+        Key: 'static,
+        Value: ?Sized + 'static,
+        F: FnMut(&Key, &mut Value) + 'static,
+    {
+        self.queue(change::update_each_indexed(traversal, closure));
+    }
+
+    /// See [`Context::take`](crate::Context::take).
+    pub fn take<Path, Value, F, const SAFE: bool>(&self, path: Path, callback: F)
+    where
+        Path: crate::Path<Sub, Value, SAFE>,
+        Value: Default + 'static,
+        F: FnOnce(Value) + 'static,
+    {
+        self.queue(change::take(path, callback));
+    }
+
+    /// See [`Context::replace`](crate::Context::replace).
+    pub fn replace<Path, Value, F, const SAFE: bool>(&self, path: Path, value: Value, callback: F)
+    where
+        Path: crate::Path<Sub, Value, SAFE>,
+        Value: 'static,
+        F: FnOnce(Value) + 'static,
+    {
+        self.queue(change::replace(path, value, callback));
+    }
+
+    /// See [`Context::refresh_cache`](crate::Context::refresh_cache).
+    pub fn refresh_cache<CachePath, SourcePath, Value, Source, F, const CACHE_SAFE: bool, const SOURCE_SAFE: bool>(
+        &self,
+        cache_path: CachePath,
+        source_path: SourcePath,
+        compute: F,
+    ) where
+        CachePath: crate::Path<Sub, Cached<Value>, CACHE_SAFE>,
+        SourcePath: crate::Path<Sub, Versioned<Source>, SOURCE_SAFE>,
+        Value: 'static,
+        Source: 'static,
+        F: FnOnce(&Source) -> Value + 'static,
+    {
+        self.queue(change::refresh_cache(cache_path, source_path, compute));
+    }
+
+    /// See [`Context::vec_push`](crate::Context::vec_push).
+    pub fn vec_push<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<Sub, Vec<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.queue(change::vec_push(path, value));
+    }
+
+    /// See [`Context::vec_remove`](crate::Context::vec_remove).
+    pub fn vec_remove<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    where
+        Path: crate::Path<Sub, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.queue(change::vec_remove(path, id));
+    }
+
+    /// See [`Context::vec_remove_with`](crate::Context::vec_remove_with).
+    pub fn vec_remove_with<Path, Value, F, const SAFE: bool>(&self, path: Path, id: Value::Id, callback: F)
+    where
+        Path: crate::Path<Sub, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+        F: FnOnce(Vec<Value>) + 'static,
+    {
+        self.queue(change::vec_remove_with(path, id, callback));
+    }
+
+    /// See [`Context::vec_clear`](crate::Context::vec_clear).
+    pub fn vec_clear<Path, Value, const SAFE: bool>(&self, path: Path)
+    where
+        Path: crate::Path<Sub, Vec<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.queue(change::vec_clear(path));
+    }
+
+    /// See [`Context::vec_sort_by`](crate::Context::vec_sort_by).
+    pub fn vec_sort_by<Path, Value, F, const SAFE: bool>(&self, path: Path, comparator: F)
+    where
+        Path: crate::Path<Sub, Vec<Value>, SAFE>,
+        Value: 'static,
+        F: FnMut(&Value, &Value) -> Ordering + 'static,
+    {
+        self.queue(change::vec_sort_by(path, comparator));
+    }
+
+    /// See [`Context::vec_extend`](crate::Context::vec_extend).
+    pub fn vec_extend<Path, Value, Items, const SAFE: bool>(&self, path: Path, items: Items)
+    where
+        Path: crate::Path<Sub, Vec<Value>, SAFE>,
+        Value: 'static,
+        Items: IntoIterator<Item = Value> + 'static,
+    {
+        self.queue(change::vec_extend(path, items));
+    }
+
+    /// See [`Context::vec_retain`](crate::Context::vec_retain).
+    pub fn vec_retain<Path, Value, F, const SAFE: bool>(&self, path: Path, predicate: F)
+    where
+        Path: crate::Path<Sub, Vec<Value>, SAFE>,
+        Value: 'static,
+        F: FnMut(&Value) -> bool + 'static,
+    {
+        self.queue(change::vec_retain(path, predicate));
+    }
+
+    /// See [`Context::vec_swap_remove`](crate::Context::vec_swap_remove).
+    pub fn vec_swap_remove<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    where
+        Path: crate::Path<Sub, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.queue(change::vec_swap_remove(path, id));
+    }
+
+    /// See [`Context::vec_move`](crate::Context::vec_move).
+    pub fn vec_move<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id, new_index: usize)
+    where
+        Path: crate::Path<Sub, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.queue(change::vec_move(path, id, new_index));
+    }
+
+    /// See [`Context::vec_dedup`](crate::Context::vec_dedup).
+    pub fn vec_dedup<Path, Value, const SAFE: bool>(&self, path: Path)
+    where
+        Path: crate::Path<Sub, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.queue(change::vec_dedup(path));
+    }
+
+    /// See [`Context::vec_truncate`](crate::Context::vec_truncate).
+    pub fn vec_truncate<Path, Value, const SAFE: bool>(&self, path: Path, len: usize)
+    where
+        Path: crate::Path<Sub, Vec<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.queue(change::vec_truncate(path, len));
+    }
+
+    /// See [`Context::vec_pop`](crate::Context::vec_pop).
+    pub fn vec_pop<Path, Value, F, const SAFE: bool>(&self, path: Path, callback: F)
+    where
+        Path: crate::Path<Sub, Vec<Value>, SAFE>,
+        Value: 'static,
+        F: FnOnce(Option<Value>) + 'static,
+    {
+        self.queue(change::vec_pop(path, callback));
+    }
+
+    /// See [`Context::vec_push_unique`](crate::Context::vec_push_unique).
+    pub fn vec_push_unique<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<Sub, Vec<Value>, SAFE>,
+        Value: VecItem + 'static,
+    {
+        self.queue(change::vec_push_unique(path, value));
+    }
+
+    /// See [`Context::string_push_str`](crate::Context::string_push_str).
+    pub fn string_push_str<Path, const SAFE: bool>(&self, path: Path, string: impl Into<String>)
+    where
+        Path: crate::Path<Sub, String, SAFE>,
+    {
+        self.queue(change::string_push_str(path, string.into()));
+    }
+
+    /// See [`Context::string_clear`](crate::Context::string_clear).
+    pub fn string_clear<Path, const SAFE: bool>(&self, path: Path)
+    where
+        Path: crate::Path<Sub, String, SAFE>,
+    {
+        self.queue(change::string_clear(path));
+    }
+
+    /// See [`Context::string_replace_range`](crate::Context::string_replace_range).
+    pub fn string_replace_range<Path, const SAFE: bool>(&self, path: Path, range: Range<usize>, replacement: impl Into<String>)
+    where
+        Path: crate::Path<Sub, String, SAFE>,
+    {
+        self.queue(change::string_replace_range(path, range, replacement.into()));
+    }
+
+    /// See [`Context::flag_set`](crate::Context::flag_set).
+    pub fn flag_set<Path, Value, const SAFE: bool>(&self, path: Path, flag: Value, value: bool)
+    where
+        Path: crate::Path<Sub, Value, SAFE>,
+        Value: Flags,
+    {
+        self.queue(change::flag_set(path, flag, value));
+    }
+
+    /// See [`Context::flag_toggle`](crate::Context::flag_toggle).
+    pub fn flag_toggle<Path, Value, const SAFE: bool>(&self, path: Path, flag: Value)
+    where
+        Path: crate::Path<Sub, Value, SAFE>,
+        Value: Flags,
+    {
+        self.queue(change::flag_toggle(path, flag));
+    }
+
+    /// See [`Context::map_insert`](crate::Context::map_insert).
+    pub fn map_insert<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id, value: Value)
+    where
+        Path: crate::Path<Sub, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+    {
+        self.queue(change::map_insert(path, id, value));
+    }
+
+    /// See [`Context::map_insert_default`](crate::Context::map_insert_default).
+    pub fn map_insert_default<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    where
+        Path: crate::Path<Sub, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + Default + 'static,
+    {
+        self.queue(change::map_insert_default(path, id));
+    }
+
+    /// See [`Context::map_replace`](crate::Context::map_replace).
+    pub fn map_replace<Path, Value, F, const SAFE: bool>(&self, path: Path, id: Value::Id, value: Value, callback: F)
+    where
+        Path: crate::Path<Sub, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnOnce(Option<Value>) + 'static,
+    {
+        self.queue(change::map_replace(path, id, value, callback));
+    }
+
+    /// See [`Context::map_remove`](crate::Context::map_remove).
+    pub fn map_remove<Path, Value, const SAFE: bool>(&self, path: Path, id: Value::Id)
+    where
+        Path: crate::Path<Sub, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+    {
+        self.queue(change::map_remove(path, id));
+    }
+
+    /// See [`Context::map_clear`](crate::Context::map_clear).
+    pub fn map_clear<Path, Value, const SAFE: bool>(&self, path: Path)
+    where
+        Path: crate::Path<Sub, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+    {
+        self.queue(change::map_clear(path));
+    }
+
+    /// See [`Context::map_retain`](crate::Context::map_retain).
+    pub fn map_retain<Path, Value, F, const SAFE: bool>(&self, path: Path, predicate: F)
+    where
+        Path: crate::Path<Sub, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnMut(&Value::Id, &mut Value) -> bool + 'static,
+    {
+        self.queue(change::map_retain(path, predicate));
+    }
+
+    /// See [`Context::map_get_or_insert_with`](crate::Context::map_get_or_insert_with).
+    pub fn map_get_or_insert_with<Path, Value, F, const SAFE: bool>(&self, path: Path, id: Value::Id, create: F)
+    where
+        Path: crate::Path<Sub, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnOnce() -> Value + 'static,
+    {
+        self.queue(change::map_get_or_insert_with(path, id, create));
+    }
+
+    /// See [`Context::map_update`](crate::Context::map_update).
+    pub fn map_update<Path, Value, F, const SAFE: bool>(&self, path: Path, id: Value::Id, closure: F)
+    where
+        Path: crate::Path<Sub, HashMap<Value::Id, Value>, SAFE>,
+        Value: MapItem + 'static,
+        F: FnOnce(&mut Value) + 'static,
+    {
+        self.queue(change::map_update(path, id, closure));
+    }
+
+    /// See [`Context::btree_insert`](crate::Context::btree_insert).
+    pub fn btree_insert<Path, Key, Value, const SAFE: bool>(&self, path: Path, key: Key, value: Value)
+    where
+        Path: crate::Path<Sub, BTreeMap<Key, Value>, SAFE>,
+        Key: Ord + 'static,
+        Value: 'static,
+    {
+        self.queue(change::btree_insert(path, key, value));
+    }
+
+    /// See [`Context::btree_remove`](crate::Context::btree_remove).
+    pub fn btree_remove<Path, Key, Value, const SAFE: bool>(&self, path: Path, key: Key)
+    where
+        Path: crate::Path<Sub, BTreeMap<Key, Value>, SAFE>,
+        Key: Ord + 'static,
+        Value: 'static,
+    {
+        self.queue(change::btree_remove(path, key));
+    }
+
+    /// See [`Context::set_insert`](crate::Context::set_insert).
+    pub fn set_insert<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<Sub, HashSet<Value>, SAFE>,
+        Value: Eq + Hash + 'static,
+    {
+        self.queue(change::set_insert(path, value));
+    }
+
+    /// See [`Context::set_remove`](crate::Context::set_remove).
+    pub fn set_remove<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<Sub, HashSet<Value>, SAFE>,
+        Value: Eq + Hash + 'static,
+    {
+        self.queue(change::set_remove(path, value));
+    }
+
+    /// See [`Context::set_clear`](crate::Context::set_clear).
+    pub fn set_clear<Path, Value, const SAFE: bool>(&self, path: Path)
+    where
+        Path: crate::Path<Sub, HashSet<Value>, SAFE>,
+        Value: 'static,
+    {
+        self.queue(change::set_clear(path));
+    }
+
+    /// See [`Context::slotmap_insert`](crate::Context::slotmap_insert).
+    #[cfg(feature = "slotmap")]
+    pub fn slotmap_insert<Path, K, Value, F, const SAFE: bool>(&self, path: Path, value: Value, callback: F)
+    where
+        Path: crate::Path<Sub, SlotMap<K, Value>, SAFE>,
+        K: Key + 'static,
+        Value: 'static,
+        F: FnOnce(K) + 'static,
+    {
+        self.queue(change::slotmap_insert(path, value, callback));
+    }
+
+    /// See [`Context::slotmap_remove`](crate::Context::slotmap_remove).
+    #[cfg(feature = "slotmap")]
+    pub fn slotmap_remove<Path, K, Value, const SAFE: bool>(&self, path: Path, key: K)
+    where
+        Path: crate::Path<Sub, SlotMap<K, Value>, SAFE>,
+        K: Key + 'static,
+        Value: 'static,
+    {
+        self.queue(change::slotmap_remove(path, key));
+    }
+
+    /// See [`Context::map_vec_push`](crate::Context::map_vec_push).
+    pub fn map_vec_push<Path, Key, Value, const SAFE: bool>(&self, path: Path, key: Key, value: Value)
+    where
+        Path: crate::Path<Sub, HashMap<Key, Vec<Value>>, SAFE>,
+        Key: Eq + Hash + 'static,
+        Value: 'static,
+    {
+        self.queue(change::map_vec_push(path, key, value));
+    }
+
+    /// See [`Context::btree_set_insert`](crate::Context::btree_set_insert).
+    pub fn btree_set_insert<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<Sub, BTreeSet<Value>, SAFE>,
+        Value: Ord + 'static,
+    {
+        self.queue(change::btree_set_insert(path, value));
+    }
+
+    /// See [`Context::btree_set_remove`](crate::Context::btree_set_remove).
+    pub fn btree_set_remove<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<Sub, BTreeSet<Value>, SAFE>,
+        Value: Ord + 'static,
+    {
+        self.queue(change::btree_set_remove(path, value));
+    }
+
+    /// See [`Context::insert_extension_at`](crate::Context::insert_extension_at).
+    pub fn insert_extension_at<Path, Value, const SAFE: bool>(&self, path: Path, value: Value)
+    where
+        Path: crate::Path<Sub, Extensions, SAFE>,
+        Value: 'static,
+    {
+        self.queue(change::insert_extension(path, value));
+    }
+
+    /// See [`Context::remove_extension_at`](crate::Context::remove_extension_at).
+    pub fn remove_extension_at<Value, Path, const SAFE: bool>(&self, path: Path)
+    where
+        Path: crate::Path<Sub, Extensions, SAFE>,
+        Value: 'static,
+    {
+        self.queue(change::remove_extension::<Sub, Value, Path, SAFE>(path));
+    }
+}
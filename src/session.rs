@@ -0,0 +1,42 @@
+//! Module providing [`Session`], a snapshot of the state and the undo history
+//! of a [`Context`](crate::Context). See
+//! [`Context::export_session`](crate::Context::export_session).
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The state of a [`Context`](crate::Context) together with its history of
+/// applied and reverted [`Command`](crate::Command)s.
+///
+/// A session is serialized as a tuple of the state, the commands that can be
+/// undone and the commands that can be redone. All commands have to be of
+/// the same type `C`, usually an enum of all commands of the application.
+pub struct Session<State, C> {
+    pub(crate) state: State,
+    pub(crate) undo_stack: Vec<C>,
+    pub(crate) redo_stack: Vec<C>,
+}
+
+impl<State, C> Session<State, C> {
+    /// Get the state of the session.
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+}
+
+impl<State: Serialize, C: Serialize> Serialize for Session<State, C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (&self.state, &self.undo_stack, &self.redo_stack).serialize(serializer)
+    }
+}
+
+impl<'de, State: Deserialize<'de>, C: Deserialize<'de>> Deserialize<'de> for Session<State, C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (state, undo_stack, redo_stack) = Deserialize::deserialize(deserializer)?;
+
+        Ok(Self {
+            state,
+            undo_stack,
+            redo_stack,
+        })
+    }
+}
@@ -0,0 +1,199 @@
+//! Module providing extension traits to query a [`HashSet`] or [`BTreeSet`] in
+//! the state.
+//!
+//! Example:
+//! ```
+//! use std::collections::HashSet;
+//! use rust_state::{Context, RustState, SetContainsExt};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     tags: HashSet<&'static str>,
+//! }
+//!
+//! let context = Context::new(State {
+//!     tags: HashSet::from(["important"]),
+//! });
+//!
+//! let important = State::path().tags().contains("important");
+//! let archived = State::path().tags().contains("archived");
+//!
+//! assert!(*context.get(&important));
+//! assert!(!*context.get(&archived));
+//! ```
+//!
+//! Since a [`BTreeSet`] is ordered, it additionally has selectors for its
+//! smallest and largest value.
+//!
+//! ```
+//! use std::collections::BTreeSet;
+//! use rust_state::{BTreeSetExt, Context, RustState};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     ids: BTreeSet<u32>,
+//! }
+//!
+//! let context = Context::new(State {
+//!     ids: BTreeSet::from([20, 10, 30]),
+//! });
+//!
+//! let contains = State::path().ids().contains(20);
+//! let min = State::path().ids().min();
+//! let max = State::path().ids().max();
+//!
+//! assert!(*context.get(&contains));
+//! assert_eq!(context.try_get(&min), Some(&10));
+//! assert_eq!(context.try_get(&max), Some(&30));
+//! ```
+
+use std::collections::{BTreeSet, HashSet};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+use crate::{Path, Selector};
+
+/// A selector that checks if a [`HashSet`] contains a value.
+///
+/// This type is not accessible outside this module, instead
+/// [`SetContainsExt`] can be used to construct it and receive a `impl
+/// Selector<State, bool>`.
+struct SetContains<State, SetPath, Value, const SAFE: bool> {
+    set_path: SetPath,
+    value: Value,
+    _marker: PhantomData<State>,
+}
+
+impl<State, SetPath, Value, const SAFE: bool> Selector<State, bool, SAFE> for SetContains<State, SetPath, Value, SAFE>
+where
+    State: 'static,
+    SetPath: Path<State, HashSet<Value>, SAFE>,
+    Value: Eq + Hash + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a bool> {
+        let contains = self.set_path.follow(state)?.contains(&self.value);
+        Some(if contains { &true } else { &false })
+    }
+}
+
+pub trait SetContainsExt<State, T, Value, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, HashSet<Value>, SAFE>,
+    Value: Eq + Hash + 'static,
+{
+    /// Selector that resolves to `true` if the set contains the given value.
+    fn contains(self, value: Value) -> impl Selector<State, bool, SAFE> {
+        SetContains {
+            set_path: self,
+            value,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Value, const SAFE: bool> SetContainsExt<State, T, Value, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, HashSet<Value>, SAFE>,
+    Value: Eq + Hash + 'static,
+{
+}
+
+/// A selector that checks if a [`BTreeSet`] contains a value.
+///
+/// This type is not accessible outside this module, instead [`BTreeSetExt`]
+/// can be used to construct it and receive a `impl Selector<State, bool>`.
+struct BTreeSetContains<State, SetPath, Value, const SAFE: bool> {
+    set_path: SetPath,
+    value: Value,
+    _marker: PhantomData<State>,
+}
+
+impl<State, SetPath, Value, const SAFE: bool> Selector<State, bool, SAFE> for BTreeSetContains<State, SetPath, Value, SAFE>
+where
+    State: 'static,
+    SetPath: Path<State, BTreeSet<Value>, SAFE>,
+    Value: Ord + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a bool> {
+        let contains = self.set_path.follow(state)?.contains(&self.value);
+        Some(if contains { &true } else { &false })
+    }
+}
+
+/// The end of a [`BTreeSet`] a [`BTreeSetBound`] resolves to.
+enum Bound {
+    Min,
+    Max,
+}
+
+/// A selector for the smallest or largest value of a [`BTreeSet`].
+///
+/// This type is not accessible outside this module, instead [`BTreeSetExt`]
+/// can be used to construct it and receive a `impl Selector<State, Value>`.
+struct BTreeSetBound<State, SetPath, Value, const SAFE: bool> {
+    set_path: SetPath,
+    bound: Bound,
+    _marker: PhantomData<(State, Value)>,
+}
+
+impl<State, SetPath, Value, const SAFE: bool> Selector<State, Value, false> for BTreeSetBound<State, SetPath, Value, SAFE>
+where
+    State: 'static,
+    SetPath: Path<State, BTreeSet<Value>, SAFE>,
+    Value: Ord + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Value> {
+        let set = self.set_path.follow(state)?;
+
+        match self.bound {
+            Bound::Min => set.first(),
+            Bound::Max => set.last(),
+        }
+    }
+}
+
+pub trait BTreeSetExt<State, T, Value, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, BTreeSet<Value>, SAFE>,
+    Value: Ord + 'static,
+{
+    /// Selector that resolves to `true` if the set contains the given value.
+    fn contains(self, value: Value) -> impl Selector<State, bool, SAFE> {
+        BTreeSetContains {
+            set_path: self,
+            value,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Selector for the smallest value of the set.
+    fn min(self) -> impl Selector<State, Value, false> {
+        BTreeSetBound {
+            set_path: self,
+            bound: Bound::Min,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Selector for the largest value of the set.
+    fn max(self) -> impl Selector<State, Value, false> {
+        BTreeSetBound {
+            set_path: self,
+            bound: Bound::Max,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Value, const SAFE: bool> BTreeSetExt<State, T, Value, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, BTreeSet<Value>, SAFE>,
+    Value: Ord + 'static,
+{
+}
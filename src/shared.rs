@@ -0,0 +1,176 @@
+//! Module providing an extension trait to dereference [`Rc`] and [`Arc`]
+//! paths in the state.
+//!
+//! Shared values can't be borrowed mutably, so [`SharedExt::shared_deref`]
+//! only returns a [`PathRead`]. [`SharedExt::make_mut`] returns a path that
+//! clones the value on write if it is shared, just like [`Rc::make_mut`].
+//!
+//! Example:
+//! ```
+//! use std::sync::Arc;
+//! use rust_state::{Context, RustState, SharedExt};
+//!
+//! #[derive(Clone, Debug, PartialEq)]
+//! struct Config {
+//!     scale: u32,
+//! }
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     config: Arc<Config>,
+//! }
+//!
+//! let config = Arc::new(Config { scale: 1 });
+//! let mut context = Context::new(State { config: config.clone() });
+//!
+//! let config_path = State::path().config().shared_deref();
+//!
+//! assert_eq!(context.get(&config_path), &Config { scale: 1 });
+//!
+//! context.update_value(State::path().config().make_mut(), Config { scale: 2 });
+//! context.apply();
+//!
+//! assert_eq!(context.get(&config_path), &Config { scale: 2 });
+//! // The original allocation is untouched.
+//! assert_eq!(config.scale, 1);
+//! ```
+
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// A reference counted pointer to a shared value.
+pub trait Shared: Deref {
+    /// Get mutable access to the value, cloning it if it is shared.
+    fn make_mut(&mut self) -> &mut Self::Target
+    where
+        Self::Target: Clone;
+}
+
+impl<T: ?Sized> Shared for Rc<T> {
+    fn make_mut(&mut self) -> &mut T
+    where
+        T: Clone,
+    {
+        Rc::make_mut(self)
+    }
+}
+
+impl<T: ?Sized> Shared for Arc<T> {
+    fn make_mut(&mut self) -> &mut T
+    where
+        T: Clone,
+    {
+        Arc::make_mut(self)
+    }
+}
+
+/// A path that dereferences a shared pointer in the state.
+///
+/// This type is not accessible outside this module, instead [`SharedExt`] can
+/// be used to construct it and receive an `impl PathRead<State, T>` or `impl
+/// Path<State, T>`.
+struct SharedPath<State, PointerPath, Pointer, Target: ?Sized, const SAFE: bool> {
+    pointer_path: PointerPath,
+    _marker: PhantomData<(State, Pointer, Target)>,
+}
+
+impl<State, PointerPath, Pointer, Target: ?Sized, const SAFE: bool> Clone for SharedPath<State, PointerPath, Pointer, Target, SAFE>
+where
+    PointerPath: Path<State, Pointer, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            pointer_path: self.pointer_path.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, PointerPath, Pointer, Target: ?Sized, const SAFE: bool> Copy for SharedPath<State, PointerPath, Pointer, Target, SAFE> where
+    PointerPath: Path<State, Pointer, SAFE> + Copy
+{
+}
+
+impl<State, PointerPath, Pointer, Target, const SAFE: bool> Selector<State, Target, SAFE>
+    for SharedPath<State, PointerPath, Pointer, Target, SAFE>
+where
+    State: 'static,
+    PointerPath: Path<State, Pointer, SAFE>,
+    Pointer: Shared<Target = Target> + 'static,
+    Target: ?Sized + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Target> {
+        self.pointer_path.follow(state).map(Deref::deref)
+    }
+}
+
+impl<State, PointerPath, Pointer, Target, const SAFE: bool> PathRead<State, Target, SAFE>
+    for SharedPath<State, PointerPath, Pointer, Target, SAFE>
+where
+    State: 'static,
+    PointerPath: Path<State, Pointer, SAFE>,
+    Pointer: Shared<Target = Target> + 'static,
+    Target: ?Sized + 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Target> {
+        self.pointer_path.follow(state).map(Deref::deref)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.pointer_path.key()?))
+    }
+}
+
+impl<State, PointerPath, Pointer, Target, const SAFE: bool> PathWrite<State, Target, SAFE>
+    for SharedPath<State, PointerPath, Pointer, Target, SAFE>
+where
+    State: 'static,
+    PointerPath: Path<State, Pointer, SAFE>,
+    Pointer: Shared<Target = Target> + 'static,
+    Target: Clone + 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Target> {
+        self.pointer_path.follow_mut(state).map(Shared::make_mut)
+    }
+}
+
+pub trait SharedExt<State, Pointer, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Pointer, SAFE> + Copy,
+    Pointer: Shared + 'static,
+    Pointer::Target: 'static,
+{
+    /// Read-only path to the shared value.
+    fn shared_deref(self) -> impl PathRead<State, Pointer::Target, SAFE> + Copy {
+        SharedPath {
+            pointer_path: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Path to the shared value that clones it on write if it is shared.
+    fn make_mut(self) -> impl Path<State, Pointer::Target, SAFE> + Copy
+    where
+        Pointer::Target: Clone,
+    {
+        SharedPath {
+            pointer_path: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Pointer, const SAFE: bool> SharedExt<State, Pointer, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Pointer, SAFE> + Copy,
+    Pointer: Shared + 'static,
+    Pointer::Target: 'static,
+{
+}
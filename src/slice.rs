@@ -0,0 +1,275 @@
+//! Module providing extension traits to index a slice in the state.
+//!
+//! Paths to types that dereference to a slice, like `Box<[T]>` or `Rc<[T]>`,
+//! can be converted to a path of `[T]` with [`AsSliceExt`], which can then be
+//! indexed with [`SliceIndexExt`].
+//!
+//! Example:
+//! ```
+//! use std::rc::Rc;
+//! use rust_state::{AsSliceExt, Context, RustState, SliceIndexExt};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     boxed: Box<[u32]>,
+//!     shared: Rc<[u32]>,
+//! }
+//!
+//! let context = Context::new(State {
+//!     boxed: Box::new([1, 2, 3]),
+//!     shared: Rc::from([4, 5, 6]),
+//! });
+//!
+//! let boxed_path = State::path().boxed().as_slice().slice_index(1);
+//! let shared_path = State::path().shared().as_slice().slice_index(2);
+//!
+//! assert_eq!(context.try_get(&boxed_path), Some(&2));
+//! assert_eq!(context.try_get(&shared_path), Some(&6));
+//! ```
+
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::sync::Arc;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// A type that dereferences to a slice of `Item`.
+pub trait AsSlice<Item> {
+    fn as_slice(&self) -> &[Item];
+
+    /// Get mutable access to the slice. Returns `None` if the slice is shared
+    /// and can't be mutated.
+    fn as_mut_slice(&mut self) -> Option<&mut [Item]>;
+}
+
+impl<Item> AsSlice<Item> for Vec<Item> {
+    fn as_slice(&self) -> &[Item] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [Item]> {
+        Some(self)
+    }
+}
+
+impl<Item, const N: usize> AsSlice<Item> for [Item; N] {
+    fn as_slice(&self) -> &[Item] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [Item]> {
+        Some(self)
+    }
+}
+
+impl<Item> AsSlice<Item> for Box<[Item]> {
+    fn as_slice(&self) -> &[Item] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [Item]> {
+        Some(self)
+    }
+}
+
+impl<Item> AsSlice<Item> for Rc<[Item]> {
+    fn as_slice(&self) -> &[Item] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [Item]> {
+        Rc::get_mut(self)
+    }
+}
+
+impl<Item> AsSlice<Item> for Arc<[Item]> {
+    fn as_slice(&self) -> &[Item] {
+        self
+    }
+
+    fn as_mut_slice(&mut self) -> Option<&mut [Item]> {
+        Arc::get_mut(self)
+    }
+}
+
+/// A path that dereferences a type implementing [`AsSlice`] into a slice.
+///
+/// This type is not accessible outside this module, instead [`AsSliceExt`]
+/// can be used to construct it and receive a `impl Path<State, [Item]>`.
+struct SlicePath<State, InnerPath, Inner, Item, const SAFE: bool> {
+    inner_path: InnerPath,
+    _marker: PhantomData<(State, Inner, Item)>,
+}
+
+impl<State, InnerPath, Inner, Item, const SAFE: bool> Clone for SlicePath<State, InnerPath, Inner, Item, SAFE>
+where
+    InnerPath: Path<State, Inner, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner_path: self.inner_path.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, InnerPath, Inner, Item, const SAFE: bool> Copy for SlicePath<State, InnerPath, Inner, Item, SAFE> where
+    InnerPath: Path<State, Inner, SAFE> + Copy
+{
+}
+
+impl<State, InnerPath, Inner, Item, const SAFE: bool> Selector<State, [Item], false> for SlicePath<State, InnerPath, Inner, Item, SAFE>
+where
+    State: 'static,
+    InnerPath: Path<State, Inner, SAFE>,
+    Inner: AsSlice<Item> + 'static,
+    Item: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a [Item]> {
+        self.follow(state)
+    }
+}
+
+impl<State, InnerPath, Inner, Item, const SAFE: bool> PathRead<State, [Item], false> for SlicePath<State, InnerPath, Inner, Item, SAFE>
+where
+    State: 'static,
+    InnerPath: Path<State, Inner, SAFE>,
+    Inner: AsSlice<Item> + 'static,
+    Item: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a [Item]> {
+        self.inner_path.follow(state).map(AsSlice::as_slice)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.inner_path.key()?))
+    }
+}
+
+impl<State, InnerPath, Inner, Item, const SAFE: bool> PathWrite<State, [Item], false> for SlicePath<State, InnerPath, Inner, Item, SAFE>
+where
+    State: 'static,
+    InnerPath: Path<State, Inner, SAFE>,
+    Inner: AsSlice<Item> + 'static,
+    Item: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut [Item]> {
+        self.inner_path.follow_mut(state)?.as_mut_slice()
+    }
+}
+
+pub trait AsSliceExt<State, Inner, Item, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Inner, SAFE> + Copy,
+    Inner: AsSlice<Item> + 'static,
+    Item: 'static,
+{
+    /// Convert the path into a path of `[Item]`. Following the path mutably
+    /// fails if the slice is shared.
+    fn as_slice(self) -> impl Path<State, [Item], false> + Copy {
+        SlicePath {
+            inner_path: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Inner, Item, const SAFE: bool> AsSliceExt<State, Inner, Item, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Inner, SAFE> + Copy,
+    Inner: AsSlice<Item> + 'static,
+    Item: 'static,
+{
+}
+
+/// A path for doing a dynamic index into a slice.
+///
+/// This type is not accessible outside this module, instead
+/// [`SliceIndexExt`] can be used to construct it and receive a `impl
+/// Path<State, Item>`.
+struct SliceIndex<State, SlicePath, Item, const SAFE: bool> {
+    slice_path: SlicePath,
+    index: usize,
+    _marker: PhantomData<(State, Item)>,
+}
+
+impl<State, SlicePath, Item, const SAFE: bool> Clone for SliceIndex<State, SlicePath, Item, SAFE>
+where
+    SlicePath: Path<State, [Item], SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            slice_path: self.slice_path.clone(),
+            index: self.index,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, SlicePath, Item, const SAFE: bool> Copy for SliceIndex<State, SlicePath, Item, SAFE> where
+    SlicePath: Path<State, [Item], SAFE> + Copy
+{
+}
+
+impl<State, SlicePath, Item, const SAFE: bool> Selector<State, Item, false> for SliceIndex<State, SlicePath, Item, SAFE>
+where
+    State: 'static,
+    SlicePath: Path<State, [Item], SAFE>,
+    Item: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Item> {
+        self.follow(state)
+    }
+}
+
+impl<State, SlicePath, Item, const SAFE: bool> PathRead<State, Item, false> for SliceIndex<State, SlicePath, Item, SAFE>
+where
+    State: 'static,
+    SlicePath: Path<State, [Item], SAFE>,
+    Item: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Item> {
+        self.slice_path.follow(state)?.get(self.index)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.slice_path.key()?).with(self.index))
+    }
+}
+
+impl<State, SlicePath, Item, const SAFE: bool> PathWrite<State, Item, false> for SliceIndex<State, SlicePath, Item, SAFE>
+where
+    State: 'static,
+    SlicePath: Path<State, [Item], SAFE>,
+    Item: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Item> {
+        self.slice_path.follow_mut(state)?.get_mut(self.index)
+    }
+}
+
+pub trait SliceIndexExt<State, Item, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, [Item], SAFE> + Copy,
+    Item: 'static,
+{
+    fn slice_index(self, index: usize) -> impl Path<State, Item, false> + Copy {
+        SliceIndex {
+            slice_path: self,
+            index,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Item, const SAFE: bool> SliceIndexExt<State, Item, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, [Item], SAFE> + Copy,
+    Item: 'static,
+{
+}
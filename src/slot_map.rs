@@ -0,0 +1,131 @@
+//! Module providing an extension trait to index a [`SlotMap`] in the state.
+//!
+//! Keys of a [`SlotMap`] are generational, so a path to an entry never
+//! resolves to a different item, even if the entry is removed and its slot is
+//! re-used.
+//!
+//! Only available with the `slotmap` feature.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, ManuallyAssertExt, RustState, SlotMapLookupExt};
+//! use slotmap::{DefaultKey, SlotMap};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     items: SlotMap<DefaultKey, u32>,
+//! }
+//!
+//! let mut items = SlotMap::new();
+//! let key = items.insert(10);
+//!
+//! let context = Context::new(State { items });
+//!
+//! let item_path = State::path().items().lookup(key);
+//!
+//! assert_eq!(context.try_get(&item_path), Some(&10));
+//! ```
+
+use std::marker::PhantomData;
+
+use slotmap::{Key, SlotMap};
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// A path for doing a lookup into a [`SlotMap`].
+///
+/// This type is not accessible outside this module, instead
+/// [`SlotMapLookupExt`] can be used to construct it and receive a `impl
+/// Path<State, Value>`.
+struct SlotMapLookup<State, MapPath, K, Value, const SAFE: bool> {
+    map_path: MapPath,
+    key: K,
+    _marker: PhantomData<(State, Value)>,
+}
+
+impl<State, MapPath, K, Value, const SAFE: bool> Clone for SlotMapLookup<State, MapPath, K, Value, SAFE>
+where
+    MapPath: Path<State, SlotMap<K, Value>, SAFE>,
+    K: Key,
+{
+    fn clone(&self) -> Self {
+        Self {
+            map_path: self.map_path.clone(),
+            key: self.key,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, MapPath, K, Value, const SAFE: bool> Copy for SlotMapLookup<State, MapPath, K, Value, SAFE>
+where
+    MapPath: Path<State, SlotMap<K, Value>, SAFE> + Copy,
+    K: Key,
+{
+}
+
+impl<State, MapPath, K, Value, const SAFE: bool> Selector<State, Value, false> for SlotMapLookup<State, MapPath, K, Value, SAFE>
+where
+    State: 'static,
+    MapPath: Path<State, SlotMap<K, Value>, SAFE>,
+    K: Key + 'static,
+    Value: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Value> {
+        self.follow(state)
+    }
+}
+
+impl<State, MapPath, K, Value, const SAFE: bool> PathRead<State, Value, false> for SlotMapLookup<State, MapPath, K, Value, SAFE>
+where
+    State: 'static,
+    MapPath: Path<State, SlotMap<K, Value>, SAFE>,
+    K: Key + 'static,
+    Value: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Value> {
+        self.map_path.follow(state)?.get(self.key)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.map_path.key()?).with(self.key))
+    }
+}
+
+impl<State, MapPath, K, Value, const SAFE: bool> PathWrite<State, Value, false> for SlotMapLookup<State, MapPath, K, Value, SAFE>
+where
+    State: 'static,
+    MapPath: Path<State, SlotMap<K, Value>, SAFE>,
+    K: Key + 'static,
+    Value: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Value> {
+        self.map_path.follow_mut(state)?.get_mut(self.key)
+    }
+}
+
+pub trait SlotMapLookupExt<State, T, K, Value, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, SlotMap<K, Value>, SAFE> + Copy,
+    K: Key + 'static,
+    Value: 'static,
+{
+    fn lookup(self, key: K) -> impl Path<State, Value, false> + Copy {
+        SlotMapLookup {
+            map_path: self,
+            key,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, K, Value, const SAFE: bool> SlotMapLookupExt<State, T, K, Value, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, SlotMap<K, Value>, SAFE> + Copy,
+    K: Key + 'static,
+    Value: 'static,
+{
+}
@@ -0,0 +1,104 @@
+//! Module providing an extension trait to treat a [`String`] path in the state
+//! as a path to [`str`].
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, RustState, StringExt};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     name: String,
+//! }
+//!
+//! let context = Context::new(State {
+//!     name: String::from("rust_state"),
+//! });
+//!
+//! let path = State::path().name().as_str();
+//!
+//! assert_eq!(context.get(&path), "rust_state");
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// A path that resolves a [`String`] in the state to a [`str`].
+///
+/// This type is not accessible outside this module. Instead,
+/// [`StringExt`] is used to construct it and receive an `impl Path<State,
+/// str>`.
+struct StrPath<State, StringPath, const SAFE: bool> {
+    string_path: StringPath,
+    _marker: PhantomData<State>,
+}
+
+impl<State, StringPath, const SAFE: bool> Clone for StrPath<State, StringPath, SAFE>
+where
+    StringPath: Path<State, String, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            string_path: self.string_path.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, StringPath, const SAFE: bool> Copy for StrPath<State, StringPath, SAFE> where StringPath: Path<State, String, SAFE> + Copy {}
+
+impl<State, StringPath, const SAFE: bool> Selector<State, str, SAFE> for StrPath<State, StringPath, SAFE>
+where
+    State: 'static,
+    StringPath: Path<State, String, SAFE>,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a str> {
+        self.follow(state)
+    }
+}
+
+impl<State, StringPath, const SAFE: bool> PathRead<State, str, SAFE> for StrPath<State, StringPath, SAFE>
+where
+    State: 'static,
+    StringPath: Path<State, String, SAFE>,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a str> {
+        self.string_path.follow(state).map(String::as_str)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.string_path.key()?))
+    }
+}
+
+impl<State, StringPath, const SAFE: bool> PathWrite<State, str, SAFE> for StrPath<State, StringPath, SAFE>
+where
+    State: 'static,
+    StringPath: Path<State, String, SAFE>,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut str> {
+        self.string_path.follow_mut(state).map(String::as_mut_str)
+    }
+}
+
+pub trait StringExt<State, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, String, SAFE> + Copy,
+{
+    /// Convert a `Path<State, String>` into a `Path<State, str>`.
+    fn as_str(self) -> impl Path<State, str, SAFE> + Copy {
+        StrPath {
+            string_path: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, const SAFE: bool> StringExt<State, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, String, SAFE> + Copy,
+{
+}
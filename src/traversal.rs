@@ -0,0 +1,295 @@
+//! Module providing [`Traversal`], paths to zero or more targets.
+//!
+//! A [`Path`] resolves to at most one target. A `Traversal` visits every
+//! target it resolves to instead, for example all items of a [`Vec`] or all
+//! values of a [`HashMap`]. Traversals are read with
+//! [`Context::for_each`](crate::Context::for_each) and updated with
+//! [`Context::update_each`](crate::Context::update_each).
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, RustState, TraversalExt};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     scores: Vec<u32>,
+//! }
+//!
+//! let mut context = Context::new(State { scores: vec![1, 2, 3] });
+//! let scores = State::path().scores().each();
+//!
+//! context.update_each(scores, |score| *score *= 10);
+//! context.apply();
+//!
+//! let mut total = 0;
+//! context.for_each(scores, |score| total += score);
+//!
+//! assert_eq!(total, 60);
+//! ```
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::marker::PhantomData;
+
+use crate::Path;
+
+/// A path to zero or more targets in the state.
+pub trait Traversal<State, To: ?Sized>: Clone + 'static {
+    /// Call `visit` for every target.
+    fn traverse<'a>(&self, state: &'a State, visit: &mut dyn FnMut(&'a To));
+
+    /// Call `visit` with mutable access to every target.
+    fn traverse_mut(&self, state: &mut State, visit: &mut dyn FnMut(&mut To));
+}
+
+/// A collection that can be traversed.
+pub trait Traversable {
+    type Item;
+
+    fn for_each_item<'a>(&'a self, visit: &mut dyn FnMut(&'a Self::Item));
+
+    fn for_each_item_mut(&mut self, visit: &mut dyn FnMut(&mut Self::Item));
+}
+
+impl<T> Traversable for Vec<T> {
+    type Item = T;
+
+    fn for_each_item<'a>(&'a self, visit: &mut dyn FnMut(&'a T)) {
+        self.iter().for_each(visit);
+    }
+
+    fn for_each_item_mut(&mut self, visit: &mut dyn FnMut(&mut T)) {
+        self.iter_mut().for_each(visit);
+    }
+}
+
+impl<T> Traversable for VecDeque<T> {
+    type Item = T;
+
+    fn for_each_item<'a>(&'a self, visit: &mut dyn FnMut(&'a T)) {
+        self.iter().for_each(visit);
+    }
+
+    fn for_each_item_mut(&mut self, visit: &mut dyn FnMut(&mut T)) {
+        self.iter_mut().for_each(visit);
+    }
+}
+
+impl<T, const N: usize> Traversable for [T; N] {
+    type Item = T;
+
+    fn for_each_item<'a>(&'a self, visit: &mut dyn FnMut(&'a T)) {
+        self.iter().for_each(visit);
+    }
+
+    fn for_each_item_mut(&mut self, visit: &mut dyn FnMut(&mut T)) {
+        self.iter_mut().for_each(visit);
+    }
+}
+
+impl<T> Traversable for Option<T> {
+    type Item = T;
+
+    fn for_each_item<'a>(&'a self, visit: &mut dyn FnMut(&'a T)) {
+        self.iter().for_each(visit);
+    }
+
+    fn for_each_item_mut(&mut self, visit: &mut dyn FnMut(&mut T)) {
+        self.iter_mut().for_each(visit);
+    }
+}
+
+impl<K, V, S> Traversable for HashMap<K, V, S> {
+    type Item = V;
+
+    fn for_each_item<'a>(&'a self, visit: &mut dyn FnMut(&'a V)) {
+        self.values().for_each(visit);
+    }
+
+    fn for_each_item_mut(&mut self, visit: &mut dyn FnMut(&mut V)) {
+        self.values_mut().for_each(visit);
+    }
+}
+
+impl<K, V> Traversable for BTreeMap<K, V> {
+    type Item = V;
+
+    fn for_each_item<'a>(&'a self, visit: &mut dyn FnMut(&'a V)) {
+        self.values().for_each(visit);
+    }
+
+    fn for_each_item_mut(&mut self, visit: &mut dyn FnMut(&mut V)) {
+        self.values_mut().for_each(visit);
+    }
+}
+
+/// A [`Traversal`] that also visits the key of every target.
+pub trait IndexedTraversal<State, Key, To: ?Sized>: Traversal<State, To> {
+    /// Call `visit` for every key and target.
+    fn traverse_indexed<'a>(&self, state: &'a State, visit: &mut dyn FnMut(&'a Key, &'a To));
+
+    /// Call `visit` for every key and with mutable access to every target.
+    fn traverse_indexed_mut(&self, state: &mut State, visit: &mut dyn FnMut(&Key, &mut To));
+}
+
+/// A collection with keys that can be traversed.
+pub trait IndexedTraversable: Traversable {
+    type Key;
+
+    fn for_each_entry<'a>(&'a self, visit: &mut dyn FnMut(&'a Self::Key, &'a Self::Item));
+
+    fn for_each_entry_mut(&mut self, visit: &mut dyn FnMut(&Self::Key, &mut Self::Item));
+}
+
+impl<K, V, S> IndexedTraversable for HashMap<K, V, S> {
+    type Key = K;
+
+    fn for_each_entry<'a>(&'a self, visit: &mut dyn FnMut(&'a K, &'a V)) {
+        self.iter().for_each(|(key, value)| visit(key, value));
+    }
+
+    fn for_each_entry_mut(&mut self, visit: &mut dyn FnMut(&K, &mut V)) {
+        self.iter_mut().for_each(|(key, value)| visit(key, value));
+    }
+}
+
+impl<K, V> IndexedTraversable for BTreeMap<K, V> {
+    type Key = K;
+
+    fn for_each_entry<'a>(&'a self, visit: &mut dyn FnMut(&'a K, &'a V)) {
+        self.iter().for_each(|(key, value)| visit(key, value));
+    }
+
+    fn for_each_entry_mut(&mut self, visit: &mut dyn FnMut(&K, &mut V)) {
+        self.iter_mut().for_each(|(key, value)| visit(key, value));
+    }
+}
+
+/// A traversal over all items of a collection.
+///
+/// This type is not accessible outside this module, instead [`TraversalExt`]
+/// can be used to construct it and receive an `impl Traversal<State, T>`.
+struct Each<State, CollectionPath, Collection, const SAFE: bool> {
+    collection_path: CollectionPath,
+    _marker: PhantomData<(State, Collection)>,
+}
+
+impl<State, CollectionPath, Collection, const SAFE: bool> Clone for Each<State, CollectionPath, Collection, SAFE>
+where
+    CollectionPath: Path<State, Collection, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            collection_path: self.collection_path.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, CollectionPath, Collection, const SAFE: bool> Copy for Each<State, CollectionPath, Collection, SAFE> where
+    CollectionPath: Path<State, Collection, SAFE> + Copy
+{
+}
+
+impl<State, CollectionPath, Collection, const SAFE: bool> Traversal<State, Collection::Item>
+    for Each<State, CollectionPath, Collection, SAFE>
+where
+    State: 'static,
+    CollectionPath: Path<State, Collection, SAFE>,
+    Collection: Traversable + 'static,
+{
+    fn traverse<'a>(&self, state: &'a State, visit: &mut dyn FnMut(&'a Collection::Item)) {
+        if let Some(collection) = self.collection_path.follow(state) {
+            collection.for_each_item(visit);
+        }
+    }
+
+    fn traverse_mut(&self, state: &mut State, visit: &mut dyn FnMut(&mut Collection::Item)) {
+        if let Some(collection) = self.collection_path.follow_mut(state) {
+            collection.for_each_item_mut(visit);
+        }
+    }
+}
+
+impl<State, CollectionPath, Collection, const SAFE: bool> IndexedTraversal<State, Collection::Key, Collection::Item>
+    for Each<State, CollectionPath, Collection, SAFE>
+where
+    State: 'static,
+    CollectionPath: Path<State, Collection, SAFE>,
+    Collection: IndexedTraversable + 'static,
+{
+    fn traverse_indexed<'a>(&self, state: &'a State, visit: &mut dyn FnMut(&'a Collection::Key, &'a Collection::Item)) {
+        if let Some(collection) = self.collection_path.follow(state) {
+            collection.for_each_entry(visit);
+        }
+    }
+
+    fn traverse_indexed_mut(&self, state: &mut State, visit: &mut dyn FnMut(&Collection::Key, &mut Collection::Item)) {
+        if let Some(collection) = self.collection_path.follow_mut(state) {
+            collection.for_each_entry_mut(visit);
+        }
+    }
+}
+
+pub trait TraversalExt<State, Collection, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Collection, SAFE> + Copy,
+    Collection: Traversable + 'static,
+{
+    /// Traversal over every item of the collection.
+    fn each(self) -> impl Traversal<State, Collection::Item> + Copy {
+        Each {
+            collection_path: self,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Traversal over every entry of the map, visiting keys as well.
+    ///
+    /// Example:
+    /// ```
+    /// use std::collections::BTreeMap;
+    /// use rust_state::{Context, RustState, TraversalExt};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct State {
+    ///     stock: BTreeMap<&'static str, u32>,
+    /// }
+    ///
+    /// let mut context = Context::new(State {
+    ///     stock: BTreeMap::from([("apples", 1), ("pears", 2)]),
+    /// });
+    /// let stock = State::path().stock().each_indexed();
+    ///
+    /// context.update_each_indexed(stock, |name, count| {
+    ///     if *name == "apples" {
+    ///         *count += 10;
+    ///     }
+    /// });
+    /// context.apply();
+    ///
+    /// let mut entries = Vec::new();
+    /// context.for_each_indexed(stock, |name, count| entries.push((*name, *count)));
+    ///
+    /// assert_eq!(entries, [("apples", 11), ("pears", 2)]);
+    /// ```
+    fn each_indexed(self) -> impl IndexedTraversal<State, Collection::Key, Collection::Item> + Copy
+    where
+        Collection: IndexedTraversable,
+    {
+        Each {
+            collection_path: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Collection, const SAFE: bool> TraversalExt<State, Collection, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Collection, SAFE> + Copy,
+    Collection: Traversable + 'static,
+{
+}
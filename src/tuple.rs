@@ -0,0 +1,186 @@
+//! Module providing an extension trait to access the elements of tuples in
+//! the state.
+//!
+//! Tuples with up to four elements are supported.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, RustState, TupleExt};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     position: (f32, f32),
+//! }
+//!
+//! let mut context = Context::new(State { position: (1.0, 2.0) });
+//!
+//! let x_path = State::path().position()._0();
+//! let y_path = State::path().position()._1();
+//!
+//! context.update_value(y_path, 5.0);
+//! context.apply();
+//!
+//! assert_eq!(context.get(&x_path), &1.0);
+//! assert_eq!(context.get(&y_path), &5.0);
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// A tuple with an element at `INDEX`.
+pub trait TupleElement<const INDEX: usize> {
+    type Element;
+
+    fn element(&self) -> &Self::Element;
+
+    fn element_mut(&mut self) -> &mut Self::Element;
+}
+
+macro_rules! implement_tuple_element {
+    (($($element:ident),*), $index:tt => $target:ident) => {
+        impl<$($element),*> TupleElement<$index> for ($($element,)*) {
+            type Element = $target;
+
+            fn element(&self) -> &Self::Element {
+                &self.$index
+            }
+
+            fn element_mut(&mut self) -> &mut Self::Element {
+                &mut self.$index
+            }
+        }
+    };
+}
+
+implement_tuple_element!((A), 0 => A);
+implement_tuple_element!((A, B), 0 => A);
+implement_tuple_element!((A, B), 1 => B);
+implement_tuple_element!((A, B, C), 0 => A);
+implement_tuple_element!((A, B, C), 1 => B);
+implement_tuple_element!((A, B, C), 2 => C);
+implement_tuple_element!((A, B, C, D), 0 => A);
+implement_tuple_element!((A, B, C, D), 1 => B);
+implement_tuple_element!((A, B, C, D), 2 => C);
+implement_tuple_element!((A, B, C, D), 3 => D);
+
+/// A path to an element of a tuple.
+///
+/// This type is not accessible outside this module, instead [`TupleExt`] can
+/// be used to construct it and receive an `impl Path<State, Element>`.
+struct TupleElementPath<State, TuplePath, Tuple, Element, const INDEX: usize, const SAFE: bool> {
+    tuple_path: TuplePath,
+    _marker: PhantomData<(State, Tuple, Element)>,
+}
+
+impl<State, TuplePath, Tuple, Element, const INDEX: usize, const SAFE: bool> Clone
+    for TupleElementPath<State, TuplePath, Tuple, Element, INDEX, SAFE>
+where
+    TuplePath: Path<State, Tuple, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            tuple_path: self.tuple_path.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, TuplePath, Tuple, Element, const INDEX: usize, const SAFE: bool> Copy
+    for TupleElementPath<State, TuplePath, Tuple, Element, INDEX, SAFE>
+where
+    TuplePath: Path<State, Tuple, SAFE> + Copy,
+{
+}
+
+impl<State, TuplePath, Tuple, Element, const INDEX: usize, const SAFE: bool> Selector<State, Element, SAFE>
+    for TupleElementPath<State, TuplePath, Tuple, Element, INDEX, SAFE>
+where
+    State: 'static,
+    TuplePath: Path<State, Tuple, SAFE>,
+    Tuple: TupleElement<INDEX, Element = Element> + 'static,
+    Element: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Element> {
+        self.follow(state)
+    }
+}
+
+impl<State, TuplePath, Tuple, Element, const INDEX: usize, const SAFE: bool> PathRead<State, Element, SAFE>
+    for TupleElementPath<State, TuplePath, Tuple, Element, INDEX, SAFE>
+where
+    State: 'static,
+    TuplePath: Path<State, Tuple, SAFE>,
+    Tuple: TupleElement<INDEX, Element = Element> + 'static,
+    Element: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Element> {
+        self.tuple_path.follow(state).map(TupleElement::element)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.tuple_path.key()?))
+    }
+}
+
+impl<State, TuplePath, Tuple, Element, const INDEX: usize, const SAFE: bool> PathWrite<State, Element, SAFE>
+    for TupleElementPath<State, TuplePath, Tuple, Element, INDEX, SAFE>
+where
+    State: 'static,
+    TuplePath: Path<State, Tuple, SAFE>,
+    Tuple: TupleElement<INDEX, Element = Element> + 'static,
+    Element: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Element> {
+        self.tuple_path.follow_mut(state).map(TupleElement::element_mut)
+    }
+}
+
+macro_rules! element_method {
+    ($(#[$attribute:meta])* $name:ident => $index:literal) => {
+        $(#[$attribute])*
+        fn $name(self) -> impl Path<State, <Tuple as TupleElement<$index>>::Element, SAFE> + Copy
+        where
+            Tuple: TupleElement<$index>,
+            <Tuple as TupleElement<$index>>::Element: 'static,
+        {
+            TupleElementPath::<_, _, _, _, $index, SAFE> {
+                tuple_path: self,
+                _marker: PhantomData,
+            }
+        }
+    };
+}
+
+pub trait TupleExt<State, Tuple, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Tuple, SAFE> + Copy,
+    Tuple: 'static,
+{
+    element_method!(
+        /// Path to the first element of the tuple.
+        _0 => 0
+    );
+    element_method!(
+        /// Path to the second element of the tuple.
+        _1 => 1
+    );
+    element_method!(
+        /// Path to the third element of the tuple.
+        _2 => 2
+    );
+    element_method!(
+        /// Path to the fourth element of the tuple.
+        _3 => 3
+    );
+}
+
+impl<State, T, Tuple, const SAFE: bool> TupleExt<State, Tuple, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Tuple, SAFE> + Copy,
+    Tuple: 'static,
+{
+}
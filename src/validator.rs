@@ -0,0 +1,80 @@
+//! Module providing validators that reject invalid writes to a path. See
+//! [`Context::add_validator`](crate::Context::add_validator).
+
+use std::error::Error;
+use std::marker::PhantomData;
+
+use crate::Path;
+
+/// Type erased validator for a path in the state.
+///
+/// Every validator keeps the last valid value of its path, so values only
+/// need to be cloned when they were changed.
+pub(crate) trait Validator<State> {
+    /// Validate the value written by a change, if it was changed.
+    fn validate(&mut self, state: &State) -> Result<(), Box<dyn Error>>;
+
+    /// Accept the current value as the last valid value.
+    fn accept(&mut self, state: &State);
+
+    /// Restore the last valid value, if the value was changed.
+    fn restore(&mut self, state: &mut State);
+}
+
+pub(crate) struct PathValidator<ValuePath, Value, F, const SAFE: bool> {
+    value_path: ValuePath,
+    validate: F,
+    last_valid: Option<Value>,
+    _marker: PhantomData<Value>,
+}
+
+impl<ValuePath, Value, F, const SAFE: bool> PathValidator<ValuePath, Value, F, SAFE> {
+    pub(crate) fn new<State>(value_path: ValuePath, validate: F, state: &State) -> Self
+    where
+        ValuePath: Path<State, Value, SAFE>,
+        Value: Clone,
+    {
+        Self {
+            last_valid: value_path.follow(state).cloned(),
+            value_path,
+            validate,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, ValuePath, Value, F, E, const SAFE: bool> Validator<State> for PathValidator<ValuePath, Value, F, SAFE>
+where
+    ValuePath: Path<State, Value, SAFE>,
+    Value: Clone + PartialEq,
+    F: Fn(&Value) -> Result<(), E>,
+    E: Error + 'static,
+{
+    fn validate(&mut self, state: &State) -> Result<(), Box<dyn Error>> {
+        match self.value_path.follow(state) {
+            // Only validate values that were written.
+            Some(value) if self.last_valid.as_ref() != Some(value) => (self.validate)(value).map_err(Into::into),
+            _ => Ok(()),
+        }
+    }
+
+    fn accept(&mut self, state: &State) {
+        let value = self.value_path.follow(state);
+
+        if self.last_valid.as_ref() != value {
+            self.last_valid = value.cloned();
+        }
+    }
+
+    fn restore(&mut self, state: &mut State) {
+        let Some(last_valid) = &self.last_valid else {
+            return;
+        };
+
+        if let Some(value) = self.value_path.follow_mut(state) {
+            if value != last_valid {
+                *value = last_valid.clone();
+            }
+        }
+    }
+}
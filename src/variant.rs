@@ -0,0 +1,157 @@
+//! Module providing an extension trait to select one variant of an enum in
+//! the state.
+//!
+//! This works for any enum, including ones from other crates that can't
+//! derive [`RustState`](crate::RustState). The variant is selected with a pair
+//! of functions, so closures that don't capture anything can be passed.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, RustState, VariantExt};
+//!
+//! enum Shape {
+//!     Circle(f32),
+//!     Square(f32),
+//! }
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     shape: Shape,
+//! }
+//!
+//! let mut context = Context::new(State { shape: Shape::Circle(1.0) });
+//!
+//! let radius_path = State::path().shape().try_as(
+//!     |shape| match shape {
+//!         Shape::Circle(radius) => Some(radius),
+//!         _ => None,
+//!     },
+//!     |shape| match shape {
+//!         Shape::Circle(radius) => Some(radius),
+//!         _ => None,
+//!     },
+//! );
+//!
+//! context.update_value(radius_path, 2.0);
+//! context.apply();
+//!
+//! assert_eq!(context.try_get(&radius_path), Some(&2.0));
+//!
+//! context.update_value(State::path().shape(), Shape::Square(1.0));
+//! context.apply();
+//!
+//! assert_eq!(context.try_get(&radius_path), None);
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// A path that selects the payload of one variant of an enum in the state.
+///
+/// This type is not accessible outside this module. Instead, [`VariantExt`]
+/// is used to construct it and receive an `impl Path<State, T>`.
+struct VariantPath<State, EnumPath, Enum, Payload: ?Sized, const SAFE: bool> {
+    enum_path: EnumPath,
+    get: fn(&Enum) -> Option<&Payload>,
+    get_mut: fn(&mut Enum) -> Option<&mut Payload>,
+    _marker: PhantomData<State>,
+}
+
+impl<State, EnumPath, Enum, Payload: ?Sized, const SAFE: bool> Clone for VariantPath<State, EnumPath, Enum, Payload, SAFE>
+where
+    EnumPath: Path<State, Enum, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            enum_path: self.enum_path.clone(),
+            get: self.get,
+            get_mut: self.get_mut,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, EnumPath, Enum, Payload: ?Sized, const SAFE: bool> Copy for VariantPath<State, EnumPath, Enum, Payload, SAFE> where
+    EnumPath: Path<State, Enum, SAFE> + Copy
+{
+}
+
+impl<State, EnumPath, Enum, Payload, const SAFE: bool> Selector<State, Payload, false> for VariantPath<State, EnumPath, Enum, Payload, SAFE>
+where
+    State: 'static,
+    EnumPath: Path<State, Enum, SAFE>,
+    Enum: 'static,
+    Payload: ?Sized + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Payload> {
+        self.follow(state)
+    }
+}
+
+impl<State, EnumPath, Enum, Payload, const SAFE: bool> PathRead<State, Payload, false> for VariantPath<State, EnumPath, Enum, Payload, SAFE>
+where
+    State: 'static,
+    EnumPath: Path<State, Enum, SAFE>,
+    Enum: 'static,
+    Payload: ?Sized + 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Payload> {
+        self.enum_path.follow(state).and_then(self.get)
+    }
+
+    /// The accessors are function pointers, which can't be identified, so the
+    /// path has no key.
+    fn key(&self) -> Option<PathKey> {
+        None
+    }
+}
+
+impl<State, EnumPath, Enum, Payload, const SAFE: bool> PathWrite<State, Payload, false>
+    for VariantPath<State, EnumPath, Enum, Payload, SAFE>
+where
+    State: 'static,
+    EnumPath: Path<State, Enum, SAFE>,
+    Enum: 'static,
+    Payload: ?Sized + 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Payload> {
+        self.enum_path.follow_mut(state).and_then(self.get_mut)
+    }
+}
+
+pub trait VariantExt<State, Enum, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Enum, SAFE> + Copy,
+    Enum: 'static,
+{
+    /// Select the payload of one variant of the enum in this path.
+    ///
+    /// This path is *not* safe. It returns `None` at runtime if the enum
+    /// holds a different variant.
+    fn try_as<Payload>(
+        self,
+        get: fn(&Enum) -> Option<&Payload>,
+        get_mut: fn(&mut Enum) -> Option<&mut Payload>,
+    ) -> impl Path<State, Payload, false> + Copy
+    where
+        Payload: ?Sized + 'static,
+    {
+        VariantPath {
+            enum_path: self,
+            get,
+            get_mut,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Enum, const SAFE: bool> VariantExt<State, Enum, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Enum, SAFE> + Copy,
+    Enum: 'static,
+{
+}
@@ -12,7 +12,7 @@
 //!
 //! Example:
 //! ```
-//! use rust_state::{Context, ManuallyAssertExt, RustState, VecItem, VecLookupExt};
+//! use rust_state::{Context, ManuallyAssertExt, RustState, VecIndexExt, VecItem, VecLookupExt};
 //!
 //! #[derive(Debug, PartialEq, Eq)]
 //! struct TestItem {
@@ -44,12 +44,26 @@
 //! let index_path = State::path().items().index(0);
 //!
 //! assert_eq!(context.try_get(&index_path), Some(&TestItem { id: 10 }));
+//!
+//! let last_path = State::path().items().last();
+//!
+//! assert_eq!(context.try_get(&last_path), Some(&TestItem { id: 10 }));
+//!
+//! let contains_path = State::path().items().contains(11);
+//!
+//! assert!(!*context.get(&contains_path));
 //! ```
 
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
 use std::hash::Hash;
 use std::marker::PhantomData;
+use std::ops::Range;
 
-use crate::{Path, Selector};
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
 
 /// An item inside a vector accessible through a [`Path`].
 pub trait VecItem {
@@ -62,6 +76,176 @@ pub trait VecItem {
     fn get_id(&self) -> Self::Id;
 }
 
+/// Error returned when an item was not inserted, because an item with the
+/// same [`VecItem::Id`] already exists.
+///
+/// See [`Context::vec_push_unique`](crate::Context::vec_push_unique).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DuplicateId {
+    type_name: &'static str,
+}
+
+impl DuplicateId {
+    pub(crate) fn new<Item>() -> Self {
+        Self {
+            type_name: std::any::type_name::<Item>(),
+        }
+    }
+}
+
+impl fmt::Display for DuplicateId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Duplicate id in vector of {}", self.type_name)
+    }
+}
+
+impl Error for DuplicateId {}
+
+/// Error reported when an item uses a [`VecItem::Id`] that belonged to an
+/// item that was removed earlier.
+///
+/// See [`Context::detect_id_reuse`](crate::Context::detect_id_reuse).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReusedId {
+    type_name: &'static str,
+}
+
+impl ReusedId {
+    pub(crate) fn new<Item>() -> Self {
+        Self {
+            type_name: std::any::type_name::<Item>(),
+        }
+    }
+}
+
+impl fmt::Display for ReusedId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Re-used id in vector of {}", self.type_name)
+    }
+}
+
+impl Error for ReusedId {}
+
+/// Check for re-used ids in a vector of the state.
+///
+/// Used by [`Context::detect_id_reuse`](crate::Context::detect_id_reuse).
+pub(crate) trait IdReuseCheck<State> {
+    /// Compare the current ids of the vector to all previously seen ids,
+    /// returning an error for every duplicate and re-used id.
+    fn check(&mut self, state: &State) -> Vec<Box<dyn Error>>;
+}
+
+/// Tracks all ids that were ever seen in a [`Vec`] of [`VecItem`]s.
+pub(crate) struct IdReuseTracker<VectorPath, Item, const SAFE: bool>
+where
+    Item: VecItem,
+{
+    vector_path: VectorPath,
+    seen: HashSet<Item::Id>,
+    present: HashSet<Item::Id>,
+}
+
+impl<VectorPath, Item, const SAFE: bool> IdReuseTracker<VectorPath, Item, SAFE>
+where
+    Item: VecItem,
+{
+    pub(crate) fn new(vector_path: VectorPath) -> Self {
+        Self {
+            vector_path,
+            seen: HashSet::new(),
+            present: HashSet::new(),
+        }
+    }
+}
+
+impl<State, VectorPath, Item, const SAFE: bool> IdReuseCheck<State> for IdReuseTracker<VectorPath, Item, SAFE>
+where
+    VectorPath: Path<State, Vec<Item>, SAFE>,
+    Item: VecItem,
+{
+    fn check(&mut self, state: &State) -> Vec<Box<dyn Error>> {
+        let Some(items) = self.vector_path.follow(state) else {
+            return Vec::new();
+        };
+
+        let mut present = HashSet::with_capacity(items.len());
+        let mut errors: Vec<Box<dyn Error>> = Vec::new();
+
+        for id in items.iter().map(VecItem::get_id) {
+            if !present.insert(id) {
+                errors.push(Box::new(DuplicateId::new::<Item>()));
+            } else if !self.present.contains(&id) && self.seen.contains(&id) {
+                errors.push(Box::new(ReusedId::new::<Item>()));
+            }
+        }
+
+        self.seen.extend(present.iter().copied());
+        self.present = present;
+
+        errors
+    }
+}
+
+/// Type erased [`VecIdIndex`] so the [`Context`](crate::Context) can store
+/// indices for different vectors.
+pub(crate) trait VecIdIndexCache {
+    /// Discard the positions so they are rebuilt on the next lookup.
+    fn invalidate(&self);
+
+    fn as_any(&self) -> &dyn Any;
+}
+
+/// Maps the ids of the items in a vector to their position.
+///
+/// See [`Context::index_vec`](crate::Context::index_vec).
+pub(crate) struct VecIdIndex<VectorPath, Item, const SAFE: bool>
+where
+    Item: VecItem,
+{
+    vector_path: VectorPath,
+    positions: RefCell<Option<HashMap<Item::Id, usize>>>,
+}
+
+impl<VectorPath, Item, const SAFE: bool> VecIdIndex<VectorPath, Item, SAFE>
+where
+    Item: VecItem,
+{
+    pub(crate) fn new(vector_path: VectorPath) -> Self {
+        Self {
+            vector_path,
+            positions: RefCell::new(None),
+        }
+    }
+
+    /// Look up the item with the given id, rebuilding the positions first if
+    /// they were invalidated.
+    pub(crate) fn lookup<'a, State>(&self, state: &'a State, id: Item::Id) -> Option<&'a Item>
+    where
+        VectorPath: Path<State, Vec<Item>, SAFE>,
+    {
+        let items = self.vector_path.follow(state)?;
+        let mut positions = self.positions.borrow_mut();
+        let positions =
+            positions.get_or_insert_with(|| items.iter().enumerate().map(|(position, item)| (item.get_id(), position)).collect());
+
+        items.get(*positions.get(&id)?)
+    }
+}
+
+impl<VectorPath, Item, const SAFE: bool> VecIdIndexCache for VecIdIndex<VectorPath, Item, SAFE>
+where
+    VectorPath: 'static,
+    Item: VecItem + 'static,
+{
+    fn invalidate(&self) {
+        self.positions.borrow_mut().take();
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
 /// A path for doing a dynamic lookup into a [`Vec`] of [`VecItem`]s.
 ///
 /// This type is not accessible outside this module, instead
@@ -82,13 +266,17 @@ where
     Item: VecItem,
 {
     fn clone(&self) -> Self {
-        *self
+        Self {
+            vector_path: self.vector_path.clone(),
+            id: self.id,
+            _marker: PhantomData,
+        }
     }
 }
 
 impl<State, VectorPath, Item, const SAFE: bool> Copy for VecLookup<State, VectorPath, Item, SAFE>
 where
-    VectorPath: Path<State, Vec<Item>, SAFE>,
+    VectorPath: Path<State, Vec<Item>, SAFE> + Copy,
     Item: VecItem,
 {
 }
@@ -104,7 +292,7 @@ where
     }
 }
 
-impl<State, VectorPath, Item, const SAFE: bool> Path<State, Item, false> for VecLookup<State, VectorPath, Item, SAFE>
+impl<State, VectorPath, Item, const SAFE: bool> PathRead<State, Item, false> for VecLookup<State, VectorPath, Item, SAFE>
 where
     State: 'static,
     VectorPath: Path<State, Vec<Item>, SAFE>,
@@ -114,6 +302,17 @@ where
         self.vector_path.follow(state)?.iter().find(|e| e.get_id() == self.id)
     }
 
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.vector_path.key()?).with(self.id))
+    }
+}
+
+impl<State, VectorPath, Item, const SAFE: bool> PathWrite<State, Item, false> for VecLookup<State, VectorPath, Item, SAFE>
+where
+    State: 'static,
+    VectorPath: Path<State, Vec<Item>, SAFE>,
+    Item: VecItem + 'static,
+{
     fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Item> {
         self.vector_path.follow_mut(state)?.iter_mut().find(|e| e.get_id() == self.id)
     }
@@ -122,26 +321,69 @@ where
 pub trait VecLookupExt<State, T, Item, const SAFE: bool>
 where
     State: 'static,
-    Self: Path<State, Vec<Item>, SAFE>,
+    Self: Path<State, Vec<Item>, SAFE> + Copy,
     Item: VecItem + 'static,
 {
-    fn lookup(self, id: Item::Id) -> impl Path<State, Item, false> {
+    fn lookup(self, id: Item::Id) -> impl Path<State, Item, false> + Copy {
         VecLookup {
             vector_path: self,
             id,
             _marker: PhantomData,
         }
     }
+
+    /// Selector that resolves to `true` if the vector contains an item with
+    /// the given id.
+    fn contains(self, id: Item::Id) -> impl Selector<State, bool, SAFE> {
+        VecContains {
+            vector_path: self,
+            id,
+            _marker: PhantomData,
+        }
+    }
 }
 
 impl<State, T, Item, const SAFE: bool> VecLookupExt<State, T, Item, SAFE> for T
 where
     State: 'static,
-    T: Path<State, Vec<Item>, SAFE>,
+    T: Path<State, Vec<Item>, SAFE> + Copy,
     Item: VecItem + 'static,
 {
 }
 
+/// A selector that checks if a vector contains an item with a given id.
+///
+/// This type is not accessible outside this module, instead
+/// [`VecLookupExt`] can be used to construct it and receive a `impl
+/// Selector<State, bool>`.
+struct VecContains<State, VectorPath, Item, const SAFE: bool>
+where
+    Item: VecItem,
+{
+    vector_path: VectorPath,
+    id: Item::Id,
+    _marker: PhantomData<State>,
+}
+
+impl<State, VectorPath, Item, const SAFE: bool> Selector<State, bool, SAFE> for VecContains<State, VectorPath, Item, SAFE>
+where
+    State: 'static,
+    VectorPath: Path<State, Vec<Item>, SAFE>,
+    Item: VecItem + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a bool> {
+        let contains = self.vector_path.follow(state)?.iter().any(|item| item.get_id() == self.id);
+        Some(if contains { &true } else { &false })
+    }
+}
+
+/// The position of the item a [`VecIndex`] resolves to.
+#[derive(Clone, Copy, Hash)]
+enum Position {
+    Index(usize),
+    Last,
+}
+
 /// A path for doing a dynamic index into a [`Vec`].
 ///
 /// This type is not accessible outside this module, instead
@@ -149,7 +391,7 @@ where
 /// Path<State, Item>`.
 struct VecIndex<State, VectorPath, Item, const SAFE: bool> {
     vector_path: VectorPath,
-    index: usize,
+    position: Position,
     _marker: PhantomData<(State, Item)>,
 }
 
@@ -158,12 +400,16 @@ where
     VectorPath: Path<State, Vec<Item>, SAFE>,
 {
     fn clone(&self) -> Self {
-        *self
+        Self {
+            vector_path: self.vector_path.clone(),
+            position: self.position,
+            _marker: PhantomData,
+        }
     }
 }
 
 impl<State, VectorPath, Item, const SAFE: bool> Copy for VecIndex<State, VectorPath, Item, SAFE> where
-    VectorPath: Path<State, Vec<Item>, SAFE>
+    VectorPath: Path<State, Vec<Item>, SAFE> + Copy
 {
 }
 
@@ -178,31 +424,186 @@ where
     }
 }
 
-impl<State, VectorPath, Item, const SAFE: bool> Path<State, Item, false> for VecIndex<State, VectorPath, Item, SAFE>
+impl<State, VectorPath, Item, const SAFE: bool> PathRead<State, Item, false> for VecIndex<State, VectorPath, Item, SAFE>
 where
     State: 'static,
     VectorPath: Path<State, Vec<Item>, SAFE>,
     Item: 'static,
 {
     fn follow<'a>(&self, state: &'a State) -> Option<&'a Item> {
-        self.vector_path.follow(state)?.get(self.index)
+        let vector = self.vector_path.follow(state)?;
+
+        match self.position {
+            Position::Index(index) => vector.get(index),
+            Position::Last => vector.last(),
+        }
     }
 
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.vector_path.key()?).with(self.position))
+    }
+}
+
+impl<State, VectorPath, Item, const SAFE: bool> PathWrite<State, Item, false> for VecIndex<State, VectorPath, Item, SAFE>
+where
+    State: 'static,
+    VectorPath: Path<State, Vec<Item>, SAFE>,
+    Item: 'static,
+{
     fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Item> {
-        self.vector_path.follow_mut(state)?.get_mut(self.index)
+        let vector = self.vector_path.follow_mut(state)?;
+
+        match self.position {
+            Position::Index(index) => vector.get_mut(index),
+            Position::Last => vector.last_mut(),
+        }
+    }
+}
+
+/// A path to the first item of a vector that matches a predicate.
+///
+/// This type is not accessible outside this module, instead
+/// [`VecIndexExt`] can be used to construct it and receive a `impl
+/// Path<State, Item>`.
+struct VecFindBy<State, VectorPath, Item, F, const SAFE: bool> {
+    vector_path: VectorPath,
+    predicate: F,
+    _marker: PhantomData<(State, Item)>,
+}
+
+impl<State, VectorPath, Item, F, const SAFE: bool> Clone for VecFindBy<State, VectorPath, Item, F, SAFE>
+where
+    VectorPath: Path<State, Vec<Item>, SAFE>,
+    F: Copy,
+{
+    fn clone(&self) -> Self {
+        Self {
+            vector_path: self.vector_path.clone(),
+            predicate: self.predicate,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, VectorPath, Item, F, const SAFE: bool> Copy for VecFindBy<State, VectorPath, Item, F, SAFE>
+where
+    VectorPath: Path<State, Vec<Item>, SAFE> + Copy,
+    F: Copy,
+{
+}
+
+impl<State, VectorPath, Item, F, const SAFE: bool> Selector<State, Item, false> for VecFindBy<State, VectorPath, Item, F, SAFE>
+where
+    State: 'static,
+    VectorPath: Path<State, Vec<Item>, SAFE>,
+    Item: 'static,
+    F: Fn(&Item) -> bool + Copy + 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a Item> {
+        self.follow(state)
+    }
+}
+
+impl<State, VectorPath, Item, F, const SAFE: bool> PathRead<State, Item, false> for VecFindBy<State, VectorPath, Item, F, SAFE>
+where
+    State: 'static,
+    VectorPath: Path<State, Vec<Item>, SAFE>,
+    Item: 'static,
+    F: Fn(&Item) -> bool + Copy + 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a Item> {
+        self.vector_path.follow(state)?.iter().find(|item| (self.predicate)(item))
+    }
+
+    /// Only predicates that don't capture anything can be identified, see
+    /// [`PathKey::with_fn`].
+    fn key(&self) -> Option<PathKey> {
+        PathKey::new::<Self>().with(self.vector_path.key()?).with_fn::<F>()
+    }
+}
+
+impl<State, VectorPath, Item, F, const SAFE: bool> PathWrite<State, Item, false> for VecFindBy<State, VectorPath, Item, F, SAFE>
+where
+    State: 'static,
+    VectorPath: Path<State, Vec<Item>, SAFE>,
+    Item: 'static,
+    F: Fn(&Item) -> bool + Copy + 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut Item> {
+        self.vector_path.follow_mut(state)?.iter_mut().find(|item| (self.predicate)(item))
     }
 }
 
 pub trait VecIndexExt<State, T, Item, const SAFE: bool>
 where
     State: 'static,
-    Self: Path<State, Vec<Item>, SAFE>,
+    Self: Path<State, Vec<Item>, SAFE> + Copy,
     Item: 'static,
 {
-    fn index(self, index: usize) -> impl Path<State, Item, false> {
+    fn index(self, index: usize) -> impl Path<State, Item, false> + Copy {
+        VecIndex {
+            vector_path: self,
+            position: Position::Index(index),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Path to the first item of the vector.
+    fn first(self) -> impl Path<State, Item, false> + Copy {
+        VecIndex {
+            vector_path: self,
+            position: Position::Index(0),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Path to the last item of the vector. Unlike [`index`](Self::index),
+    /// this follows the length of the vector as it changes.
+    fn last(self) -> impl Path<State, Item, false> + Copy {
         VecIndex {
             vector_path: self,
-            index,
+            position: Position::Last,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Path to the first item of the vector that matches the predicate.
+    ///
+    /// Since paths are [`Copy`], the predicate has to be [`Copy`] as well,
+    /// which is the case for closures that only capture [`Copy`] values.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState, VecIndexExt};
+    ///
+    /// #[derive(Debug, PartialEq, Eq)]
+    /// struct User {
+    ///     name: &'static str,
+    ///     age: u32,
+    /// }
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct State {
+    ///     users: Vec<User>,
+    /// }
+    ///
+    /// let context = Context::new(State {
+    ///     users: vec![User { name: "alice", age: 30 }, User { name: "bob", age: 40 }],
+    /// });
+    ///
+    /// let name = "bob";
+    /// let user_path = State::path().users().find_by(move |user: &User| user.name == name);
+    ///
+    /// assert_eq!(context.try_get(&user_path).map(|user| user.age), Some(40));
+    /// ```
+    fn find_by<F>(self, predicate: F) -> impl Path<State, Item, false> + Copy
+    where
+        F: Fn(&Item) -> bool + Copy + 'static,
+    {
+        VecFindBy {
+            vector_path: self,
+            predicate,
             _marker: PhantomData,
         }
     }
@@ -211,7 +612,120 @@ where
 impl<State, T, Item, const SAFE: bool> VecIndexExt<State, T, Item, SAFE> for T
 where
     State: 'static,
-    T: Path<State, Vec<Item>, SAFE>,
+    T: Path<State, Vec<Item>, SAFE> + Copy,
+    Item: 'static,
+{
+}
+
+/// A path to a sub-slice of a vector.
+///
+/// This type is not accessible outside this module, instead
+/// [`VecRangeExt`] can be used to construct it and receive a `impl
+/// Path<State, [Item]>`.
+struct VecRange<State, VectorPath, Item, const SAFE: bool> {
+    vector_path: VectorPath,
+    start: usize,
+    end: usize,
+    _marker: PhantomData<(State, Item)>,
+}
+
+impl<State, VectorPath, Item, const SAFE: bool> Clone for VecRange<State, VectorPath, Item, SAFE>
+where
+    VectorPath: Path<State, Vec<Item>, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            vector_path: self.vector_path.clone(),
+            start: self.start,
+            end: self.end,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, VectorPath, Item, const SAFE: bool> Copy for VecRange<State, VectorPath, Item, SAFE> where
+    VectorPath: Path<State, Vec<Item>, SAFE> + Copy
+{
+}
+
+impl<State, VectorPath, Item, const SAFE: bool> Selector<State, [Item], false> for VecRange<State, VectorPath, Item, SAFE>
+where
+    State: 'static,
+    VectorPath: Path<State, Vec<Item>, SAFE>,
+    Item: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a [Item]> {
+        self.follow(state)
+    }
+}
+
+impl<State, VectorPath, Item, const SAFE: bool> PathRead<State, [Item], false> for VecRange<State, VectorPath, Item, SAFE>
+where
+    State: 'static,
+    VectorPath: Path<State, Vec<Item>, SAFE>,
+    Item: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a [Item]> {
+        self.vector_path.follow(state)?.get(self.start..self.end)
+    }
+
+    fn key(&self) -> Option<PathKey> {
+        Some(PathKey::new::<Self>().with(self.vector_path.key()?).with(self.start).with(self.end))
+    }
+}
+
+impl<State, VectorPath, Item, const SAFE: bool> PathWrite<State, [Item], false> for VecRange<State, VectorPath, Item, SAFE>
+where
+    State: 'static,
+    VectorPath: Path<State, Vec<Item>, SAFE>,
+    Item: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut [Item]> {
+        self.vector_path.follow_mut(state)?.get_mut(self.start..self.end)
+    }
+}
+
+pub trait VecRangeExt<State, T, Item, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, Vec<Item>, SAFE> + Copy,
+    Item: 'static,
+{
+    /// Path to the items in the given range. The path does not resolve if the
+    /// range is out of bounds.
+    ///
+    /// Example:
+    /// ```
+    /// use rust_state::{Context, RustState, VecRangeExt};
+    ///
+    /// #[derive(RustState)]
+    /// #[state_root]
+    /// struct State {
+    ///     items: Vec<u32>,
+    /// }
+    ///
+    /// let context = Context::new(State { items: vec![1, 2, 3, 4] });
+    ///
+    /// let visible_path = State::path().items().range(1..3);
+    /// let invalid_path = State::path().items().range(3..5);
+    ///
+    /// assert_eq!(context.try_get(&visible_path), Some(&[2, 3][..]));
+    /// assert_eq!(context.try_get(&invalid_path), None);
+    /// ```
+    fn range(self, range: Range<usize>) -> impl Path<State, [Item], false> + Copy {
+        VecRange {
+            vector_path: self,
+            start: range.start,
+            end: range.end,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, Item, const SAFE: bool> VecRangeExt<State, T, Item, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, Vec<Item>, SAFE> + Copy,
     Item: 'static,
 {
 }
@@ -0,0 +1,132 @@
+//! Module providing an extension trait to transform values written to a
+//! path.
+//!
+//! The adapter is applied whenever a whole value is written, for example with
+//! [`Context::update_value`](crate::Context::update_value). This allows
+//! encoding invariants like clamping or rounding in the path itself, rather
+//! than trusting every caller. Changes that mutate the target in place, like
+//! [`Context::update_value_with`](crate::Context::update_value_with), are not
+//! adapted.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, RustState, WriteAdapterExt};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     volume: f32,
+//! }
+//!
+//! let mut context = Context::new(State { volume: 0.5 });
+//!
+//! let volume_path = State::path().volume().with_write_adapter(|volume| volume.clamp(0.0, 1.0));
+//!
+//! context.update_value(volume_path, 1.5);
+//! context.apply();
+//!
+//! assert_eq!(context.get(&volume_path), &1.0);
+//!
+//! context.update_value(volume_path, -0.5);
+//! context.apply();
+//!
+//! assert_eq!(context.get(&volume_path), &0.0);
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::{Path, PathKey, PathRead, PathWrite, Selector};
+
+/// A path that transforms every value written to it.
+///
+/// This type is not accessible outside this module, instead
+/// [`WriteAdapterExt`] can be used to construct it and receive an `impl
+/// Path<State, To>`.
+struct WriteAdapter<State, InnerPath, To, const SAFE: bool> {
+    path: InnerPath,
+    adapter: fn(To) -> To,
+    _marker: PhantomData<State>,
+}
+
+impl<State, InnerPath, To, const SAFE: bool> Clone for WriteAdapter<State, InnerPath, To, SAFE>
+where
+    InnerPath: Path<State, To, SAFE>,
+{
+    fn clone(&self) -> Self {
+        Self {
+            path: self.path.clone(),
+            adapter: self.adapter,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, InnerPath, To, const SAFE: bool> Copy for WriteAdapter<State, InnerPath, To, SAFE> where InnerPath: Path<State, To, SAFE> + Copy {}
+
+impl<State, InnerPath, To, const SAFE: bool> Selector<State, To, SAFE> for WriteAdapter<State, InnerPath, To, SAFE>
+where
+    State: 'static,
+    InnerPath: Path<State, To, SAFE>,
+    To: 'static,
+{
+    fn select<'a>(&'a self, state: &'a State) -> Option<&'a To> {
+        self.path.select(state)
+    }
+}
+
+impl<State, InnerPath, To, const SAFE: bool> PathRead<State, To, SAFE> for WriteAdapter<State, InnerPath, To, SAFE>
+where
+    State: 'static,
+    InnerPath: Path<State, To, SAFE>,
+    To: 'static,
+{
+    fn follow<'a>(&self, state: &'a State) -> Option<&'a To> {
+        self.path.follow(state)
+    }
+
+    /// The adapter is a function pointer, which can't be identified, so the
+    /// path has no key.
+    fn key(&self) -> Option<PathKey> {
+        None
+    }
+}
+
+impl<State, InnerPath, To, const SAFE: bool> PathWrite<State, To, SAFE> for WriteAdapter<State, InnerPath, To, SAFE>
+where
+    State: 'static,
+    InnerPath: Path<State, To, SAFE>,
+    To: 'static,
+{
+    fn follow_mut<'a>(&self, state: &'a mut State) -> Option<&'a mut To> {
+        self.path.follow_mut(state)
+    }
+
+    fn write(&self, state: &mut State, value: To) -> bool {
+        self.path.write(state, (self.adapter)(value))
+    }
+}
+
+pub trait WriteAdapterExt<State, To, const SAFE: bool>
+where
+    State: 'static,
+    Self: Path<State, To, SAFE> + Copy,
+    To: 'static,
+{
+    /// Transform every value written to this path, for example to clamp or
+    /// normalize it.
+    fn with_write_adapter(self, adapter: fn(To) -> To) -> impl Path<State, To, SAFE> + Copy {
+        WriteAdapter {
+            path: self,
+            adapter,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<State, T, To, const SAFE: bool> WriteAdapterExt<State, To, SAFE> for T
+where
+    State: 'static,
+    T: Path<State, To, SAFE> + Copy,
+    To: 'static,
+{
+}
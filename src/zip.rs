@@ -0,0 +1,89 @@
+//! Module providing [`Zip`], a selector combining multiple selectors.
+//!
+//! A tuple of up to four selectors can be zipped with [`ZipExt::zip`] and read
+//! in a single call with [`Context::get_zip`](crate::Context::get_zip) or
+//! [`Context::try_get_zip`](crate::Context::try_get_zip). All selectors have
+//! to be either safe or unsafe.
+//!
+//! Example:
+//! ```
+//! use rust_state::{Context, RustState, ZipExt};
+//!
+//! #[derive(RustState)]
+//! #[state_root]
+//! struct State {
+//!     name: String,
+//!     age: u32,
+//! }
+//!
+//! let context = Context::new(State {
+//!     name: "Alice".to_owned(),
+//!     age: 30,
+//! });
+//!
+//! let zipped = (State::path().name(), State::path().age()).zip();
+//! let (name, age) = context.get_zip(&zipped);
+//!
+//! assert_eq!(name, "Alice");
+//! assert_eq!(age, &30);
+//! ```
+
+use std::marker::PhantomData;
+
+use crate::Selector;
+
+/// Selectors that resolve to multiple references at once.
+pub trait SelectAll<State, const SAFE: bool = true>: 'static {
+    /// The references the selectors resolve to.
+    type Output<'a>
+    where
+        Self: 'a,
+        State: 'a;
+
+    fn select_all<'a>(&'a self, state: &'a State) -> Option<Self::Output<'a>>;
+}
+
+/// Multiple selectors zipped together.
+///
+/// [`ZipExt::zip`] is used to construct it.
+pub struct Zip<Selectors, Targets: ?Sized> {
+    selectors: Selectors,
+    _marker: PhantomData<fn() -> Targets>,
+}
+
+pub trait ZipExt: Sized {
+    /// Zip the selectors in the tuple into a single selector.
+    fn zip<Targets: ?Sized>(self) -> Zip<Self, Targets> {
+        Zip {
+            selectors: self,
+            _marker: PhantomData,
+        }
+    }
+}
+
+macro_rules! implement_zip {
+    ($($selector:ident: $target:ident => $index:tt),*) => {
+        impl<$($selector),*> ZipExt for ($($selector,)*) {}
+
+        impl<State, $($selector, $target,)* const SAFE: bool> SelectAll<State, SAFE> for Zip<($($selector,)*), ($($target,)*)>
+        where
+            $(
+                $selector: Selector<State, $target, SAFE>,
+                $target: 'static,
+            )*
+        {
+            type Output<'a> = ($(&'a $target,)*)
+            where
+                Self: 'a,
+                State: 'a;
+
+            fn select_all<'a>(&'a self, state: &'a State) -> Option<Self::Output<'a>> {
+                Some(($(self.selectors.$index.select(state)?,)*))
+            }
+        }
+    };
+}
+
+implement_zip!(A: AT => 0, B: BT => 1);
+implement_zip!(A: AT => 0, B: BT => 1, C: CT => 2);
+implement_zip!(A: AT => 0, B: BT => 1, C: CT => 2, D: DT => 3);